@@ -0,0 +1,9 @@
+// dispatch/src/lib.rs
+// Dispatch decisions (no DB / Redis access): callers plug in where drivers
+// are (`search::DriverIndex`) and who is free (`search::DriverPool`).
+pub mod geo;
+pub mod matching;
+pub mod pool;
+pub mod search;
+pub mod settings;
+pub mod strategy;
@@ -0,0 +1,216 @@
+// dispatch/src/matching.rs
+use uuid::Uuid;
+
+use crate::strategy::Candidate;
+
+/// Stands in for "this driver can't take this ride"; larger than any real
+/// batch total so the solver only uses it when nothing else fits.
+const FORBIDDEN_COST: f64 = 1e9;
+
+/// Optimal one-to-one assignment of rows (rides) to columns (drivers).
+///
+/// `cost[i][j]` is the cost of giving ride `i` to driver `j`, `None` if that
+/// pair is not allowed. Returns, per ride, the chosen driver column (`None`
+/// if the ride stays unmatched). Maximises the number of matched rides
+/// first, then minimises their total cost (Hungarian method, O(n³)).
+pub fn min_cost_assignment(cost: &[Vec<Option<f64>>]) -> Vec<Option<usize>> {
+    let rows = cost.len();
+    let cols = cost.iter().map(|r| r.len()).max().unwrap_or(0);
+    if rows == 0 || cols == 0 {
+        return vec![None; rows];
+    }
+
+    // square it up; padding cells are free "unmatched" slots
+    let n = rows.max(cols);
+    let at = |i: usize, j: usize| -> f64 {
+        if i >= rows || j >= cols {
+            return 0.0;
+        }
+        cost[i].get(j).copied().flatten().unwrap_or(FORBIDDEN_COST)
+    };
+
+    // potentials u (rows) / v (cols), 1-based with 0 as the virtual start
+    let mut u = vec![0.0_f64; n + 1];
+    let mut v = vec![0.0_f64; n + 1];
+    // p[j] = row matched to column j
+    let mut p = vec![0_usize; n + 1];
+    let mut way = vec![0_usize; n + 1];
+
+    for i in 1..=n {
+        p[0] = i;
+        let mut j0 = 0;
+        let mut minv = vec![f64::INFINITY; n + 1];
+        let mut used = vec![false; n + 1];
+
+        loop {
+            used[j0] = true;
+            let i0 = p[j0];
+            let mut delta = f64::INFINITY;
+            let mut j1 = 0;
+
+            for j in 1..=n {
+                if used[j] {
+                    continue;
+                }
+                let cur = at(i0 - 1, j - 1) - u[i0] - v[j];
+                if cur < minv[j] {
+                    minv[j] = cur;
+                    way[j] = j0;
+                }
+                if minv[j] < delta {
+                    delta = minv[j];
+                    j1 = j;
+                }
+            }
+
+            for j in 0..=n {
+                if used[j] {
+                    u[p[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    minv[j] -= delta;
+                }
+            }
+
+            j0 = j1;
+            if p[j0] == 0 {
+                break;
+            }
+        }
+
+        // walk the augmenting path back
+        loop {
+            let j1 = way[j0];
+            p[j0] = p[j1];
+            j0 = j1;
+            if j0 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut assignment = vec![None; rows];
+    for (j, &i) in p.iter().enumerate().skip(1) {
+        if i == 0 || i > rows || j > cols {
+            continue;
+        }
+        // padding / forbidden pairs mean "unmatched"
+        if cost[i - 1].get(j - 1).copied().flatten().is_some() {
+            assignment[i - 1] = Some(j - 1);
+        }
+    }
+
+    assignment
+}
+
+/// Result of matching one batch of rides against their candidate drivers.
+#[derive(Debug, Clone)]
+pub struct BatchAssignment {
+    /// Matrix columns: every driver that is a candidate for at least one ride
+    pub drivers: Vec<Uuid>,
+    /// `cost[ride][driver]` = pickup distance (km), None if not a candidate
+    pub cost: Vec<Vec<Option<f64>>>,
+    /// Per ride (same order as the input), the chosen driver column
+    pub picks: Vec<Option<usize>>,
+}
+
+impl BatchAssignment {
+    /// Driver picked for ride `i`, with its pickup distance
+    pub fn pick(&self, i: usize) -> Option<(Uuid, f64)> {
+        let j = self.picks.get(i).copied().flatten()?;
+        Some((self.drivers[j], self.cost[i][j]?))
+    }
+
+    pub fn matched(&self) -> usize {
+        self.picks.iter().filter(|p| p.is_some()).count()
+    }
+
+    pub fn total_cost_km(&self) -> f64 {
+        (0..self.picks.len())
+            .filter_map(|i| self.pick(i).map(|(_, km)| km))
+            .sum()
+    }
+}
+
+/// Build the rides x drivers pickup-distance matrix from each ride's
+/// candidates and solve it in one pass.
+pub fn assign_batch(ride_candidates: &[Vec<Candidate>]) -> BatchAssignment {
+    let mut drivers: Vec<Uuid> = Vec::new();
+    for candidates in ride_candidates {
+        for c in candidates {
+            if !drivers.contains(&c.driver_user_id) {
+                drivers.push(c.driver_user_id);
+            }
+        }
+    }
+
+    let cost: Vec<Vec<Option<f64>>> = ride_candidates
+        .iter()
+        .map(|candidates| {
+            drivers
+                .iter()
+                .map(|d| {
+                    candidates
+                        .iter()
+                        .find(|c| c.driver_user_id == *d)
+                        .map(|c| c.distance_km)
+                })
+                .collect()
+        })
+        .collect();
+
+    let picks = min_cost_assignment(&cost);
+
+    BatchAssignment { drivers, cost, picks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn more_rides_than_drivers_matches_the_cheapest_pairs() {
+        let cost = vec![
+            vec![Some(1.0), Some(2.0)],
+            vec![Some(2.0), Some(1.0)],
+            vec![Some(0.5), Some(0.6)],
+        ];
+
+        assert_eq!(min_cost_assignment(&cost), vec![None, Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn more_drivers_than_rides_picks_the_cheapest_column() {
+        let cost = vec![vec![Some(3.0), Some(1.0), Some(2.0)]];
+
+        assert_eq!(min_cost_assignment(&cost), vec![Some(1)]);
+    }
+
+    #[test]
+    fn ragged_rows_treat_missing_cells_as_forbidden() {
+        let cost = vec![vec![Some(1.0)], vec![Some(5.0), Some(2.0)]];
+
+        assert_eq!(min_cost_assignment(&cost), vec![Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn forbidden_pairs_are_never_picked() {
+        let cost = vec![vec![None, None], vec![Some(1.0), None]];
+
+        assert_eq!(min_cost_assignment(&cost), vec![None, Some(0)]);
+    }
+
+    #[test]
+    fn matching_more_rides_beats_a_cheaper_total() {
+        // greedy would give ride 0 driver 0 and strand ride 1
+        let cost = vec![vec![Some(1.0), Some(10.0)], vec![Some(2.0), None]];
+
+        assert_eq!(min_cost_assignment(&cost), vec![Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn no_rides_or_no_drivers() {
+        assert!(min_cost_assignment(&[]).is_empty());
+        assert_eq!(min_cost_assignment(&[vec![], vec![]]), vec![None, None]);
+    }
+}
@@ -0,0 +1,395 @@
+// dispatch/src/pool.rs
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::geo::haversine_km;
+
+/// Short trips always get this much room to detour, whatever the ratio says
+const DETOUR_SLACK_KM: f64 = 0.5;
+
+/// Shared rides: several riders with overlapping routes in one car.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolSettings {
+    /// Rides requested in this tier are pooled
+    pub tier: String,
+    /// Riders in the car at once
+    pub max_riders: usize,
+    /// A rider's planned in-car distance may be at most this times the
+    /// straight line from their pickup to their drop-off
+    pub max_detour_ratio: f64,
+    /// Only trips whose driver is this close to the new pickup are tried (km)
+    pub max_pickup_km: f64,
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        Self {
+            tier: "pool".to_string(),
+            max_riders: 3,
+            max_detour_ratio: 1.5,
+            max_pickup_km: 3.0,
+        }
+    }
+}
+
+impl PoolSettings {
+    pub fn is_pool_tier(&self, tier: &str) -> bool {
+        self.tier == tier
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaypointKind {
+    Pickup,
+    Dropoff,
+}
+
+/// One pickup or drop-off on a pooled trip's route.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Waypoint {
+    pub ride_id: Uuid,
+    pub kind: WaypointKind,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Where a new ride goes in a trip's remaining route.
+#[derive(Clone, Debug, Serialize)]
+pub struct Insertion {
+    /// Remaining route with the new pickup / drop-off slotted in
+    pub route: Vec<Waypoint>,
+    /// Extra driving the new rider adds to the trip (km)
+    pub added_km: f64,
+}
+
+/// Straight-line length of `route`, starting at `origin`
+fn route_km(origin: (f64, f64), route: &[Waypoint]) -> f64 {
+    let mut km = 0.0;
+    let mut from = origin;
+    for w in route {
+        km += haversine_km(from.0, from.1, w.lat, w.lon);
+        from = (w.lat, w.lon);
+    }
+    km
+}
+
+/// Seats and detours hold for everyone on `route`
+fn route_fits(origin: (f64, f64), route: &[Waypoint], settings: &PoolSettings) -> bool {
+    // riders whose drop-off is pending but pickup is not are already in the car
+    let mut in_car = route
+        .iter()
+        .filter(|w| {
+            w.kind == WaypointKind::Dropoff
+                && !route
+                    .iter()
+                    .any(|p| p.ride_id == w.ride_id && p.kind == WaypointKind::Pickup)
+        })
+        .count();
+    if in_car > settings.max_riders {
+        return false;
+    }
+
+    // distance driven from `origin` up to each waypoint
+    let mut cum = Vec::with_capacity(route.len());
+    let mut km = 0.0;
+    let mut from = origin;
+    for w in route {
+        km += haversine_km(from.0, from.1, w.lat, w.lon);
+        cum.push(km);
+        from = (w.lat, w.lon);
+
+        match w.kind {
+            WaypointKind::Pickup => {
+                in_car += 1;
+                if in_car > settings.max_riders {
+                    return false;
+                }
+            }
+            WaypointKind::Dropoff => in_car = in_car.saturating_sub(1),
+        }
+    }
+
+    route.iter().enumerate().all(|(end, drop)| {
+        if drop.kind != WaypointKind::Dropoff {
+            return true;
+        }
+        let pickup = route
+            .iter()
+            .position(|p| p.ride_id == drop.ride_id && p.kind == WaypointKind::Pickup);
+        let (start_km, start) = match pickup {
+            Some(i) => (cum[i], (route[i].lat, route[i].lon)),
+            None => (0.0, origin),
+        };
+
+        let planned = cum[end] - start_km;
+        let direct = haversine_km(start.0, start.1, drop.lat, drop.lon);
+        planned <= direct * settings.max_detour_ratio || planned - direct <= DETOUR_SLACK_KM
+    })
+}
+
+/// Cheapest way to add `pickup` -> `dropoff` to a trip's remaining route.
+///
+/// `pending` are the waypoints not reached yet, in planned order, starting
+/// from the driver at `origin`; a ride with only its drop-off pending is in
+/// the car. Every rider's planned in-car distance (from their pickup, or
+/// from `origin` if already on board) must stay within `max_detour_ratio`
+/// of the straight line, and the car never holds more than `max_riders`.
+/// Returns None if the ride fits nowhere.
+pub fn plan_insertion(
+    origin: (f64, f64),
+    pending: &[Waypoint],
+    pickup: Waypoint,
+    dropoff: Waypoint,
+    settings: &PoolSettings,
+) -> Option<Insertion> {
+    let base_km = route_km(origin, pending);
+    let mut best: Option<Insertion> = None;
+
+    for i in 0..=pending.len() {
+        for j in i..=pending.len() {
+            let mut route = Vec::with_capacity(pending.len() + 2);
+            route.extend_from_slice(&pending[..i]);
+            route.push(pickup.clone());
+            route.extend_from_slice(&pending[i..j]);
+            route.push(dropoff.clone());
+            route.extend_from_slice(&pending[j..]);
+
+            let added_km = route_km(origin, &route) - base_km;
+            if best.as_ref().is_some_and(|b| b.added_km <= added_km) {
+                continue;
+            }
+            if route_fits(origin, &route, settings) {
+                best = Some(Insertion { route, added_km });
+            }
+        }
+    }
+
+    best
+}
+
+/// A pooled rider's part of the trip.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PoolShare {
+    /// Distance this rider was in the car
+    pub in_vehicle_km: f64,
+    /// Each leg divided by the riders in the car for it
+    pub shared_km: f64,
+    /// Most riders in the car at once while this one was
+    pub max_riders: usize,
+}
+
+impl PoolShare {
+    /// Share of their own in-car distance the rider pays for (0..1]
+    pub fn ratio(&self) -> f64 {
+        if self.in_vehicle_km > 0.0 {
+            self.shared_km / self.in_vehicle_km
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Split a trip between its riders: every leg between consecutive
+/// waypoints is shared equally by the riders in the car for it.
+///
+/// `route` is the trip in driven order, holding only riders who actually
+/// rode. None if `ride_id` never got in.
+pub fn fare_share(route: &[Waypoint], ride_id: Uuid) -> Option<PoolShare> {
+    route
+        .iter()
+        .position(|w| w.ride_id == ride_id && w.kind == WaypointKind::Pickup)?;
+
+    let mut in_car: Vec<Uuid> = Vec::new();
+    let mut share = PoolShare {
+        in_vehicle_km: 0.0,
+        shared_km: 0.0,
+        max_riders: 0,
+    };
+
+    for (i, w) in route.iter().enumerate() {
+        // the leg that ends at `w`
+        if i > 0 && in_car.contains(&ride_id) {
+            let prev = &route[i - 1];
+            let km = haversine_km(prev.lat, prev.lon, w.lat, w.lon);
+            share.in_vehicle_km += km;
+            share.shared_km += km / in_car.len() as f64;
+            share.max_riders = share.max_riders.max(in_car.len());
+        }
+
+        match w.kind {
+            WaypointKind::Pickup => in_car.push(w.ride_id),
+            WaypointKind::Dropoff => in_car.retain(|r| *r != w.ride_id),
+        }
+    }
+
+    Some(share)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ~1 km of latitude / longitude at the equator
+    const KM: f64 = 1.0 / 111.195;
+
+    fn at(kind: WaypointKind, ride_id: Uuid, east_km: f64, north_km: f64) -> Waypoint {
+        Waypoint {
+            ride_id,
+            kind,
+            lat: north_km * KM,
+            lon: east_km * KM,
+        }
+    }
+
+    fn order(route: &[Waypoint]) -> Vec<(Uuid, WaypointKind)> {
+        route.iter().map(|w| (w.ride_id, w.kind)).collect()
+    }
+
+    #[test]
+    fn ride_on_the_way_slots_in_for_free() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let pending = vec![
+            at(WaypointKind::Pickup, a, 0.0, 1.0),
+            at(WaypointKind::Dropoff, a, 0.0, 5.0),
+        ];
+
+        let insertion = plan_insertion(
+            (0.0, 0.0),
+            &pending,
+            at(WaypointKind::Pickup, b, 0.0, 2.0),
+            at(WaypointKind::Dropoff, b, 0.0, 4.0),
+            &PoolSettings::default(),
+        )
+        .unwrap();
+
+        assert!(insertion.added_km.abs() < 1e-6);
+        assert_eq!(
+            order(&insertion.route),
+            vec![
+                (a, WaypointKind::Pickup),
+                (b, WaypointKind::Pickup),
+                (b, WaypointKind::Dropoff),
+                (a, WaypointKind::Dropoff),
+            ]
+        );
+    }
+
+    #[test]
+    fn detour_limit_of_a_rider_in_the_car_wins_over_a_shorter_route() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        // A is in the car, 2 km from their drop-off; B wants to go east
+        let pending = vec![at(WaypointKind::Dropoff, a, 0.0, 2.0)];
+        let pickup = at(WaypointKind::Pickup, b, 1.0, 0.0);
+        let dropoff = at(WaypointKind::Dropoff, b, 2.0, 0.0);
+
+        let strict = plan_insertion(
+            (0.0, 0.0),
+            &pending,
+            pickup.clone(),
+            dropoff.clone(),
+            &PoolSettings::default(),
+        )
+        .unwrap();
+        // serving B first is shorter, but doubles A's ride: A is dropped first
+        assert_eq!(
+            order(&strict.route),
+            vec![
+                (a, WaypointKind::Dropoff),
+                (b, WaypointKind::Pickup),
+                (b, WaypointKind::Dropoff),
+            ]
+        );
+
+        let lax = PoolSettings {
+            max_detour_ratio: 10.0,
+            ..PoolSettings::default()
+        };
+        let relaxed = plan_insertion((0.0, 0.0), &pending, pickup, dropoff, &lax).unwrap();
+        assert_eq!(relaxed.route[0].ride_id, b);
+        assert!(relaxed.added_km < strict.added_km);
+    }
+
+    #[test]
+    fn seat_limit_holds_the_pickup_until_someone_gets_out() {
+        let (a, c, b) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        // A and C are in the car
+        let pending = vec![
+            at(WaypointKind::Dropoff, a, 0.0, 2.0),
+            at(WaypointKind::Dropoff, c, 0.0, 4.0),
+        ];
+        let pickup = at(WaypointKind::Pickup, b, 0.0, 1.0);
+        let dropoff = at(WaypointKind::Dropoff, b, 0.0, 3.0);
+
+        let two_seats = PoolSettings {
+            max_riders: 2,
+            ..PoolSettings::default()
+        };
+        let insertion = plan_insertion(
+            (0.0, 0.0),
+            &pending,
+            pickup.clone(),
+            dropoff.clone(),
+            &two_seats,
+        )
+        .unwrap();
+        let route = order(&insertion.route);
+        let boards = route
+            .iter()
+            .position(|w| *w == (b, WaypointKind::Pickup))
+            .unwrap();
+        let first_out = route
+            .iter()
+            .position(|w| w.1 == WaypointKind::Dropoff)
+            .unwrap();
+        assert!(first_out < boards);
+
+        let one_seat = PoolSettings {
+            max_riders: 1,
+            ..PoolSettings::default()
+        };
+        assert!(plan_insertion((0.0, 0.0), &pending, pickup, dropoff, &one_seat).is_none());
+    }
+
+    #[test]
+    fn shares_add_up_to_the_whole_trip() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let route = vec![
+            at(WaypointKind::Pickup, a, 0.0, 0.0),
+            at(WaypointKind::Pickup, b, 0.0, 1.0),
+            at(WaypointKind::Pickup, c, 0.0, 2.5),
+            at(WaypointKind::Dropoff, a, 0.0, 3.0),
+            at(WaypointKind::Dropoff, b, 0.0, 5.0),
+            // C rides on alone
+            at(WaypointKind::Dropoff, c, 1.0, 5.0),
+        ];
+
+        let shares: Vec<PoolShare> = [a, b, c]
+            .iter()
+            .map(|id| fare_share(&route, *id).unwrap())
+            .collect();
+        let total_km = route_km((route[0].lat, route[0].lon), &route[1..]);
+        let shared: f64 = shares.iter().map(|s| s.shared_km).sum();
+
+        assert!((shared - total_km).abs() < 1e-6);
+        assert_eq!(shares[0].max_riders, 3);
+        assert!((shares[0].in_vehicle_km - 3.0).abs() < 0.01);
+        assert!(shares.iter().all(|s| s.ratio() > 0.0 && s.ratio() <= 1.0));
+    }
+
+    #[test]
+    fn solo_rider_pays_the_whole_trip() {
+        let a = Uuid::new_v4();
+        let route = vec![
+            at(WaypointKind::Pickup, a, 0.0, 0.0),
+            at(WaypointKind::Dropoff, a, 0.0, 3.0),
+        ];
+
+        let share = fare_share(&route, a).unwrap();
+
+        assert_eq!(share.ratio(), 1.0);
+        assert_eq!(share.max_riders, 1);
+        assert!(fare_share(&route, Uuid::new_v4()).is_none());
+    }
+}
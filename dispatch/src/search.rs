@@ -0,0 +1,78 @@
+// dispatch/src/search.rs
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::settings::DispatchSettings;
+use crate::strategy::{Candidate, DispatchRide};
+
+/// Where drivers are (Redis GEO in the server).
+#[async_trait]
+pub trait DriverIndex: Send + Sync {
+    /// Drivers within `radius_km` of the point, nearest first, as
+    /// `(user_id, distance_km)`
+    async fn nearby(
+        &self,
+        tenant_id: Uuid,
+        lat: f64,
+        lon: f64,
+        radius_km: f64,
+        max_results: usize,
+    ) -> anyhow::Result<Vec<(Uuid, f64)>>;
+}
+
+/// Who can take a ride right now (driver + ride tables in the server).
+#[async_trait]
+pub trait DriverPool: Send + Sync {
+    /// The drivers of `nearby` that are online in the tenant and not busy
+    /// with another ride, as candidates, in the order given.
+    async fn available(
+        &self,
+        tenant_id: Uuid,
+        nearby: &[(Uuid, f64)],
+    ) -> anyhow::Result<Vec<Candidate>>;
+}
+
+/// Search around pickup in expanding rings until at least `wanted` eligible,
+/// non-excluded drivers are found. Returns every such driver seen (nearest
+/// first) and the ring the search stopped at.
+pub async fn find_candidates(
+    index: &dyn DriverIndex,
+    pool: &dyn DriverPool,
+    ride: &DispatchRide,
+    dispatch: &DispatchSettings,
+    excluded: &HashSet<Uuid>,
+    wanted: usize,
+) -> anyhow::Result<(Vec<Candidate>, Option<f64>)> {
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut last_radius_km = None;
+
+    for &radius_km in &dispatch.search_radii_km {
+        last_radius_km = Some(radius_km);
+
+        let nearby: Vec<(Uuid, f64)> = index
+            .nearby(
+                ride.tenant_id,
+                ride.pickup_lat,
+                ride.pickup_lon,
+                radius_km,
+                dispatch.max_candidates_per_ring,
+            )
+            .await?
+            .into_iter()
+            .filter(|(id, _)| !excluded.contains(id))
+            .filter(|(id, _)| !candidates.iter().any(|c| c.driver_user_id == *id))
+            .collect();
+
+        if !nearby.is_empty() {
+            candidates.extend(pool.available(ride.tenant_id, &nearby).await?);
+        }
+
+        if candidates.len() >= wanted {
+            break;
+        }
+    }
+
+    Ok((candidates, last_radius_km))
+}
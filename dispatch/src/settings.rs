@@ -0,0 +1,69 @@
+// dispatch/src/settings.rs
+use serde::{Deserialize, Serialize};
+
+use crate::strategy::{StrategyKind, WeightedStrategy};
+
+/// How a ride is offered to drivers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DispatchMode {
+    /// One driver at a time, nearest first
+    #[default]
+    Sequential,
+    /// Nearest `broadcast_size` drivers at once, first accept wins
+    Broadcast,
+    /// Collect requests for `batch_window_secs`, then match them all at once
+    Batch,
+}
+
+/// How dispatch searches for drivers around the pickup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DispatchSettings {
+    pub mode: DispatchMode,
+    /// K for broadcast mode
+    pub broadcast_size: usize,
+    /// Collection window for batch mode
+    pub batch_window_secs: u64,
+    /// How candidates are ranked
+    pub strategy: StrategyKind,
+    /// Weights for `strategy = "weighted"`
+    pub weighted: WeightedStrategy,
+    /// Expanding search rings, nearest first (km)
+    pub search_radii_km: Vec<f64>,
+    /// Max GEO hits looked at per ring
+    pub max_candidates_per_ring: usize,
+    /// How long a driver has to accept an offer
+    pub offer_ttl_secs: u64,
+    /// Give up (`no_driver_found`) after this many dispatch attempts
+    pub max_dispatch_attempts: i32,
+    /// Wait before searching again when nobody was nearby
+    pub retry_delay_secs: u64,
+}
+
+impl Default for DispatchSettings {
+    fn default() -> Self {
+        Self {
+            mode: DispatchMode::Sequential,
+            broadcast_size: 3,
+            batch_window_secs: 2,
+            strategy: StrategyKind::Nearest,
+            weighted: WeightedStrategy::default(),
+            search_radii_km: vec![1.0, 3.0, 5.0],
+            max_candidates_per_ring: 20,
+            offer_ttl_secs: 15,
+            max_dispatch_attempts: 5,
+            retry_delay_secs: 10,
+        }
+    }
+}
+
+impl DispatchSettings {
+    /// How many drivers one offer round goes to
+    pub fn offers_per_round(&self) -> usize {
+        match self.mode {
+            DispatchMode::Broadcast => self.broadcast_size.max(1),
+            DispatchMode::Sequential | DispatchMode::Batch => 1,
+        }
+    }
+}
@@ -0,0 +1,280 @@
+// dispatch/src/strategy.rs
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The ride being dispatched, as far as dispatch cares.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct DispatchRide {
+    pub ride_id: Uuid,
+    pub tenant_id: Uuid,
+    pub pickup_lat: f64,
+    pub pickup_lon: f64,
+}
+
+/// What a strategy knows about one eligible driver.
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    pub driver_user_id: Uuid,
+    /// Straight-line distance to pickup (km)
+    pub distance_km: f64,
+    /// Seconds since the driver came online / finished their last trip
+    pub idle_secs: i64,
+    /// Accepted / received offers (None: never offered a ride)
+    pub acceptance_rate: Option<f64>,
+    /// Average rating, 1..=5 (None: not rated yet)
+    pub rating: Option<f64>,
+}
+
+/// A candidate plus the score its strategy gave it. Higher is better.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoredCandidate {
+    #[serde(flatten)]
+    pub candidate: Candidate,
+    pub score: f64,
+}
+
+/// Ranks candidate drivers for a ride.
+pub trait DispatchStrategy: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Score a single candidate; only compared within one ranking.
+    fn score(&self, ride: &DispatchRide, candidate: &Candidate) -> f64;
+
+    /// Candidates best first. Ties keep the incoming (nearest-first) order.
+    fn rank(&self, ride: &DispatchRide, candidates: Vec<Candidate>) -> Vec<ScoredCandidate> {
+        let mut scored: Vec<ScoredCandidate> = candidates
+            .into_iter()
+            .map(|candidate| ScoredCandidate {
+                score: self.score(ride, &candidate),
+                candidate,
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+        scored
+    }
+}
+
+/// Closest driver first.
+pub struct NearestStrategy;
+
+impl DispatchStrategy for NearestStrategy {
+    fn name(&self) -> &'static str {
+        "nearest"
+    }
+
+    fn score(&self, _ride: &DispatchRide, candidate: &Candidate) -> f64 {
+        -candidate.distance_km
+    }
+}
+
+/// Driver who has been waiting longest first (spreads work around).
+pub struct LongestIdleStrategy;
+
+impl DispatchStrategy for LongestIdleStrategy {
+    fn name(&self) -> &'static str {
+        "longest_idle"
+    }
+
+    fn score(&self, _ride: &DispatchRide, candidate: &Candidate) -> f64 {
+        candidate.idle_secs as f64
+    }
+}
+
+/// Weighted mix of pickup ETA, acceptance rate and rating.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeightedStrategy {
+    pub eta_weight: f64,
+    pub acceptance_weight: f64,
+    pub rating_weight: f64,
+    /// Used to turn distance into an ETA
+    pub avg_speed_kmh: f64,
+    /// ETAs at or above this score 0 on the ETA part
+    pub max_eta_mins: f64,
+}
+
+impl Default for WeightedStrategy {
+    fn default() -> Self {
+        Self {
+            eta_weight: 0.6,
+            acceptance_weight: 0.25,
+            rating_weight: 0.15,
+            avg_speed_kmh: 25.0,
+            max_eta_mins: 20.0,
+        }
+    }
+}
+
+/// Assumed for drivers without history so new drivers still get rides
+const NEUTRAL_ACCEPTANCE_RATE: f64 = 0.8;
+const NEUTRAL_RATING: f64 = 4.5;
+
+impl WeightedStrategy {
+    pub fn eta_mins(&self, distance_km: f64) -> f64 {
+        distance_km / self.avg_speed_kmh.max(1.0) * 60.0
+    }
+}
+
+impl DispatchStrategy for WeightedStrategy {
+    fn name(&self) -> &'static str {
+        "weighted"
+    }
+
+    fn score(&self, _ride: &DispatchRide, candidate: &Candidate) -> f64 {
+        // every part normalised to 0..=1
+        let eta = self.eta_mins(candidate.distance_km);
+        let eta_part = 1.0 - (eta / self.max_eta_mins.max(1.0)).min(1.0);
+        let acceptance_part = candidate
+            .acceptance_rate
+            .unwrap_or(NEUTRAL_ACCEPTANCE_RATE)
+            .clamp(0.0, 1.0);
+        let rating_part = ((candidate.rating.unwrap_or(NEUTRAL_RATING) - 1.0) / 4.0).clamp(0.0, 1.0);
+
+        self.eta_weight * eta_part
+            + self.acceptance_weight * acceptance_part
+            + self.rating_weight * rating_part
+    }
+}
+
+/// Which built-in strategy a tenant uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    #[default]
+    Nearest,
+    LongestIdle,
+    Weighted,
+}
+
+impl StrategyKind {
+    pub fn build(self, weighted: &WeightedStrategy) -> Box<dyn DispatchStrategy> {
+        match self {
+            StrategyKind::Nearest => Box::new(NearestStrategy),
+            StrategyKind::LongestIdle => Box::new(LongestIdleStrategy),
+            StrategyKind::Weighted => Box::new(weighted.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ride() -> DispatchRide {
+        DispatchRide {
+            ride_id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            pickup_lat: 12.0,
+            pickup_lon: 77.0,
+        }
+    }
+
+    fn candidate(distance_km: f64, idle_secs: i64) -> Candidate {
+        Candidate {
+            driver_user_id: Uuid::new_v4(),
+            distance_km,
+            idle_secs,
+            acceptance_rate: None,
+            rating: None,
+        }
+    }
+
+    fn order(ranked: &[ScoredCandidate], candidates: &[Candidate]) -> Vec<usize> {
+        ranked
+            .iter()
+            .map(|s| {
+                candidates
+                    .iter()
+                    .position(|c| c.driver_user_id == s.candidate.driver_user_id)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nearest_ranks_by_distance() {
+        let candidates = vec![candidate(2.0, 0), candidate(0.5, 0), candidate(1.0, 0)];
+
+        let ranked = NearestStrategy.rank(&ride(), candidates.clone());
+
+        assert_eq!(order(&ranked, &candidates), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn longest_idle_ranks_by_wait() {
+        let candidates = vec![candidate(0.5, 60), candidate(2.0, 900), candidate(1.0, 300)];
+
+        let ranked = LongestIdleStrategy.rank(&ride(), candidates.clone());
+
+        assert_eq!(order(&ranked, &candidates), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn ties_keep_the_incoming_order() {
+        let candidates = vec![candidate(1.0, 300), candidate(1.0, 300), candidate(1.0, 300)];
+
+        for strategy in [
+            StrategyKind::Nearest,
+            StrategyKind::LongestIdle,
+            StrategyKind::Weighted,
+        ] {
+            let ranked = strategy
+                .build(&WeightedStrategy::default())
+                .rank(&ride(), candidates.clone());
+            assert_eq!(order(&ranked, &candidates), vec![0, 1, 2], "{strategy:?}");
+        }
+    }
+
+    #[test]
+    fn weighted_trades_distance_for_a_better_record() {
+        let weighted = WeightedStrategy::default();
+        // ~2.4 vs 4.8 min away
+        let near_unreliable = Candidate {
+            acceptance_rate: Some(0.1),
+            rating: Some(3.0),
+            ..candidate(1.0, 0)
+        };
+        let farther_reliable = Candidate {
+            acceptance_rate: Some(1.0),
+            rating: Some(5.0),
+            ..candidate(2.0, 0)
+        };
+        let candidates = vec![near_unreliable, farther_reliable];
+
+        let ranked = weighted.rank(&ride(), candidates.clone());
+
+        assert_eq!(order(&ranked, &candidates), vec![1, 0]);
+    }
+
+    #[test]
+    fn weighted_scores_stay_in_range() {
+        let weighted = WeightedStrategy::default();
+        let best = Candidate {
+            acceptance_rate: Some(1.0),
+            rating: Some(5.0),
+            ..candidate(0.0, 0)
+        };
+        let worst = Candidate {
+            acceptance_rate: Some(0.0),
+            rating: Some(1.0),
+            ..candidate(100.0, 0)
+        };
+
+        assert!((weighted.score(&ride(), &best) - 1.0).abs() < 1e-9);
+        assert_eq!(weighted.score(&ride(), &worst), 0.0);
+    }
+
+    #[test]
+    fn new_drivers_get_neutral_history() {
+        let weighted = WeightedStrategy::default();
+        let new = candidate(1.0, 0);
+        let average = Candidate {
+            acceptance_rate: Some(NEUTRAL_ACCEPTANCE_RATE),
+            rating: Some(NEUTRAL_RATING),
+            ..candidate(1.0, 0)
+        };
+
+        assert_eq!(weighted.score(&ride(), &new), weighted.score(&ride(), &average));
+    }
+}
@@ -7,6 +7,30 @@ mod m20251122_133751_create_rides;
 mod m20251122_190000_create_drivers;
 mod m20251123_000001_create_ride_events;
 mod m20251123_000002_create_driver_events;
+mod m20251124_000001_add_ride_status_check;
+mod m20251124_000002_add_ride_version;
+mod m20251125_000001_add_tenant_settings;
+mod m20251125_000002_add_ride_cancellation;
+mod m20251126_000001_add_driver_cancellation_count;
+mod m20251126_000002_add_ride_dispatch_attempts;
+mod m20251127_000001_add_ride_offer_expiry;
+mod m20251128_000001_add_driver_dispatch_stats;
+mod m20251129_000001_add_ride_pricing;
+mod m20251130_000001_create_rate_cards;
+mod m20251130_000002_add_ride_fare;
+mod m20251201_000001_money_minor_units;
+mod m20251202_000001_create_ride_breadcrumbs;
+mod m20251203_000001_add_ride_scheduling;
+mod m20251204_000001_create_ride_stops;
+mod m20251204_000002_add_rate_card_waiting;
+mod m20251205_000001_add_ride_pending_destination;
+mod m20251206_000001_add_ride_arrival;
+mod m20251207_000001_add_ride_pickup_pin;
+mod m20251208_000001_create_pool_trips;
+mod m20251209_000001_create_ride_ratings;
+mod m20251210_000001_add_ride_tip;
+mod m20251211_000001_create_promo_codes;
+mod m20251212_000001_add_ride_original_destination;
 
 pub struct Migrator;
 
@@ -20,6 +44,30 @@ impl MigratorTrait for Migrator {
             Box::new(m20251122_190000_create_drivers::Migration),
             Box::new(m20251123_000001_create_ride_events::Migration),
             Box::new(m20251123_000002_create_driver_events::Migration),
+            Box::new(m20251124_000001_add_ride_status_check::Migration),
+            Box::new(m20251124_000002_add_ride_version::Migration),
+            Box::new(m20251125_000001_add_tenant_settings::Migration),
+            Box::new(m20251125_000002_add_ride_cancellation::Migration),
+            Box::new(m20251126_000001_add_driver_cancellation_count::Migration),
+            Box::new(m20251126_000002_add_ride_dispatch_attempts::Migration),
+            Box::new(m20251127_000001_add_ride_offer_expiry::Migration),
+            Box::new(m20251128_000001_add_driver_dispatch_stats::Migration),
+            Box::new(m20251129_000001_add_ride_pricing::Migration),
+            Box::new(m20251130_000001_create_rate_cards::Migration),
+            Box::new(m20251130_000002_add_ride_fare::Migration),
+            Box::new(m20251201_000001_money_minor_units::Migration),
+            Box::new(m20251202_000001_create_ride_breadcrumbs::Migration),
+            Box::new(m20251203_000001_add_ride_scheduling::Migration),
+            Box::new(m20251204_000001_create_ride_stops::Migration),
+            Box::new(m20251204_000002_add_rate_card_waiting::Migration),
+            Box::new(m20251205_000001_add_ride_pending_destination::Migration),
+            Box::new(m20251206_000001_add_ride_arrival::Migration),
+            Box::new(m20251207_000001_add_ride_pickup_pin::Migration),
+            Box::new(m20251208_000001_create_pool_trips::Migration),
+            Box::new(m20251209_000001_create_ride_ratings::Migration),
+            Box::new(m20251210_000001_add_ride_tip::Migration),
+            Box::new(m20251211_000001_create_promo_codes::Migration),
+            Box::new(m20251212_000001_add_ride_original_destination::Migration),
         ]
     }
 }
@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Keep in sync with RideStatus (src/types/ride_status.rs)
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"ALTER TABLE "ride"
+                   ADD CONSTRAINT "chk_ride_status"
+                   CHECK ("status" IN (
+                       'requested',
+                       'assigned',
+                       'accepted',
+                       'in_progress',
+                       'completed'
+                   ))"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(r#"ALTER TABLE "ride" DROP CONSTRAINT IF EXISTS "chk_ride_status""#)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Per-tenant policies (JSON) + the user allowed to change them
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tenant::Table)
+                    .add_column(
+                        ColumnDef::new(Tenant::Settings)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'{}'::jsonb")),
+                    )
+                    .add_column(
+                        ColumnDef::new(Tenant::OwnerUserId)
+                            .uuid()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tenant::Table)
+                    .drop_column(Tenant::Settings)
+                    .drop_column(Tenant::OwnerUserId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Tenant {
+    Table,
+    Settings,
+    OwnerUserId,
+}
@@ -0,0 +1,97 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1) Cancellation bookkeeping on ride
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .add_column(
+                        ColumnDef::new(Ride::AcceptedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .add_column(
+                        ColumnDef::new(Ride::CancelledAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .add_column(
+                        ColumnDef::new(Ride::CancelReason)
+                            .string()
+                            .null(),
+                    )
+                    .add_column(
+                        ColumnDef::new(Ride::CancellationFee)
+                            .double()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2) Allow the new 'cancelled' status
+        let conn = manager.get_connection();
+        conn.execute_unprepared(r#"ALTER TABLE "ride" DROP CONSTRAINT IF EXISTS "chk_ride_status""#)
+            .await?;
+        conn.execute_unprepared(
+            r#"ALTER TABLE "ride"
+               ADD CONSTRAINT "chk_ride_status"
+               CHECK ("status" IN (
+                   'requested',
+                   'assigned',
+                   'accepted',
+                   'in_progress',
+                   'completed',
+                   'cancelled'
+               ))"#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        conn.execute_unprepared(r#"ALTER TABLE "ride" DROP CONSTRAINT IF EXISTS "chk_ride_status""#)
+            .await?;
+        conn.execute_unprepared(
+            r#"ALTER TABLE "ride"
+               ADD CONSTRAINT "chk_ride_status"
+               CHECK ("status" IN (
+                   'requested',
+                   'assigned',
+                   'accepted',
+                   'in_progress',
+                   'completed'
+               ))"#,
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .drop_column(Ride::AcceptedAt)
+                    .drop_column(Ride::CancelledAt)
+                    .drop_column(Ride::CancelReason)
+                    .drop_column(Ride::CancellationFee)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    AcceptedAt,
+    CancelledAt,
+    CancelReason,
+    CancellationFee,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // How many times dispatch has tried to find a driver for this ride
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .add_column(
+                        ColumnDef::new(Ride::DispatchAttempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .drop_column(Ride::DispatchAttempts)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    DispatchAttempts,
+}
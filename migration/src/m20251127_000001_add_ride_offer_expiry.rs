@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1) When the current driver offer lapses
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .add_column(
+                        ColumnDef::new(Ride::OfferExpiresAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2) Allow the terminal 'no_driver_found' status
+        let conn = manager.get_connection();
+        conn.execute_unprepared(r#"ALTER TABLE "ride" DROP CONSTRAINT IF EXISTS "chk_ride_status""#)
+            .await?;
+        conn.execute_unprepared(
+            r#"ALTER TABLE "ride"
+               ADD CONSTRAINT "chk_ride_status"
+               CHECK ("status" IN (
+                   'requested',
+                   'assigned',
+                   'accepted',
+                   'in_progress',
+                   'completed',
+                   'cancelled',
+                   'no_driver_found'
+               ))"#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        conn.execute_unprepared(r#"ALTER TABLE "ride" DROP CONSTRAINT IF EXISTS "chk_ride_status""#)
+            .await?;
+        conn.execute_unprepared(
+            r#"ALTER TABLE "ride"
+               ADD CONSTRAINT "chk_ride_status"
+               CHECK ("status" IN (
+                   'requested',
+                   'assigned',
+                   'accepted',
+                   'in_progress',
+                   'completed',
+                   'cancelled'
+               ))"#,
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .drop_column(Ride::OfferExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    OfferExpiresAt,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Inputs for dispatch strategies: idle time and acceptance rate
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Driver::Table)
+                    .add_column(
+                        ColumnDef::new(Driver::IdleSince)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .add_column(
+                        ColumnDef::new(Driver::OffersReceived)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .add_column(
+                        ColumnDef::new(Driver::OffersAccepted)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Driver::Table)
+                    .drop_column(Driver::IdleSince)
+                    .drop_column(Driver::OffersReceived)
+                    .drop_column(Driver::OffersAccepted)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Driver {
+    Table,
+    IdleSince,
+    OffersReceived,
+    OffersAccepted,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Surge the ride was priced at (locked by a quote) + the quote itself
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .add_column(
+                        ColumnDef::new(Ride::SurgeMultiplier)
+                            .double()
+                            .not_null()
+                            .default(1.0),
+                    )
+                    .add_column(ColumnDef::new(Ride::Quote).json_binary().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .drop_column(Ride::SurgeMultiplier)
+                    .drop_column(Ride::Quote)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    SurgeMultiplier,
+    Quote,
+}
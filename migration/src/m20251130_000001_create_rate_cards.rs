@@ -0,0 +1,101 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RateCard::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RateCard::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(RateCard::TenantId).uuid().not_null())
+                    .col(ColumnDef::new(RateCard::Tier).string().not_null())
+                    .col(ColumnDef::new(RateCard::BaseFare).double().not_null())
+                    .col(ColumnDef::new(RateCard::PerKm).double().not_null())
+                    .col(ColumnDef::new(RateCard::PerMinute).double().not_null())
+                    .col(ColumnDef::new(RateCard::MinimumFare).double().not_null().default(0.0))
+                    .col(ColumnDef::new(RateCard::BookingFee).double().not_null().default(0.0))
+                    // null = surge is not capped
+                    .col(ColumnDef::new(RateCard::SurgeCap).double().null())
+                    .col(
+                        ColumnDef::new(RateCard::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .col(
+                        ColumnDef::new(RateCard::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One card per tier per tenant
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_rate_card_tenant_tier")
+                    .table(RateCard::Table)
+                    .col(RateCard::TenantId)
+                    .col(RateCard::Tier)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_rate_card_tenant")
+                    .from(RateCard::Table, RateCard::TenantId)
+                    .to(Tenant::Table, Tenant::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RateCard::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum RateCard {
+    Table,
+    Id,
+    TenantId,
+    Tier,
+    BaseFare,
+    PerKm,
+    PerMinute,
+    MinimumFare,
+    BookingFee,
+    SurgeCap,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Tenant {
+    Table,
+    Id,
+}
@@ -0,0 +1,80 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Amounts become integer minor units; existing values are taken as
+        // 2-decimal amounts (paise / cents)
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"ALTER TABLE "rate_card"
+               ALTER COLUMN "base_fare" TYPE bigint USING round("base_fare" * 100)::bigint,
+               ALTER COLUMN "per_km" TYPE bigint USING round("per_km" * 100)::bigint,
+               ALTER COLUMN "per_minute" TYPE bigint USING round("per_minute" * 100)::bigint,
+               ALTER COLUMN "minimum_fare" TYPE bigint USING round("minimum_fare" * 100)::bigint,
+               ALTER COLUMN "booking_fee" TYPE bigint USING round("booking_fee" * 100)::bigint"#,
+        )
+        .await?;
+
+        db.execute_unprepared(
+            r#"ALTER TABLE "ride"
+               ALTER COLUMN "cancellation_fee" TYPE bigint USING round("cancellation_fee" * 100)::bigint"#,
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .add_column(
+                        ColumnDef::new(Ride::Currency)
+                            .string_len(3)
+                            .not_null()
+                            .default("INR"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .drop_column(Ride::Currency)
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"ALTER TABLE "ride"
+               ALTER COLUMN "cancellation_fee" TYPE double precision USING "cancellation_fee" / 100.0"#,
+        )
+        .await?;
+
+        db.execute_unprepared(
+            r#"ALTER TABLE "rate_card"
+               ALTER COLUMN "base_fare" TYPE double precision USING "base_fare" / 100.0,
+               ALTER COLUMN "per_km" TYPE double precision USING "per_km" / 100.0,
+               ALTER COLUMN "per_minute" TYPE double precision USING "per_minute" / 100.0,
+               ALTER COLUMN "minimum_fare" TYPE double precision USING "minimum_fare" / 100.0,
+               ALTER COLUMN "booking_fee" TYPE double precision USING "booking_fee" / 100.0"#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    Currency,
+}
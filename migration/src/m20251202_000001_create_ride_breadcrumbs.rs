@@ -0,0 +1,94 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Driver GPS trail while a ride is in progress (metered fare)
+        manager
+            .create_table(
+                Table::create()
+                    .table(RideBreadcrumb::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RideBreadcrumb::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(RideBreadcrumb::TenantId).uuid().not_null())
+                    .col(ColumnDef::new(RideBreadcrumb::RideId).uuid().not_null())
+                    .col(ColumnDef::new(RideBreadcrumb::DriverUserId).uuid().not_null())
+                    .col(ColumnDef::new(RideBreadcrumb::Lat).double().not_null())
+                    .col(ColumnDef::new(RideBreadcrumb::Lon).double().not_null())
+                    .col(ColumnDef::new(RideBreadcrumb::AccuracyM).double().null())
+                    .col(
+                        ColumnDef::new(RideBreadcrumb::RecordedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                    )
+                    .col(
+                        ColumnDef::new(RideBreadcrumb::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ride_breadcrumb_ride_recorded_at")
+                    .table(RideBreadcrumb::Table)
+                    .col(RideBreadcrumb::RideId)
+                    .col(RideBreadcrumb::RecordedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_ride_breadcrumb_ride")
+                    .from(RideBreadcrumb::Table, RideBreadcrumb::RideId)
+                    .to(Ride::Table, Ride::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RideBreadcrumb::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum RideBreadcrumb {
+    Table,
+    Id,
+    TenantId,
+    RideId,
+    DriverUserId,
+    Lat,
+    Lon,
+    AccuracyM,
+    RecordedAt,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    Id,
+}
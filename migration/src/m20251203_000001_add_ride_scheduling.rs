@@ -0,0 +1,103 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1) Pickup time for advance bookings
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .add_column(
+                        ColumnDef::new(Ride::ScheduledFor)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ride_tenant_status_scheduled_for")
+                    .table(Ride::Table)
+                    .col(Ride::TenantId)
+                    .col(Ride::Status)
+                    .col(Ride::ScheduledFor)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2) Allow the 'scheduled' status
+        let conn = manager.get_connection();
+        conn.execute_unprepared(r#"ALTER TABLE "ride" DROP CONSTRAINT IF EXISTS "chk_ride_status""#)
+            .await?;
+        conn.execute_unprepared(
+            r#"ALTER TABLE "ride"
+               ADD CONSTRAINT "chk_ride_status"
+               CHECK ("status" IN (
+                   'scheduled',
+                   'requested',
+                   'assigned',
+                   'accepted',
+                   'in_progress',
+                   'completed',
+                   'cancelled',
+                   'no_driver_found'
+               ))"#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        conn.execute_unprepared(r#"ALTER TABLE "ride" DROP CONSTRAINT IF EXISTS "chk_ride_status""#)
+            .await?;
+        conn.execute_unprepared(
+            r#"ALTER TABLE "ride"
+               ADD CONSTRAINT "chk_ride_status"
+               CHECK ("status" IN (
+                   'requested',
+                   'assigned',
+                   'accepted',
+                   'in_progress',
+                   'completed',
+                   'cancelled',
+                   'no_driver_found'
+               ))"#,
+        )
+        .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_ride_tenant_status_scheduled_for")
+                    .table(Ride::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .drop_column(Ride::ScheduledFor)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    TenantId,
+    Status,
+    ScheduledFor,
+}
@@ -0,0 +1,108 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Ordered intermediate stops between pickup and destination
+        manager
+            .create_table(
+                Table::create()
+                    .table(RideStop::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RideStop::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(RideStop::TenantId).uuid().not_null())
+                    .col(ColumnDef::new(RideStop::RideId).uuid().not_null())
+                    .col(ColumnDef::new(RideStop::Position).integer().not_null())
+                    .col(ColumnDef::new(RideStop::Lat).double().not_null())
+                    .col(ColumnDef::new(RideStop::Lon).double().not_null())
+                    .col(ColumnDef::new(RideStop::Address).string().null())
+                    .col(
+                        ColumnDef::new(RideStop::ArrivedAt)
+                            .timestamp_with_time_zone()
+                            .null()
+                    )
+                    .col(
+                        ColumnDef::new(RideStop::DepartedAt)
+                            .timestamp_with_time_zone()
+                            .null()
+                    )
+                    .col(
+                        ColumnDef::new(RideStop::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .col(
+                        ColumnDef::new(RideStop::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ride_stop_ride_position")
+                    .table(RideStop::Table)
+                    .col(RideStop::RideId)
+                    .col(RideStop::Position)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_ride_stop_ride")
+                    .from(RideStop::Table, RideStop::RideId)
+                    .to(Ride::Table, Ride::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RideStop::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum RideStop {
+    Table,
+    Id,
+    TenantId,
+    RideId,
+    Position,
+    Lat,
+    Lon,
+    Address,
+    ArrivedAt,
+    DepartedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    Id,
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Waiting charge: minor units per minute after a free allowance
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RateCard::Table)
+                    .add_column(
+                        ColumnDef::new(RateCard::WaitPerMinute)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .add_column(
+                        ColumnDef::new(RateCard::FreeWaitMins)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RateCard::Table)
+                    .drop_column(RateCard::WaitPerMinute)
+                    .drop_column(RateCard::FreeWaitMins)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum RateCard {
+    Table,
+    WaitPerMinute,
+    FreeWaitMins,
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1) When the driver reached pickup (starts the waiting clock) and
+        //    the fee paid to them if the rider never showed
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .add_column(
+                        ColumnDef::new(Ride::ArrivedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .add_column(ColumnDef::new(Ride::NoShowFee).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2) Allow 'driver_arrived' and 'rider_no_show'
+        let conn = manager.get_connection();
+        conn.execute_unprepared(r#"ALTER TABLE "ride" DROP CONSTRAINT IF EXISTS "chk_ride_status""#)
+            .await?;
+        conn.execute_unprepared(
+            r#"ALTER TABLE "ride"
+               ADD CONSTRAINT "chk_ride_status"
+               CHECK ("status" IN (
+                   'scheduled',
+                   'requested',
+                   'assigned',
+                   'accepted',
+                   'driver_arrived',
+                   'in_progress',
+                   'completed',
+                   'cancelled',
+                   'rider_no_show',
+                   'no_driver_found'
+               ))"#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        conn.execute_unprepared(r#"ALTER TABLE "ride" DROP CONSTRAINT IF EXISTS "chk_ride_status""#)
+            .await?;
+        conn.execute_unprepared(
+            r#"ALTER TABLE "ride"
+               ADD CONSTRAINT "chk_ride_status"
+               CHECK ("status" IN (
+                   'scheduled',
+                   'requested',
+                   'assigned',
+                   'accepted',
+                   'in_progress',
+                   'completed',
+                   'cancelled',
+                   'no_driver_found'
+               ))"#,
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .drop_column(Ride::ArrivedAt)
+                    .drop_column(Ride::NoShowFee)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    ArrivedAt,
+    NoShowFee,
+}
@@ -0,0 +1,137 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1) One driver's shared trip; pooled rides hang off it
+        manager
+            .create_table(
+                Table::create()
+                    .table(PoolTrip::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PoolTrip::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(PoolTrip::TenantId).uuid().not_null())
+                    .col(ColumnDef::new(PoolTrip::DriverUserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(PoolTrip::Route)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'[]'::jsonb"))
+                    )
+                    .col(
+                        ColumnDef::new(PoolTrip::Version)
+                            .integer()
+                            .not_null()
+                            .default(1)
+                    )
+                    .col(
+                        ColumnDef::new(PoolTrip::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .col(
+                        ColumnDef::new(PoolTrip::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_pool_trip_tenant")
+                    .from(PoolTrip::Table, PoolTrip::TenantId)
+                    .to(Tenant::Table, Tenant::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2) ride -> its pooled trip
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .add_column(ColumnDef::new(Ride::PoolTripId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_ride_pool_trip")
+                    .from(Ride::Table, Ride::PoolTripId)
+                    .to(PoolTrip::Table, PoolTrip::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ride_pool_trip_id")
+                    .table(Ride::Table)
+                    .col(Ride::PoolTripId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .drop_column(Ride::PoolTripId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PoolTrip::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PoolTrip {
+    Table,
+    Id,
+    TenantId,
+    DriverUserId,
+    Route,
+    Version,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    PoolTripId,
+}
+
+#[derive(Iden)]
+enum Tenant {
+    Table,
+    Id,
+}
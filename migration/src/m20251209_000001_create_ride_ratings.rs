@@ -0,0 +1,211 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1) One rating per participant per completed ride
+        manager
+            .create_table(
+                Table::create()
+                    .table(RideRating::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RideRating::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(RideRating::TenantId).uuid().not_null())
+                    .col(ColumnDef::new(RideRating::RideId).uuid().not_null())
+                    .col(ColumnDef::new(RideRating::RaterUserId).uuid().not_null())
+                    .col(ColumnDef::new(RideRating::RateeUserId).uuid().not_null())
+                    .col(ColumnDef::new(RideRating::RaterRole).string_len(16).not_null())
+                    .col(ColumnDef::new(RideRating::Stars).integer().not_null())
+                    .col(
+                        ColumnDef::new(RideRating::Tags)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'[]'::jsonb"))
+                    )
+                    .col(ColumnDef::new(RideRating::Comment).text().null())
+                    .col(
+                        ColumnDef::new(RideRating::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .col(
+                        ColumnDef::new(RideRating::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_ride_rating_ride")
+                    .from(RideRating::Table, RideRating::RideId)
+                    .to(Ride::Table, Ride::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ride_rating_ride_rater")
+                    .table(RideRating::Table)
+                    .col(RideRating::RideId)
+                    .col(RideRating::RaterUserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ride_rating_ratee")
+                    .table(RideRating::Table)
+                    .col(RideRating::RateeUserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"ALTER TABLE "ride_rating" ADD CONSTRAINT "chk_ride_rating_stars" CHECK ("stars" BETWEEN 1 AND 5)"#,
+            )
+            .await?;
+
+        // 2) Running totals: drivers per tenant, riders on their account
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Driver::Table)
+                    .add_column(
+                        ColumnDef::new(Driver::RatingSum)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .add_column(
+                        ColumnDef::new(Driver::RatingCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .add_column(
+                        ColumnDef::new(Driver::RatingFlaggedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new(User::RiderRatingSum)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .add_column(
+                        ColumnDef::new(User::RiderRatingCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .add_column(
+                        ColumnDef::new(User::RiderRatingFlaggedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::RiderRatingSum)
+                    .drop_column(User::RiderRatingCount)
+                    .drop_column(User::RiderRatingFlaggedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Driver::Table)
+                    .drop_column(Driver::RatingSum)
+                    .drop_column(Driver::RatingCount)
+                    .drop_column(Driver::RatingFlaggedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RideRating::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum RideRating {
+    Table,
+    Id,
+    TenantId,
+    RideId,
+    RaterUserId,
+    RateeUserId,
+    RaterRole,
+    Stars,
+    Tags,
+    Comment,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Driver {
+    Table,
+    RatingSum,
+    RatingCount,
+    RatingFlaggedAt,
+}
+
+#[derive(Iden)]
+enum User {
+    Table,
+    RiderRatingSum,
+    RiderRatingCount,
+    RiderRatingFlaggedAt,
+}
@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Rider's tip for the driver, kept apart from the fare; the client's
+        // idempotency key lets a retried request find the tip it already made
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .add_column(ColumnDef::new(Ride::TipAmount).big_integer().null())
+                    .add_column(ColumnDef::new(Ride::TipIdempotencyKey).string_len(64).null())
+                    .add_column(
+                        ColumnDef::new(Ride::TippedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ride::Table)
+                    .drop_column(Ride::TipAmount)
+                    .drop_column(Ride::TipIdempotencyKey)
+                    .drop_column(Ride::TippedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    TipAmount,
+    TipIdempotencyKey,
+    TippedAt,
+}
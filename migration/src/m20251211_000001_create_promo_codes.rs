@@ -0,0 +1,240 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1) Codes a tenant hands out; amounts are minor units of the tenant currency
+        manager
+            .create_table(
+                Table::create()
+                    .table(PromoCode::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PromoCode::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(PromoCode::TenantId).uuid().not_null())
+                    .col(ColumnDef::new(PromoCode::Code).string_len(32).not_null())
+                    .col(ColumnDef::new(PromoCode::Kind).string_len(16).not_null())
+                    .col(ColumnDef::new(PromoCode::Percent).double().null())
+                    .col(ColumnDef::new(PromoCode::AmountMinor).big_integer().null())
+                    .col(ColumnDef::new(PromoCode::MaxDiscountMinor).big_integer().null())
+                    .col(ColumnDef::new(PromoCode::MaxUses).integer().null())
+                    .col(ColumnDef::new(PromoCode::MaxUsesPerUser).integer().null())
+                    .col(
+                        ColumnDef::new(PromoCode::ValidFrom)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(PromoCode::ValidUntil)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(PromoCode::FirstRideOnly)
+                            .boolean()
+                            .not_null()
+                            .default(false)
+                    )
+                    .col(
+                        ColumnDef::new(PromoCode::Tiers)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'[]'::jsonb"))
+                    )
+                    .col(
+                        ColumnDef::new(PromoCode::Active)
+                            .boolean()
+                            .not_null()
+                            .default(true)
+                    )
+                    .col(
+                        ColumnDef::new(PromoCode::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .col(
+                        ColumnDef::new(PromoCode::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_promo_code_tenant")
+                    .from(PromoCode::Table, PromoCode::TenantId)
+                    .to(Tenant::Table, Tenant::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_promo_code_tenant_code")
+                    .table(PromoCode::Table)
+                    .col(PromoCode::TenantId)
+                    .col(PromoCode::Code)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2) A code's use on one ride: reserved at request, consumed at
+        //    completion, released if the ride never completes
+        manager
+            .create_table(
+                Table::create()
+                    .table(PromoRedemption::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PromoRedemption::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(PromoRedemption::TenantId).uuid().not_null())
+                    .col(ColumnDef::new(PromoRedemption::PromoCodeId).uuid().not_null())
+                    .col(ColumnDef::new(PromoRedemption::RideId).uuid().not_null())
+                    .col(ColumnDef::new(PromoRedemption::UserId).uuid().not_null())
+                    .col(ColumnDef::new(PromoRedemption::Status).string_len(16).not_null())
+                    .col(ColumnDef::new(PromoRedemption::Discount).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(PromoRedemption::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .col(
+                        ColumnDef::new(PromoRedemption::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_promo_redemption_promo_code")
+                    .from(PromoRedemption::Table, PromoRedemption::PromoCodeId)
+                    .to(PromoCode::Table, PromoCode::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_promo_redemption_ride")
+                    .from(PromoRedemption::Table, PromoRedemption::RideId)
+                    .to(Ride::Table, Ride::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // one code per ride
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_promo_redemption_ride_id")
+                    .table(PromoRedemption::Table)
+                    .col(PromoRedemption::RideId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_promo_redemption_promo_user")
+                    .table(PromoRedemption::Table)
+                    .col(PromoRedemption::PromoCodeId)
+                    .col(PromoRedemption::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PromoRedemption::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PromoCode::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PromoCode {
+    Table,
+    Id,
+    TenantId,
+    Code,
+    Kind,
+    Percent,
+    AmountMinor,
+    MaxDiscountMinor,
+    MaxUses,
+    MaxUsesPerUser,
+    ValidFrom,
+    ValidUntil,
+    FirstRideOnly,
+    Tiers,
+    Active,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum PromoRedemption {
+    Table,
+    Id,
+    TenantId,
+    PromoCodeId,
+    RideId,
+    UserId,
+    Status,
+    Discount,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Ride {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Tenant {
+    Table,
+    Id,
+}
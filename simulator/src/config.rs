@@ -0,0 +1,138 @@
+// simulator/src/config.rs
+use anyhow::{anyhow, bail, Result};
+
+use dispatch::settings::{DispatchMode, DispatchSettings};
+use dispatch::strategy::StrategyKind;
+
+pub const USAGE: &str = "\
+dispatch-sim: replay a synthetic, seeded scenario through the dispatch code
+
+USAGE:
+    cargo run -p dispatch-sim -- [OPTIONS]
+
+OPTIONS:
+    --seed <n>               scenario seed                         [42]
+    --drivers <n>            drivers online for the whole run      [80]
+    --requests-per-min <x>   average ride requests per minute      [6]
+    --minutes <n>            how long requests keep coming         [60]
+    --bbox <a,b,c,d>         min_lat,min_lon,max_lat,max_lon       [12.93,77.58,13.00,77.66]
+    --speed-kmh <x>          driving speed for pickup / trip       [25]
+    --min-accept <x>         lowest per-driver accept probability  [0.6]
+    --response-secs <n>      time a driver takes to answer         [5]
+    --modes <list>           sequential,broadcast,batch            [sequential]
+    --strategies <list>      nearest,longest_idle,weighted         [nearest,longest_idle,weighted]
+    --settings <json>        base tenant dispatch settings (JSON)  [defaults]
+
+Every mode x strategy combination runs on the same scenario. Batch mode
+ranks by pickup distance, so its rows don't change with the strategy.";
+
+/// One simulated scenario plus which dispatch variants to run on it.
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+    pub seed: u64,
+    pub drivers: usize,
+    pub requests_per_min: f64,
+    pub minutes: u64,
+    /// (min_lat, min_lon, max_lat, max_lon)
+    pub bbox: (f64, f64, f64, f64),
+    pub speed_kmh: f64,
+    pub min_accept: f64,
+    pub response_secs: u64,
+    pub modes: Vec<DispatchMode>,
+    pub strategies: Vec<StrategyKind>,
+    pub settings: DispatchSettings,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            drivers: 80,
+            requests_per_min: 6.0,
+            minutes: 60,
+            bbox: (12.93, 77.58, 13.00, 77.66),
+            speed_kmh: 25.0,
+            min_accept: 0.6,
+            response_secs: 5,
+            modes: vec![DispatchMode::Sequential],
+            strategies: vec![
+                StrategyKind::Nearest,
+                StrategyKind::LongestIdle,
+                StrategyKind::Weighted,
+            ],
+            settings: DispatchSettings::default(),
+        }
+    }
+}
+
+/// Parse a snake_case enum value the same way tenant settings JSON does
+fn parse_enum_list<T: serde::de::DeserializeOwned>(raw: &str) -> Result<Vec<T>> {
+    raw.split(',')
+        .map(|s| {
+            serde_json::from_value(serde_json::Value::String(s.trim().to_string()))
+                .map_err(|_| anyhow!("unknown value '{}'", s.trim()))
+        })
+        .collect()
+}
+
+impl SimConfig {
+    /// `None` means `--help` was asked for
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Option<Self>> {
+        let mut cfg = SimConfig::default();
+        let mut args = args.peekable();
+
+        while let Some(flag) = args.next() {
+            if flag == "--help" || flag == "-h" {
+                return Ok(None);
+            }
+
+            // accept both `--key value` and `--key=value`
+            let (key, value) = match flag.split_once('=') {
+                Some((k, v)) => (k.to_string(), v.to_string()),
+                None => {
+                    let v = args
+                        .next()
+                        .ok_or_else(|| anyhow!("missing value for {flag}"))?;
+                    (flag, v)
+                }
+            };
+
+            match key.as_str() {
+                "--seed" => cfg.seed = value.parse()?,
+                "--drivers" => cfg.drivers = value.parse()?,
+                "--requests-per-min" => cfg.requests_per_min = value.parse()?,
+                "--minutes" => cfg.minutes = value.parse()?,
+                "--speed-kmh" => cfg.speed_kmh = value.parse()?,
+                "--min-accept" => cfg.min_accept = value.parse()?,
+                "--response-secs" => cfg.response_secs = value.parse()?,
+                "--modes" => cfg.modes = parse_enum_list(&value)?,
+                "--strategies" => cfg.strategies = parse_enum_list(&value)?,
+                "--settings" => cfg.settings = serde_json::from_str(&value)?,
+                "--bbox" => {
+                    let v: Vec<f64> = value
+                        .split(',')
+                        .map(|p| p.trim().parse())
+                        .collect::<Result<_, _>>()?;
+                    let [a, b, c, d] = v[..] else {
+                        bail!("--bbox needs min_lat,min_lon,max_lat,max_lon");
+                    };
+                    if a >= c || b >= d {
+                        bail!("--bbox min must be below max");
+                    }
+                    cfg.bbox = (a, b, c, d);
+                }
+                other => bail!("unknown option {other} (see --help)"),
+            }
+        }
+
+        if cfg.drivers == 0 || cfg.minutes == 0 {
+            bail!("--drivers and --minutes must be > 0");
+        }
+        if cfg.speed_kmh <= 0.0 {
+            bail!("--speed-kmh must be > 0");
+        }
+        cfg.min_accept = cfg.min_accept.clamp(0.0, 1.0);
+
+        Ok(Some(cfg))
+    }
+}
@@ -0,0 +1,36 @@
+// simulator/src/main.rs
+// Offline dispatch simulator: same seeded scenario, every requested
+// mode x strategy, side by side.
+mod config;
+mod memory;
+mod report;
+mod scenario;
+mod sim;
+
+use config::{SimConfig, USAGE};
+use report::print_report;
+use scenario::Scenario;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let Some(cfg) = SimConfig::from_args(std::env::args().skip(1))? else {
+        println!("{USAGE}");
+        return Ok(());
+    };
+
+    let scenario = Scenario::generate(&cfg);
+
+    let mut runs = Vec::new();
+    for &mode in &cfg.modes {
+        for &strategy in &cfg.strategies {
+            let mut settings = cfg.settings.clone();
+            settings.mode = mode;
+            settings.strategy = strategy;
+
+            runs.push(sim::run(&cfg, &scenario, &settings).await?);
+        }
+    }
+
+    print_report(&cfg, &runs);
+    Ok(())
+}
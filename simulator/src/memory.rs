@@ -0,0 +1,167 @@
+// simulator/src/memory.rs
+// In-memory stand-ins for what the server keeps in Redis GEO and in the
+// `driver` / `ride` tables.
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use dispatch::geo::haversine_km;
+use dispatch::search::{DriverIndex, DriverPool};
+use dispatch::strategy::Candidate;
+
+use crate::scenario::{DriverSpec, RequestSpec};
+
+/// Row of the in-memory `driver` table
+#[derive(Debug, Clone)]
+pub struct SimDriver {
+    pub spec: DriverSpec,
+    /// Current position (moves to the drop-off after every trip)
+    pub lat: f64,
+    pub lon: f64,
+    pub idle_since: u64,
+    pub offers_received: i32,
+    pub offers_accepted: i32,
+}
+
+impl SimDriver {
+    pub fn new(spec: DriverSpec) -> Self {
+        Self {
+            lat: spec.lat,
+            lon: spec.lon,
+            spec,
+            idle_since: 0,
+            offers_received: 0,
+            offers_accepted: 0,
+        }
+    }
+}
+
+/// Where a simulated ride is in its life
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimRideState {
+    /// `requested`: waiting for (another) dispatch round
+    Waiting,
+    /// A driver took it; busy from `matched_at` until `dropoff_at`
+    Matched {
+        matched_at: u64,
+        pickup_at: u64,
+        dropoff_at: u64,
+        pickup_km: f64,
+    },
+    /// Trip finished
+    Completed {
+        matched_at: u64,
+        pickup_at: u64,
+        dropoff_at: u64,
+        pickup_km: f64,
+    },
+    /// `no_driver_found`
+    NoDriver,
+}
+
+/// Row of the in-memory `ride` table
+#[derive(Debug, Clone)]
+pub struct SimRide {
+    pub spec: RequestSpec,
+    pub state: SimRideState,
+    pub driver_user_id: Option<Uuid>,
+    pub attempts: i32,
+    /// Drivers who declined this ride (the Redis exclusion set)
+    pub excluded: HashSet<Uuid>,
+    /// Earliest time the next dispatch round may run
+    pub next_dispatch_at: u64,
+}
+
+impl SimRide {
+    pub fn new(spec: RequestSpec) -> Self {
+        Self {
+            next_dispatch_at: spec.at,
+            spec,
+            state: SimRideState::Waiting,
+            driver_user_id: None,
+            attempts: 0,
+            excluded: HashSet::new(),
+        }
+    }
+}
+
+/// Redis GEO stand-in: brute-force radius search over driver positions
+pub struct InMemoryGeo<'a> {
+    pub tenant_id: Uuid,
+    pub drivers: &'a [SimDriver],
+}
+
+#[async_trait]
+impl DriverIndex for InMemoryGeo<'_> {
+    async fn nearby(
+        &self,
+        tenant_id: Uuid,
+        lat: f64,
+        lon: f64,
+        radius_km: f64,
+        max_results: usize,
+    ) -> anyhow::Result<Vec<(Uuid, f64)>> {
+        if tenant_id != self.tenant_id {
+            return Ok(Vec::new());
+        }
+
+        let mut hits: Vec<(Uuid, f64)> = self
+            .drivers
+            .iter()
+            .map(|d| (d.spec.user_id, haversine_km(lat, lon, d.lat, d.lon)))
+            .filter(|(_, km)| *km <= radius_km)
+            .collect();
+
+        // ASC COUNT N
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+        hits.truncate(max_results);
+        Ok(hits)
+    }
+}
+
+/// Driver / ride table stand-in: a driver is free unless one of the rides
+/// holds them
+pub struct InMemoryPool<'a> {
+    pub tenant_id: Uuid,
+    pub drivers: &'a [SimDriver],
+    pub rides: &'a [SimRide],
+    /// Simulation clock (seconds)
+    pub now: u64,
+}
+
+#[async_trait]
+impl DriverPool for InMemoryPool<'_> {
+    async fn available(
+        &self,
+        tenant_id: Uuid,
+        nearby: &[(Uuid, f64)],
+    ) -> anyhow::Result<Vec<Candidate>> {
+        if tenant_id != self.tenant_id {
+            return Ok(Vec::new());
+        }
+
+        let busy: HashSet<Uuid> = self
+            .rides
+            .iter()
+            .filter(|r| matches!(r.state, SimRideState::Matched { .. }))
+            .filter_map(|r| r.driver_user_id)
+            .collect();
+
+        Ok(nearby
+            .iter()
+            .filter(|(id, _)| !busy.contains(id))
+            .filter_map(|(id, distance_km)| {
+                let d = self.drivers.iter().find(|d| d.spec.user_id == *id)?;
+                Some(Candidate {
+                    driver_user_id: *id,
+                    distance_km: *distance_km,
+                    idle_secs: self.now.saturating_sub(d.idle_since) as i64,
+                    acceptance_rate: (d.offers_received > 0)
+                        .then(|| d.offers_accepted as f64 / d.offers_received as f64),
+                    rating: Some(d.spec.rating),
+                })
+            })
+            .collect())
+    }
+}
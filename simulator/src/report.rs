@@ -0,0 +1,131 @@
+// simulator/src/report.rs
+use dispatch::settings::{DispatchMode, DispatchSettings};
+use dispatch::strategy::StrategyKind;
+
+use crate::config::SimConfig;
+use crate::memory::{SimDriver, SimRide, SimRideState};
+
+/// What one run of a scenario produced.
+#[derive(Debug, Clone)]
+pub struct RunStats {
+    pub mode: DispatchMode,
+    pub strategy: StrategyKind,
+    pub requests: usize,
+    pub matched: usize,
+    pub no_driver: usize,
+    /// Request -> pickup (seconds), matched rides only
+    pub wait_avg_secs: f64,
+    pub wait_p50_secs: u64,
+    pub wait_p90_secs: u64,
+    pub pickup_avg_km: f64,
+    /// Share of driver time spent on the way to pickup or on a trip
+    /// (request window only)
+    pub utilization: f64,
+    pub rounds_per_ride: f64,
+}
+
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[idx]
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, n) = values.fold((0.0, 0usize), |(s, n), v| (s + v, n + 1));
+    if n == 0 { 0.0 } else { sum / n as f64 }
+}
+
+impl RunStats {
+    pub fn collect(
+        settings: &DispatchSettings,
+        drivers: &[SimDriver],
+        rides: &[SimRide],
+        horizon: u64,
+    ) -> Self {
+        // (matched_at, pickup_at, dropoff_at, pickup_km, requested_at)
+        let matched: Vec<(u64, u64, u64, f64, u64)> = rides
+            .iter()
+            .filter_map(|r| match r.state {
+                SimRideState::Matched { matched_at, pickup_at, dropoff_at, pickup_km }
+                | SimRideState::Completed { matched_at, pickup_at, dropoff_at, pickup_km } => {
+                    Some((matched_at, pickup_at, dropoff_at, pickup_km, r.spec.at))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut waits: Vec<u64> = matched.iter().map(|m| m.1 - m.4).collect();
+        waits.sort_unstable();
+
+        let busy_secs: u64 = matched
+            .iter()
+            .map(|&(from, _, to, _, _)| to.min(horizon).saturating_sub(from.min(horizon)))
+            .sum();
+        let driver_secs = (drivers.len() as u64 * horizon).max(1);
+
+        RunStats {
+            mode: settings.mode,
+            strategy: settings.strategy,
+            requests: rides.len(),
+            matched: matched.len(),
+            no_driver: rides
+                .iter()
+                .filter(|r| r.state == SimRideState::NoDriver)
+                .count(),
+            wait_avg_secs: mean(waits.iter().map(|w| *w as f64)),
+            wait_p50_secs: percentile(&waits, 0.5),
+            wait_p90_secs: percentile(&waits, 0.9),
+            pickup_avg_km: mean(matched.iter().map(|m| m.3)),
+            utilization: busy_secs as f64 / driver_secs as f64,
+            rounds_per_ride: mean(rides.iter().map(|r| r.attempts as f64)),
+        }
+    }
+
+    pub fn match_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.matched as f64 / self.requests as f64
+        }
+    }
+}
+
+fn label<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+pub fn print_report(cfg: &SimConfig, runs: &[RunStats]) {
+    println!(
+        "scenario: seed={} drivers={} requests/min={} minutes={} bbox={:?} speed={}km/h",
+        cfg.seed, cfg.drivers, cfg.requests_per_min, cfg.minutes, cfg.bbox, cfg.speed_kmh
+    );
+    println!();
+    println!(
+        "{:<11} {:<13} {:>8} {:>8} {:>9} {:>7} {:>9} {:>9} {:>9} {:>10} {:>7} {:>7}",
+        "mode", "strategy", "requests", "matched", "no_driver", "match%", "wait_avg", "wait_p50", "wait_p90",
+        "pickup_km", "util%", "rounds"
+    );
+
+    for r in runs {
+        println!(
+            "{:<11} {:<13} {:>8} {:>8} {:>9} {:>6.1}% {:>8.0}s {:>8}s {:>8}s {:>10.2} {:>6.1}% {:>7.2}",
+            label(&r.mode),
+            label(&r.strategy),
+            r.requests,
+            r.matched,
+            r.no_driver,
+            r.match_rate() * 100.0,
+            r.wait_avg_secs,
+            r.wait_p50_secs,
+            r.wait_p90_secs,
+            r.pickup_avg_km,
+            r.utilization * 100.0,
+            r.rounds_per_ride,
+        );
+    }
+}
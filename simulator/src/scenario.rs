@@ -0,0 +1,97 @@
+// simulator/src/scenario.rs
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use uuid::Uuid;
+
+use crate::config::SimConfig;
+
+/// A driver as generated for the scenario (before any dispatch happened).
+#[derive(Debug, Clone)]
+pub struct DriverSpec {
+    pub user_id: Uuid,
+    pub lat: f64,
+    pub lon: f64,
+    /// Chance this driver accepts any single offer
+    pub accept_prob: f64,
+    pub rating: f64,
+}
+
+/// One ride request of the scenario.
+#[derive(Debug, Clone)]
+pub struct RequestSpec {
+    pub ride_id: Uuid,
+    /// Seconds since the start of the run
+    pub at: u64,
+    pub pickup: (f64, f64),
+    pub dest: (f64, f64),
+}
+
+/// Everything random about a run, fixed by the seed so every dispatch
+/// variant sees the exact same drivers and requests.
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    pub tenant_id: Uuid,
+    pub drivers: Vec<DriverSpec>,
+    pub requests: Vec<RequestSpec>,
+}
+
+fn random_point(rng: &mut StdRng, bbox: (f64, f64, f64, f64)) -> (f64, f64) {
+    let (min_lat, min_lon, max_lat, max_lon) = bbox;
+    (
+        rng.random_range(min_lat..max_lat),
+        rng.random_range(min_lon..max_lon),
+    )
+}
+
+/// Knuth's method; fine for the small per-second rates we use
+fn poisson(rng: &mut StdRng, lambda: f64) -> u32 {
+    let limit = (-lambda).exp();
+    let mut k = 0;
+    let mut p = 1.0;
+    loop {
+        p *= rng.random::<f64>();
+        if p <= limit {
+            return k;
+        }
+        k += 1;
+    }
+}
+
+impl Scenario {
+    pub fn generate(cfg: &SimConfig) -> Self {
+        let mut rng = StdRng::seed_from_u64(cfg.seed);
+        let tenant_id = Uuid::from_u128(rng.random());
+
+        let drivers = (0..cfg.drivers)
+            .map(|_| {
+                let (lat, lon) = random_point(&mut rng, cfg.bbox);
+                DriverSpec {
+                    user_id: Uuid::from_u128(rng.random()),
+                    lat,
+                    lon,
+                    accept_prob: rng.random_range(cfg.min_accept..=1.0),
+                    rating: rng.random_range(3.5..=5.0),
+                }
+            })
+            .collect();
+
+        let per_sec = cfg.requests_per_min / 60.0;
+        let mut requests = Vec::new();
+        for at in 0..cfg.minutes * 60 {
+            for _ in 0..poisson(&mut rng, per_sec) {
+                requests.push(RequestSpec {
+                    ride_id: Uuid::from_u128(rng.random()),
+                    at,
+                    pickup: random_point(&mut rng, cfg.bbox),
+                    dest: random_point(&mut rng, cfg.bbox),
+                });
+            }
+        }
+
+        Scenario {
+            tenant_id,
+            drivers,
+            requests,
+        }
+    }
+}
@@ -0,0 +1,284 @@
+// simulator/src/sim.rs
+use anyhow::Result;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use uuid::Uuid;
+
+use dispatch::geo::haversine_km;
+use dispatch::matching::assign_batch;
+use dispatch::search::find_candidates;
+use dispatch::settings::{DispatchMode, DispatchSettings};
+use dispatch::strategy::{DispatchRide, DispatchStrategy};
+
+use crate::config::SimConfig;
+use crate::memory::{InMemoryGeo, InMemoryPool, SimDriver, SimRide, SimRideState};
+use crate::report::RunStats;
+use crate::scenario::Scenario;
+
+/// Keep ticking this long after the last request so open rides can settle
+const DRAIN_SECS: u64 = 2 * 60 * 60;
+
+/// One run of a scenario under one set of dispatch settings.
+struct World<'a> {
+    cfg: &'a SimConfig,
+    settings: &'a DispatchSettings,
+    strategy: Box<dyn DispatchStrategy>,
+    tenant_id: Uuid,
+    drivers: Vec<SimDriver>,
+    rides: Vec<SimRide>,
+    /// Decides how drivers answer offers
+    rng: StdRng,
+}
+
+/// Offer outcome for one dispatch round of one ride
+struct Offer {
+    /// (driver, pickup distance km), best first
+    drivers: Vec<(Uuid, f64)>,
+    broadcast: bool,
+}
+
+pub async fn run(cfg: &SimConfig, scenario: &Scenario, settings: &DispatchSettings) -> Result<RunStats> {
+    let mut world = World {
+        cfg,
+        settings,
+        strategy: settings.strategy.build(&settings.weighted),
+        tenant_id: scenario.tenant_id,
+        drivers: scenario.drivers.iter().cloned().map(SimDriver::new).collect(),
+        rides: Vec::with_capacity(scenario.requests.len()),
+        // separate stream from the scenario so answers don't shift the world
+        rng: StdRng::seed_from_u64(cfg.seed.wrapping_add(1)),
+    };
+
+    let horizon = cfg.minutes * 60;
+    let mut requests = scenario.requests.iter().peekable();
+    let mut now = 0;
+
+    loop {
+        world.finish_trips(now);
+
+        while let Some(req) = requests.next_if(|r| r.at <= now) {
+            world.rides.push(SimRide::new(req.clone()));
+        }
+
+        match settings.mode {
+            DispatchMode::Batch => {
+                if now % settings.batch_window_secs.max(1) == 0 {
+                    world.dispatch_batch(now).await?;
+                }
+            }
+            DispatchMode::Sequential | DispatchMode::Broadcast => {
+                world.dispatch_each(now).await?;
+            }
+        }
+
+        now += 1;
+        let settled = requests.peek().is_none() && world.all_settled();
+        if (now >= horizon && settled) || now >= horizon + DRAIN_SECS {
+            break;
+        }
+    }
+
+    Ok(RunStats::collect(settings, &world.drivers, &world.rides, horizon))
+}
+
+impl World<'_> {
+    fn all_settled(&self) -> bool {
+        self.rides
+            .iter()
+            .all(|r| matches!(r.state, SimRideState::Completed { .. } | SimRideState::NoDriver))
+    }
+
+    fn travel_secs(&self, km: f64) -> u64 {
+        (km / self.cfg.speed_kmh * 3600.0).round() as u64
+    }
+
+    fn dispatch_ride(&self, ride: &SimRide) -> DispatchRide {
+        DispatchRide {
+            ride_id: ride.spec.ride_id,
+            tenant_id: self.tenant_id,
+            pickup_lat: ride.spec.pickup.0,
+            pickup_lon: ride.spec.pickup.1,
+        }
+    }
+
+    /// Rides due for a dispatch round now, oldest request first
+    fn due(&self, now: u64) -> Vec<usize> {
+        (0..self.rides.len())
+            .filter(|&i| {
+                self.rides[i].state == SimRideState::Waiting && self.rides[i].next_dispatch_at <= now
+            })
+            .collect()
+    }
+
+    /// Drop-offs: the driver is free again at the destination
+    fn finish_trips(&mut self, now: u64) {
+        for ride in &mut self.rides {
+            if let SimRideState::Matched { matched_at, pickup_at, dropoff_at, pickup_km } = ride.state {
+                if dropoff_at > now {
+                    continue;
+                }
+                ride.state = SimRideState::Completed { matched_at, pickup_at, dropoff_at, pickup_km };
+
+                if let Some(d) = self
+                    .drivers
+                    .iter_mut()
+                    .find(|d| Some(d.spec.user_id) == ride.driver_user_id)
+                {
+                    (d.lat, d.lon) = ride.spec.dest;
+                    d.idle_since = dropoff_at;
+                }
+            }
+        }
+    }
+
+    /// Sequential / broadcast: every due ride gets its own ranked round
+    async fn dispatch_each(&mut self, now: u64) -> Result<()> {
+        let wanted = self.settings.offers_per_round();
+        let broadcast = self.settings.mode == DispatchMode::Broadcast;
+
+        for i in self.due(now) {
+            if self.rides[i].attempts >= self.settings.max_dispatch_attempts {
+                self.rides[i].state = SimRideState::NoDriver;
+                continue;
+            }
+
+            let ride = self.dispatch_ride(&self.rides[i]);
+            let (candidates, _) = find_candidates(
+                &InMemoryGeo { tenant_id: self.tenant_id, drivers: &self.drivers },
+                &InMemoryPool {
+                    tenant_id: self.tenant_id,
+                    drivers: &self.drivers,
+                    rides: &self.rides,
+                    now,
+                },
+                &ride,
+                self.settings,
+                &self.rides[i].excluded,
+                wanted,
+            )
+            .await?;
+
+            let drivers = self
+                .strategy
+                .rank(&ride, candidates)
+                .into_iter()
+                .take(wanted)
+                .map(|c| (c.candidate.driver_user_id, c.candidate.distance_km))
+                .collect();
+
+            self.resolve_offer(i, now, Offer { drivers, broadcast });
+        }
+
+        Ok(())
+    }
+
+    /// Batch: all due rides are matched against their candidates at once
+    async fn dispatch_batch(&mut self, now: u64) -> Result<()> {
+        let mut batch = Vec::new();
+        for i in self.due(now) {
+            if self.rides[i].attempts >= self.settings.max_dispatch_attempts {
+                self.rides[i].state = SimRideState::NoDriver;
+            } else {
+                batch.push(i);
+            }
+        }
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let mut ride_candidates = Vec::with_capacity(batch.len());
+        for &i in &batch {
+            let ride = self.dispatch_ride(&self.rides[i]);
+            let (candidates, _) = find_candidates(
+                &InMemoryGeo { tenant_id: self.tenant_id, drivers: &self.drivers },
+                &InMemoryPool {
+                    tenant_id: self.tenant_id,
+                    drivers: &self.drivers,
+                    rides: &self.rides,
+                    now,
+                },
+                &ride,
+                self.settings,
+                &self.rides[i].excluded,
+                batch.len(),
+            )
+            .await?;
+            ride_candidates.push(candidates);
+        }
+
+        let assignment = assign_batch(&ride_candidates);
+
+        for (slot, &i) in batch.iter().enumerate() {
+            let drivers = assignment.pick(slot).into_iter().collect();
+            self.resolve_offer(i, now, Offer { drivers, broadcast: false });
+        }
+
+        Ok(())
+    }
+
+    /// Drivers answer the offer; the ride is matched, retried or given up
+    fn resolve_offer(&mut self, i: usize, now: u64, offer: Offer) {
+        let attempt = self.rides[i].attempts + 1;
+        self.rides[i].attempts = attempt;
+        let out_of_attempts = attempt >= self.settings.max_dispatch_attempts;
+
+        if offer.drivers.is_empty() {
+            if out_of_attempts {
+                self.rides[i].state = SimRideState::NoDriver;
+            } else {
+                self.rides[i].next_dispatch_at = now + self.settings.retry_delay_secs;
+            }
+            return;
+        }
+
+        // broadcast: answers come back in no particular order, first yes wins
+        let mut answering = offer.drivers.clone();
+        if offer.broadcast {
+            answering.shuffle(&mut self.rng);
+        }
+
+        let mut winner = None;
+        for (driver_user_id, pickup_km) in answering {
+            let Some(d) = self.drivers.iter_mut().find(|d| d.spec.user_id == driver_user_id) else {
+                continue;
+            };
+            d.offers_received += 1;
+
+            if self.rng.random_bool(d.spec.accept_prob) {
+                if winner.is_none() {
+                    d.offers_accepted += 1;
+                    winner = Some((driver_user_id, pickup_km));
+                }
+            } else {
+                self.rides[i].excluded.insert(driver_user_id);
+            }
+        }
+
+        let response = self.cfg.response_secs;
+
+        let Some((driver_user_id, pickup_km)) = winner else {
+            if out_of_attempts {
+                self.rides[i].state = SimRideState::NoDriver;
+            } else {
+                // offer round took `response` seconds to come back empty
+                self.rides[i].next_dispatch_at = now + response.max(1);
+            }
+            return;
+        };
+
+        let (pickup, dest) = (self.rides[i].spec.pickup, self.rides[i].spec.dest);
+        let trip_km = haversine_km(pickup.0, pickup.1, dest.0, dest.1);
+        let pickup_at = now + response + self.travel_secs(pickup_km);
+        let dropoff_at = pickup_at + self.travel_secs(trip_km).max(1);
+
+        let ride = &mut self.rides[i];
+        ride.driver_user_id = Some(driver_user_id);
+        ride.state = SimRideState::Matched {
+            matched_at: now,
+            pickup_at,
+            dropoff_at,
+            pickup_km,
+        };
+    }
+}
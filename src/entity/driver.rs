@@ -16,6 +16,21 @@ pub struct Model {
     pub lat: Option<f64>,
     pub lon: Option<f64>,
 
+    /// Accepted rides this driver later cancelled
+    pub cancellation_count: i32,
+
+    /// Free since (went online / finished last trip); None while on a trip
+    pub idle_since: Option<DateTimeWithTimeZone>,
+    /// Ride offers sent / accepted, for the acceptance rate
+    pub offers_received: i32,
+    pub offers_accepted: i32,
+
+    /// Stars received from riders; average = sum / count
+    pub rating_sum: i64,
+    pub rating_count: i32,
+    /// Set while the average sits below the tenant's flag threshold
+    pub rating_flagged_at: Option<DateTimeWithTimeZone>,
+
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
 }
@@ -5,6 +5,13 @@ pub mod ride;
 pub mod driver;
 pub mod ride_event;
 pub mod driver_event;
+pub mod rate_card;
+pub mod ride_breadcrumb;
+pub mod ride_stop;
+pub mod pool_trip;
+pub mod ride_rating;
+pub mod promo_code;
+pub mod promo_redemption;
 
 pub mod prelude {
     pub use super::tenant::Entity as Tenant;
@@ -13,4 +20,11 @@ pub mod prelude {
     pub use super::driver::Entity as Driver;
     pub use super::ride_event::Entity as RideEvent;
     pub use super::driver_event::Entity as DriverEvent;
+    pub use super::rate_card::Entity as RateCard;
+    pub use super::ride_breadcrumb::Entity as RideBreadcrumb;
+    pub use super::ride_stop::Entity as RideStop;
+    pub use super::pool_trip::Entity as PoolTrip;
+    pub use super::ride_rating::Entity as RideRating;
+    pub use super::promo_code::Entity as PromoCode;
+    pub use super::promo_redemption::Entity as PromoRedemption;
 }
@@ -0,0 +1,30 @@
+// src/entity/pool_trip.rs
+use sea_orm::entity::prelude::*;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+/// One driver carrying several pooled rides.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "pool_trip")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub tenant_id: Uuid,
+    pub driver_user_id: Uuid,
+
+    /// Planned pickups / drop-offs in order
+    /// (`[dispatch::pool::Waypoint]`: done ones first, then the remaining plan)
+    pub route: Json,
+
+    /// Bumped on every route change (optimistic concurrency)
+    pub version: i32,
+
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,62 @@
+// src/entity/promo_code.rs
+use sea_orm::entity::prelude::*;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::types::promo::PromoKind;
+
+/// Discount code a tenant hands out. Amounts are minor units of the tenant currency.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "promo_code")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub tenant_id: Uuid,
+    /// Upper-case, unique per tenant
+    pub code: String,
+
+    pub kind: PromoKind,
+    /// `percent` codes: share of the fare taken off (0..=100]
+    pub percent: Option<f64>,
+    /// `flat` codes: amount taken off
+    pub amount_minor: Option<i64>,
+    /// Cap on the discount for one ride
+    pub max_discount_minor: Option<i64>,
+
+    /// Reserved + consumed uses allowed, in total / per rider; None = unlimited
+    pub max_uses: Option<i32>,
+    pub max_uses_per_user: Option<i32>,
+
+    pub valid_from: Option<DateTimeWithTimeZone>,
+    pub valid_until: Option<DateTimeWithTimeZone>,
+
+    /// Only for riders without a completed ride in the tenant
+    pub first_ride_only: bool,
+    /// Tiers the code works for; empty = every tier
+    pub tiers: Json,
+    pub active: bool,
+
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::tenant::Entity",
+        from = "Column::TenantId",
+        to   = "super::tenant::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Tenant,
+}
+
+impl Related<super::tenant::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tenant.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
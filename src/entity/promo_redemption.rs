@@ -0,0 +1,61 @@
+// src/entity/promo_redemption.rs
+use sea_orm::entity::prelude::*;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::types::promo::RedemptionStatus;
+
+/// One promo code use, tied to the ride it was attached to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "promo_redemption")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub tenant_id: Uuid,
+    pub promo_code_id: Uuid,
+    pub ride_id: Uuid,
+    pub user_id: Uuid,
+
+    pub status: RedemptionStatus,
+    /// Estimated discount while reserved, the one charged once consumed
+    /// (minor units of the ride currency)
+    pub discount: i64,
+
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::promo_code::Entity",
+        from = "Column::PromoCodeId",
+        to   = "super::promo_code::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    PromoCode,
+    #[sea_orm(
+        belongs_to = "super::ride::Entity",
+        from = "Column::RideId",
+        to   = "super::ride::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Ride,
+}
+
+impl Related<super::promo_code::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PromoCode.def()
+    }
+}
+
+impl Related<super::ride::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Ride.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
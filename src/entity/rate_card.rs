@@ -0,0 +1,51 @@
+// src/entity/rate_card.rs
+use sea_orm::entity::prelude::*;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+/// Prices for one tier of one tenant (see `types::fare::RateCard`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "rate_card")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub tenant_id: Uuid,
+    /// Matches `ride.tier`
+    pub tier: String,
+
+    /// Amounts in minor units of the tenant's currency
+    pub base_fare: i64,
+    pub per_km: i64,
+    pub per_minute: i64,
+    pub minimum_fare: i64,
+    pub booking_fee: i64,
+    /// Waiting charge per minute beyond `free_wait_mins` (per stop)
+    pub wait_per_minute: i64,
+    pub free_wait_mins: f64,
+    /// Highest surge multiplier this tier will charge; None = uncapped
+    pub surge_cap: Option<f64>,
+
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::tenant::Entity",
+        from = "Column::TenantId",
+        to   = "super::tenant::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Tenant,
+}
+
+impl Related<super::tenant::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tenant.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
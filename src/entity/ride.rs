@@ -2,6 +2,9 @@ use sea_orm::entity::prelude::*;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
+use crate::types::money::Currency;
+use crate::types::ride_status::RideStatus;
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
 #[sea_orm(table_name = "ride")]
 pub struct Model {
@@ -19,10 +22,62 @@ pub struct Model {
     pub dest_lat: f64,
     pub dest_lon: f64,
     pub dest_address: Option<String>,
+    /// Rider's new destination awaiting driver approval
+    /// (`{lat, lon, address, shift_km, requested_at}`)
+    pub pending_destination: Option<Json>,
+    /// Destination the ride was booked to (`{lat, lon, address}`), kept on
+    /// its first change; shifts are measured from here
+    pub original_destination: Option<Json>,
 
     pub tier: String,
     pub payment_method_id: String,
-    pub status: String,
+    pub status: RideStatus,
+
+    /// Shared trip this pooled ride rides on (set once a driver has it)
+    pub pool_trip_id: Option<Uuid>,
+
+    /// Bumped on every status transition (optimistic concurrency).
+    pub version: i32,
+
+    /// Pickup time of an advance booking; None = immediate ride
+    pub scheduled_for: Option<DateTimeWithTimeZone>,
+
+    pub accepted_at: Option<DateTimeWithTimeZone>,
+    /// Code the rider tells the driver before the trip starts; set on accept
+    /// when the tenant requires it for the tier. Never shown to the driver.
+    pub pickup_pin: Option<String>,
+    /// Driver reached pickup; pickup waiting is billed from here
+    pub arrived_at: Option<DateTimeWithTimeZone>,
+    pub started_at: Option<DateTimeWithTimeZone>,
+    pub completed_at: Option<DateTimeWithTimeZone>,
+    pub cancelled_at: Option<DateTimeWithTimeZone>,
+    /// Reason code given by whoever cancelled (e.g. "changed_plans")
+    pub cancel_reason: Option<String>,
+    /// Minor units of `currency`
+    pub cancellation_fee: Option<i64>,
+    /// Paid to the driver when the ride ended as `rider_no_show` (minor units)
+    pub no_show_fee: Option<i64>,
+    /// Rider's tip for the driver after completion (minor units), on top of the fare
+    pub tip_amount: Option<i64>,
+    /// Client key of the request that made the tip; a retry with it is a no-op
+    pub tip_idempotency_key: Option<String>,
+    pub tipped_at: Option<DateTimeWithTimeZone>,
+
+    /// Dispatch rounds run so far (1-based attempt numbers in ride_event)
+    pub dispatch_attempts: i32,
+
+    /// Deadline for the assigned driver to accept the current offer
+    pub offer_expires_at: Option<DateTimeWithTimeZone>,
+
+    /// Tenant currency when the ride was requested; all ride amounts use it
+    pub currency: Currency,
+
+    /// Surge the ride is priced at (from the quote when one was used)
+    pub surge_multiplier: f64,
+    /// Estimate the rider accepted via `quote_id`, if any
+    pub quote: Option<Json>,
+    /// Final fare breakdown, set on completion
+    pub fare: Option<Json>,
 
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
@@ -32,3 +87,103 @@ pub struct Model {
 pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Whether this ride keeps its driver from accepting ride `accepting`,
+    /// which would ride on pool trip `pool_trip_id`: it does once the driver
+    /// has taken it on, unless both share the trip. Open offers don't count.
+    pub fn blocks_accept(&self, accepting: Uuid, pool_trip_id: Option<Uuid>) -> bool {
+        self.id != accepting
+            && RideStatus::DRIVER_ENGAGED.contains(&self.status)
+            && (self.pool_trip_id.is_none() || self.pool_trip_id != pool_trip_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ride(status: RideStatus, driver: Uuid, pool_trip_id: Option<Uuid>) -> Model {
+        let now = chrono::DateTime::parse_from_rfc3339("2025-01-01T10:00:00Z").unwrap();
+        Model {
+            id: Uuid::new_v4(),
+            tenant_id: Uuid::nil(),
+            rider_id: Uuid::new_v4(),
+            driver_id: Some(driver),
+            pickup_lat: 12.0,
+            pickup_lon: 77.0,
+            pickup_address: None,
+            dest_lat: 12.1,
+            dest_lon: 77.1,
+            dest_address: None,
+            pending_destination: None,
+            original_destination: None,
+            tier: "economy".into(),
+            payment_method_id: "card".into(),
+            status,
+            pool_trip_id,
+            version: 1,
+            scheduled_for: None,
+            accepted_at: None,
+            pickup_pin: None,
+            arrived_at: None,
+            started_at: None,
+            completed_at: None,
+            cancelled_at: None,
+            cancel_reason: None,
+            cancellation_fee: None,
+            no_show_fee: None,
+            tip_amount: None,
+            tip_idempotency_key: None,
+            tipped_at: None,
+            dispatch_attempts: 1,
+            offer_expires_at: None,
+            currency: Currency::default(),
+            surge_multiplier: 1.0,
+            quote: None,
+            fare: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn second_sequential_offer_cannot_be_accepted_once_the_first_is() {
+        let driver = Uuid::new_v4();
+        let mut first = ride(RideStatus::Assigned, driver, None);
+        let second = ride(RideStatus::Assigned, driver, None);
+
+        // both offers open: either may be accepted
+        assert!(!second.blocks_accept(first.id, None));
+        assert!(!first.blocks_accept(second.id, None));
+
+        first.status = RideStatus::Accepted;
+        assert!(first.blocks_accept(second.id, None));
+        // re-accepting the same ride is left to the transition check
+        assert!(!first.blocks_accept(first.id, None));
+    }
+
+    #[test]
+    fn co_riders_on_the_same_pool_trip_do_not_block() {
+        let (driver, trip) = (Uuid::new_v4(), Uuid::new_v4());
+        let on_board = ride(RideStatus::InProgress, driver, Some(trip));
+        let next = Uuid::new_v4();
+
+        assert!(!on_board.blocks_accept(next, Some(trip)));
+        assert!(on_board.blocks_accept(next, Some(Uuid::new_v4())));
+        assert!(on_board.blocks_accept(next, None));
+    }
+
+    #[test]
+    fn finished_rides_do_not_block() {
+        let driver = Uuid::new_v4();
+        for status in [
+            RideStatus::Completed,
+            RideStatus::Cancelled,
+            RideStatus::RiderNoShow,
+            RideStatus::NoDriverFound,
+        ] {
+            assert!(!ride(status, driver, None).blocks_accept(Uuid::new_v4(), None));
+        }
+    }
+}
@@ -0,0 +1,45 @@
+// src/entity/ride_breadcrumb.rs
+use sea_orm::entity::prelude::*;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+/// One driver GPS fix recorded while a ride was in progress.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "ride_breadcrumb")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub tenant_id: Uuid,
+    pub ride_id: Uuid,
+    pub driver_user_id: Uuid,
+
+    pub lat: f64,
+    pub lon: f64,
+    /// Reported horizontal accuracy (meters), if the device sent one
+    pub accuracy_m: Option<f64>,
+
+    /// Device time of the fix (server time if the device didn't send one)
+    pub recorded_at: DateTimeWithTimeZone,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::ride::Entity",
+        from = "Column::RideId",
+        to   = "super::ride::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Ride,
+}
+
+impl Related<super::ride::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Ride.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,49 @@
+// src/entity/ride_rating.rs
+use sea_orm::entity::prelude::*;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+/// What one side of a completed ride thought of the other.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "ride_rating")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub tenant_id: Uuid,
+    pub ride_id: Uuid,
+
+    pub rater_user_id: Uuid,
+    pub ratee_user_id: Uuid,
+    /// "rider" (rating the driver) or "driver" (rating the rider)
+    pub rater_role: String,
+
+    /// 1..=5
+    pub stars: i32,
+    /// Short labels, e.g. ["clean_car", "friendly"]
+    pub tags: Json,
+    pub comment: Option<String>,
+
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::ride::Entity",
+        from = "Column::RideId",
+        to   = "super::ride::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Ride,
+}
+
+impl Related<super::ride::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Ride.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
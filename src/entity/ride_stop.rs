@@ -0,0 +1,48 @@
+// src/entity/ride_stop.rs
+use sea_orm::entity::prelude::*;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+/// Intermediate stop of a multi-stop ride.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "ride_stop")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub tenant_id: Uuid,
+    pub ride_id: Uuid,
+    /// 1-based order between pickup and destination
+    pub position: i32,
+
+    pub lat: f64,
+    pub lon: f64,
+    pub address: Option<String>,
+
+    /// Set by the driver on reaching / leaving the stop
+    pub arrived_at: Option<DateTimeWithTimeZone>,
+    pub departed_at: Option<DateTimeWithTimeZone>,
+
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::ride::Entity",
+        from = "Column::RideId",
+        to   = "super::ride::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Ride,
+}
+
+impl Related<super::ride::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Ride.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
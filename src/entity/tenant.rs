@@ -15,6 +15,12 @@ pub struct Model {
     #[sea_orm(unique)]
     pub slug: String,
 
+    /// Per-tenant policies, see `types::tenant_settings::TenantSettings`
+    pub settings: Json,
+
+    /// User who created the tenant; the only one allowed to change settings
+    pub owner_user_id: Option<Uuid>,
+
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
 }
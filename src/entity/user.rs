@@ -26,6 +26,14 @@ pub struct Model {
     #[sea_orm(default_value = false)]
     pub locked: bool,
 
+    /// Stars received from drivers as a rider; average = sum / count
+    #[sea_orm(default_value = 0)]
+    pub rider_rating_sum: i64,
+    #[sea_orm(default_value = 0)]
+    pub rider_rating_count: i32,
+    /// Set while the average sits below the tenant's flag threshold
+    pub rider_rating_flagged_at: Option<DateTimeWithTimeZone>,
+
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
 }
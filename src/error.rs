@@ -5,6 +5,8 @@ use serde::Serialize;
 use thiserror::Error;
 use serde_json::json;
 
+use crate::types::ride_status::RideStatus;
+
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
     pub code: String,
@@ -34,6 +36,15 @@ pub enum AppError {
     RateLimited {
         retry_after_seconds: i64,
     },
+
+    #[error("conflict")]
+    Conflict(String),
+
+    #[error("invalid ride transition")]
+    InvalidTransition {
+        from: RideStatus,
+        to: RideStatus,
+    },
 }
 
 impl ResponseError for AppError {
@@ -84,6 +95,25 @@ impl ResponseError for AppError {
                     .insert_header((header::RETRY_AFTER, retry_after_seconds.to_string()))
                     .json(body)
             }
+            AppError::Conflict(msg) => {
+                let body = ErrorResponse {
+                    code: "CONFLICT".into(),
+                    message: msg.clone(),
+                    details: None,
+                };
+                HttpResponse::Conflict().json(body)
+            }
+            AppError::InvalidTransition { from, to } => {
+                let body = ErrorResponse {
+                    code: "INVALID_TRANSITION".into(),
+                    message: format!("Ride cannot move from '{from}' to '{to}'"),
+                    details: Some(json!({
+                        "from": from,
+                        "to": to,
+                    })),
+                };
+                HttpResponse::BadRequest().json(body)
+            }
         }
     }
 }
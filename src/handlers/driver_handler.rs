@@ -2,11 +2,12 @@
 use actix_web::{web, HttpRequest, HttpResponse};
 use sea_orm::DatabaseConnection;
 
-use crate::requests::structures::DriverLocationPayload;
+use crate::requests::structures::{DriverEarningsQuery, DriverLocationPayload};
 use crate::services::driver_service::{
     driver_go_online_service,
     driver_go_offline_service,
     driver_update_location_service,
+    driver_earnings_service,
 };
 
 pub async fn driver_online_handler(
@@ -31,3 +32,11 @@ pub async fn driver_location_handler(
 ) -> Result<HttpResponse, actix_web::Error> {
     driver_update_location_service(req, db, payload).await
 }
+
+pub async fn driver_earnings_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<DriverEarningsQuery>,
+) -> Result<HttpResponse, actix_web::Error> {
+    driver_earnings_service(req, db, query.into_inner()).await
+}
@@ -2,8 +2,12 @@ use actix_web::{web, HttpRequest, HttpResponse};
 use sea_orm::DatabaseConnection;
 use uuid::Uuid;
 
-use crate::requests::structures::{RideRequestPayload, RideListQuery};
+use crate::requests::structures::{
+    RideRequestPayload, RideEstimatePayload, RideListQuery, RideCancelPayload, DriverCancelPayload,
+    RideDestinationPayload, RideRatingPayload, RideStartPayload, RideTipPayload,
+};
 use crate::services::ride_service::{
+    estimate_ride_service,
     request_ride_service,
     get_ride_service,
     list_rides_service,
@@ -11,7 +15,26 @@ use crate::services::ride_service::{
     reject_ride_service,
     start_ride_service,
     complete_ride_service,
+    cancel_ride_service,
+    driver_cancel_ride_service,
+    driver_arrived_service,
+    rider_no_show_service,
+};
+use crate::services::ride_stop_service::{arrive_at_stop_service, depart_stop_service};
+use crate::services::ride_destination_service::{
+    accept_destination_change_service, change_destination_service,
+    decline_destination_change_service,
 };
+use crate::services::ride_rating_service::rate_ride_service;
+use crate::services::ride_tip_service::tip_ride_service;
+
+pub async fn estimate_ride_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    payload: web::Json<RideEstimatePayload>,
+) -> Result<HttpResponse, actix_web::Error> {
+    estimate_ride_service(req, db, payload).await
+}
 
 pub async fn request_ride_handler(
     req: HttpRequest,
@@ -60,9 +83,11 @@ pub async fn start_ride_handler(
     req: HttpRequest,
     db: web::Data<DatabaseConnection>,
     path: web::Path<Uuid>,
+    payload: Option<web::Json<RideStartPayload>>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let ride_id = path.into_inner();
-    start_ride_service(req, db, ride_id).await
+    let payload = payload.unwrap_or_else(|| web::Json(RideStartPayload::default()));
+    start_ride_service(req, db, ride_id, payload).await
 }
 
 pub async fn complete_ride_handler(
@@ -73,3 +98,107 @@ pub async fn complete_ride_handler(
     let ride_id = path.into_inner();
     complete_ride_service(req, db, ride_id).await
 }
+
+pub async fn cancel_ride_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+    payload: web::Json<RideCancelPayload>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let ride_id = path.into_inner();
+    cancel_ride_service(req, db, ride_id, payload).await
+}
+
+pub async fn driver_cancel_ride_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+    payload: web::Json<DriverCancelPayload>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let ride_id = path.into_inner();
+    driver_cancel_ride_service(req, db, ride_id, payload).await
+}
+
+pub async fn driver_arrived_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let ride_id = path.into_inner();
+    driver_arrived_service(req, db, ride_id).await
+}
+
+pub async fn rider_no_show_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let ride_id = path.into_inner();
+    rider_no_show_service(req, db, ride_id).await
+}
+
+pub async fn arrive_at_stop_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<(Uuid, i32)>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (ride_id, position) = path.into_inner();
+    arrive_at_stop_service(req, db, ride_id, position).await
+}
+
+pub async fn depart_stop_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<(Uuid, i32)>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (ride_id, position) = path.into_inner();
+    depart_stop_service(req, db, ride_id, position).await
+}
+
+pub async fn change_destination_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+    payload: web::Json<RideDestinationPayload>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let ride_id = path.into_inner();
+    change_destination_service(req, db, ride_id, payload).await
+}
+
+pub async fn accept_destination_change_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let ride_id = path.into_inner();
+    accept_destination_change_service(req, db, ride_id).await
+}
+
+pub async fn decline_destination_change_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let ride_id = path.into_inner();
+    decline_destination_change_service(req, db, ride_id).await
+}
+
+pub async fn rate_ride_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+    payload: web::Json<RideRatingPayload>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let ride_id = path.into_inner();
+    rate_ride_service(req, db, ride_id, payload).await
+}
+
+pub async fn tip_ride_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+    payload: web::Json<RideTipPayload>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let ride_id = path.into_inner();
+    tip_ride_service(req, db, ride_id, payload).await
+}
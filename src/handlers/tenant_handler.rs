@@ -3,7 +3,20 @@ use actix_web::{web, HttpRequest, HttpResponse};
 use sea_orm::DatabaseConnection;
 
 use crate::dto::tenant::CreateTenantRequest;
-use crate::services::tenant_service::create_tenant_service;
+use crate::requests::structures::{PromoCodePayload, RateCardPayload};
+use crate::services::tenant_service::{
+    create_tenant_service,
+    deactivate_promo_code_service,
+    delete_rate_card_service,
+    get_tenant_settings_service,
+    list_flagged_accounts_service,
+    list_promo_codes_service,
+    list_rate_cards_service,
+    update_tenant_settings_service,
+    upsert_promo_code_service,
+    upsert_rate_card_service,
+};
+use crate::types::tenant_settings::TenantSettings;
 
 pub async fn create_tenant_handler(
     req: HttpRequest,
@@ -12,3 +25,73 @@ pub async fn create_tenant_handler(
 ) -> Result<HttpResponse, actix_web::Error> {
     create_tenant_service(req, body, db).await
 }
+
+pub async fn get_tenant_settings_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, actix_web::Error> {
+    get_tenant_settings_service(req, db).await
+}
+
+pub async fn update_tenant_settings_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    body: web::Json<TenantSettings>,
+) -> Result<HttpResponse, actix_web::Error> {
+    update_tenant_settings_service(req, db, body).await
+}
+
+pub async fn list_rate_cards_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, actix_web::Error> {
+    list_rate_cards_service(req, db).await
+}
+
+pub async fn upsert_rate_card_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<String>,
+    body: web::Json<RateCardPayload>,
+) -> Result<HttpResponse, actix_web::Error> {
+    upsert_rate_card_service(req, db, path.into_inner(), body).await
+}
+
+pub async fn delete_rate_card_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, actix_web::Error> {
+    delete_rate_card_service(req, db, path.into_inner()).await
+}
+
+pub async fn list_flagged_accounts_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, actix_web::Error> {
+    list_flagged_accounts_service(req, db).await
+}
+
+pub async fn list_promo_codes_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, actix_web::Error> {
+    list_promo_codes_service(req, db).await
+}
+
+pub async fn upsert_promo_code_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<String>,
+    body: web::Json<PromoCodePayload>,
+) -> Result<HttpResponse, actix_web::Error> {
+    upsert_promo_code_service(req, db, path.into_inner(), body).await
+}
+
+pub async fn deactivate_promo_code_handler(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, actix_web::Error> {
+    deactivate_promo_code_service(req, db, path.into_inner()).await
+}
@@ -1,4 +1,5 @@
 // /Users/xsm/Documents/workspace/xtras/daw/src/jresponse/mod.rs
 pub mod user_jresponse;
 pub mod tenant_jresponse;
-pub mod ride_jresponse;
\ No newline at end of file
+pub mod ride_jresponse;
+pub mod rating_jresponse;
\ No newline at end of file
@@ -0,0 +1,32 @@
+// src/jresponse/rating_jresponse.rs
+use crate::entity::ride_rating::Model as RideRatingModel;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde_json::{json, Value};
+
+pub fn ride_rating_datum(rating: &RideRatingModel) -> Value {
+    json!({
+        "id": rating.id,
+        "ride_id": rating.ride_id,
+        "rater_user_id": rating.rater_user_id,
+        "ratee_user_id": rating.ratee_user_id,
+        "rater_role": rating.rater_role,
+        "stars": rating.stars,
+        "tags": rating.tags,
+        "comment": rating.comment,
+        "created_at": rating.created_at.to_string(),
+    })
+}
+
+/// Running rating totals of a driver / rider; `average` is null until rated
+pub fn rating_summary_datum(
+    sum: i64,
+    count: i32,
+    flagged_at: Option<DateTimeWithTimeZone>,
+) -> Value {
+    json!({
+        "average": (count > 0).then(|| sum as f64 / count as f64),
+        "count": count,
+        "flagged": flagged_at.is_some(),
+        "flagged_at": flagged_at.map(|t| t.to_string()),
+    })
+}
@@ -1,8 +1,23 @@
 // /Users/xsm/Documents/workspace/xtras/daw/src/jresponse/ride_jresponse.rs
 use crate::entity::ride::Model as RideModel;
+use crate::entity::ride_stop::Model as RideStopModel;
 use serde_json::{json, Value};
 
-pub fn ride_datum(ride: &RideModel) -> Value {
+use crate::types::money::Money;
+
+pub fn ride_stop_datum(stop: &RideStopModel) -> Value {
+    json!({
+        "position": stop.position,
+        "lat": stop.lat,
+        "lon": stop.lon,
+        "address": stop.address,
+        "arrived_at": stop.arrived_at.map(|t| t.to_string()),
+        "departed_at": stop.departed_at.map(|t| t.to_string()),
+    })
+}
+
+/// `stops` are the ride's stops in order (empty for a point-to-point ride)
+pub fn ride_datum(ride: &RideModel, stops: &[RideStopModel]) -> Value {
     json!({
         "id": ride.id,
         "tenant_id": ride.tenant_id,
@@ -13,14 +28,43 @@ pub fn ride_datum(ride: &RideModel) -> Value {
             "lon": ride.pickup_lon,
             "address": ride.pickup_address,
         },
+        "stops": stops.iter().map(ride_stop_datum).collect::<Vec<_>>(),
         "destination": {
             "lat": ride.dest_lat,
             "lon": ride.dest_lon,
             "address": ride.dest_address,
         },
+        "pending_destination": ride.pending_destination,
+        "original_destination": ride.original_destination,
         "tier": ride.tier,
         "payment_method_id": ride.payment_method_id,
+        "currency": ride.currency,
         "status": ride.status,
+        "scheduled_for": ride.scheduled_for.map(|t| t.to_string()),
+        "offer_expires_at": ride.offer_expires_at.map(|t| t.to_string()),
+        "dispatch_attempts": ride.dispatch_attempts,
+        "accepted_at": ride.accepted_at.map(|t| t.to_string()),
+        "arrived_at": ride.arrived_at.map(|t| t.to_string()),
+        "started_at": ride.started_at.map(|t| t.to_string()),
+        "completed_at": ride.completed_at.map(|t| t.to_string()),
+        "cancellation": ride.cancelled_at.map(|t| json!({
+            "cancelled_at": t.to_string(),
+            "reason": ride.cancel_reason,
+            "fee": ride.cancellation_fee.map(|m| Money::new(m, ride.currency.clone())),
+        })),
+        "no_show": ride.no_show_fee.map(|m| json!({
+            "fee": Money::new(m, ride.currency.clone()),
+            "paid_to": ride.driver_id,
+        })),
+        "tip": ride.tip_amount.map(|m| json!({
+            "amount": Money::new(m, ride.currency.clone()),
+            "tipped_at": ride.tipped_at.map(|t| t.to_string()),
+        })),
+        "pricing": {
+            "surge_multiplier": ride.surge_multiplier,
+            "quote": ride.quote,
+        },
+        "fare": ride.fare,
         "created_at": ride.created_at.to_string(),
         "updated_at": ride.updated_at.to_string(),
     })
@@ -1,13 +1,65 @@
 // /Users/xsm/Documents/workspace/xtras/daw/src/jresponse/tenant_jresponse.rs
+use crate::entity::promo_code::Model as PromoCodeModel;
+use crate::entity::rate_card::Model as RateCardModel;
 use crate::entity::tenant::Model as TenantModel;
 use serde_json::{json, Value};
 
+use crate::types::money::Currency;
+use crate::types::tenant_settings::TenantSettings;
+
 pub fn tenant_datum(tenant: &TenantModel) -> Value {
     json!({
         "id": tenant.id,
         "name": tenant.name,
         "slug": tenant.slug,
+        // unreadable settings are shown as stored so the owner can fix them
+        "settings": TenantSettings::from_tenant(tenant)
+            .map(|s| json!(s))
+            .unwrap_or_else(|_| tenant.settings.clone()),
         "created_at": tenant.created_at.to_string(),
         "updated_at": tenant.updated_at.to_string(),
     })
 }
+
+/// Amounts are minor units of `currency` (the tenant's)
+pub fn rate_card_datum(card: &RateCardModel, currency: &Currency) -> Value {
+    json!({
+        "id": card.id,
+        "tenant_id": card.tenant_id,
+        "tier": card.tier,
+        "currency": currency,
+        "base_fare": card.base_fare,
+        "per_km": card.per_km,
+        "per_minute": card.per_minute,
+        "minimum_fare": card.minimum_fare,
+        "booking_fee": card.booking_fee,
+        "wait_per_minute": card.wait_per_minute,
+        "free_wait_mins": card.free_wait_mins,
+        "surge_cap": card.surge_cap,
+        "created_at": card.created_at.to_string(),
+        "updated_at": card.updated_at.to_string(),
+    })
+}
+
+/// Amounts are minor units of `currency` (the tenant's); `uses` = reserved + consumed
+pub fn promo_code_datum(promo: &PromoCodeModel, currency: &Currency, uses: u64) -> Value {
+    json!({
+        "id": promo.id,
+        "code": promo.code,
+        "currency": currency,
+        "kind": promo.kind,
+        "percent": promo.percent,
+        "amount_minor": promo.amount_minor,
+        "max_discount_minor": promo.max_discount_minor,
+        "max_uses": promo.max_uses,
+        "max_uses_per_user": promo.max_uses_per_user,
+        "uses": uses,
+        "valid_from": promo.valid_from.map(|t| t.to_string()),
+        "valid_until": promo.valid_until.map(|t| t.to_string()),
+        "first_ride_only": promo.first_ride_only,
+        "tiers": promo.tiers,
+        "active": promo.active,
+        "created_at": promo.created_at.to_string(),
+        "updated_at": promo.updated_at.to_string(),
+    })
+}
@@ -1,5 +1,6 @@
 // /Users/xsm/Documents/workspace/xtras/daw/src/jresponse/user_jresponse.rs
 use crate::entity::user::Model as UserModel;
+use crate::jresponse::rating_jresponse::rating_summary_datum;
 use serde_json::{json, Value};
 
 pub fn user_minimal_datum(user: &UserModel) -> Value {
@@ -10,6 +11,11 @@ pub fn user_minimal_datum(user: &UserModel) -> Value {
         "tenant_id": user.tenant_id,
         "deleted": user.deleted,
         "locked": user.locked,
+        "rider_rating": rating_summary_datum(
+            user.rider_rating_sum,
+            user.rider_rating_count,
+            user.rider_rating_flagged_at,
+        ),
         "created_at": user.created_at.to_string(),
         "updated_at": user.updated_at.to_string(),
     })
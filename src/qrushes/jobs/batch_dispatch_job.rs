@@ -0,0 +1,174 @@
+// src/qrushes/jobs/batch_dispatch_job.rs
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use qrush::job::Job;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use anyhow::Result;
+use serde_json::json;
+
+use std::collections::HashSet;
+
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+use crate::config::AppConfig;
+use crate::db::init_db;
+use dispatch::matching::assign_batch;
+use dispatch::strategy::Candidate;
+use crate::entity::ride::{
+    Entity as RideEntity,
+    Column as RideColumn,
+};
+use crate::qrushes::jobs::dispatch_ride_job::{
+    find_candidates, give_up_dispatch, offer_ride, record_no_driver,
+};
+use crate::services::tenant_service::load_tenant_settings;
+use crate::types::ride_status::RideStatus;
+use crate::types::tenant_settings::DispatchMode;
+use crate::utils::dispatch_state::{excluded_drivers, take_batch};
+
+/// Closes a tenant's batch window: every ride still `requested` in it is
+/// matched against the nearby drivers in one optimal assignment
+/// (rides x drivers, cost = pickup distance) instead of one by one.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BatchDispatchJob {
+    pub tenant_id: Uuid,
+}
+
+#[async_trait]
+impl Job for BatchDispatchJob {
+    async fn perform(&self) -> Result<()> {
+        let cfg = AppConfig::from_env()?;
+        let db = init_db(&cfg.database.url).await?;
+
+        batch_dispatch(&db, self.tenant_id).await
+    }
+
+    fn name(&self) -> &'static str {
+        "BatchDispatchJob"
+    }
+
+    fn queue(&self) -> &'static str {
+        "dispatch"
+    }
+}
+
+impl BatchDispatchJob {
+    pub fn name() -> &'static str {
+        "BatchDispatchJob"
+    }
+
+    pub fn handler(payload: String) -> BoxFuture<'static, Result<Box<dyn Job>>> {
+        Box::pin(async move {
+            let job: BatchDispatchJob = serde_json::from_str(&payload)?;
+            Ok(Box::new(job) as Box<dyn Job>)
+        })
+    }
+}
+
+async fn batch_dispatch(db: &DatabaseConnection, tenant_id: Uuid) -> Result<()> {
+    let ride_ids = take_batch(tenant_id).await?;
+    if ride_ids.is_empty() {
+        return Ok(());
+    }
+
+    // cancelled / already handled rides drop out of the batch
+    let rides = RideEntity::find()
+        .filter(RideColumn::Id.is_in(ride_ids))
+        .filter(RideColumn::TenantId.eq(tenant_id))
+        .filter(RideColumn::Status.eq(RideStatus::Requested))
+        .all(db)
+        .await?;
+
+    let settings = load_tenant_settings(db, tenant_id).await?;
+    let dispatch = settings.dispatch;
+
+    let mut batch = Vec::new();
+    for ride in rides {
+        if ride.dispatch_attempts >= dispatch.max_dispatch_attempts {
+            give_up_dispatch(db, ride).await?;
+        } else {
+            batch.push(ride);
+        }
+    }
+
+    if batch.is_empty() {
+        return Ok(());
+    }
+
+    // every ride looks for enough drivers to cover the whole batch
+    let mut ride_candidates: Vec<Vec<Candidate>> = Vec::with_capacity(batch.len());
+    let mut excluded_counts: Vec<usize> = Vec::with_capacity(batch.len());
+    for ride in &batch {
+        let excluded: HashSet<Uuid> = excluded_drivers(ride.id).await.unwrap_or_else(|e| {
+            tracing::warn!("failed to load excluded drivers for ride {}: {}", ride.id, e);
+            Default::default()
+        });
+        let (candidates, _) = find_candidates(db, ride, &dispatch, &excluded, batch.len()).await?;
+        ride_candidates.push(candidates);
+        excluded_counts.push(excluded.len());
+    }
+
+    let assignment = assign_batch(&ride_candidates);
+    let matched = assignment.matched();
+    let total_cost_km = assignment.total_cost_km();
+
+    println!(
+        "Batch for tenant {}: {} rides, {} drivers, {} matched ({:.2} km total pickup)",
+        tenant_id, batch.len(), assignment.drivers.len(), matched, total_cost_km
+    );
+
+    let batch_info = json!({
+        "size": batch.len(),
+        "drivers": assignment.drivers.len(),
+        "matched": matched,
+        "total_cost_km": total_cost_km,
+    });
+
+    let rides = batch.into_iter().zip(ride_candidates).zip(excluded_counts);
+    for (i, ((ride, candidates), excluded_count)) in rides.enumerate() {
+        let attempt = ride.dispatch_attempts + 1;
+
+        let result = match assignment.pick(i) {
+            Some((driver_user_id, cost_km)) => {
+                offer_ride(
+                    db,
+                    ride,
+                    &dispatch,
+                    attempt,
+                    &[driver_user_id],
+                    json!({
+                        "mode": DispatchMode::Batch,
+                        "excluded_drivers": excluded_count,
+                        "cost_km": cost_km,
+                        "candidates": candidates,
+                        "batch": batch_info.clone(),
+                    }),
+                )
+                .await
+            }
+            None => {
+                record_no_driver(
+                    db,
+                    ride,
+                    &dispatch,
+                    attempt,
+                    json!({
+                        "mode": DispatchMode::Batch,
+                        "excluded_drivers": excluded_count,
+                        "candidates": candidates.len(),
+                        "batch": batch_info.clone(),
+                    }),
+                )
+                .await
+            }
+        };
+
+        // one bad ride must not stall the rest of the batch
+        if let Err(e) = result {
+            tracing::error!("batch dispatch failed for a ride of tenant {}: {:?}", tenant_id, e);
+        }
+    }
+
+    Ok(())
+}
@@ -2,34 +2,50 @@
 use async_trait::async_trait;
 use futures::future::BoxFuture;
 use qrush::job::Job;
+use qrush::queue::{enqueue, enqueue_in};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use anyhow::{Result, anyhow};
-use serde_json::json;
+use serde_json::{json, Value};
+
+use std::collections::HashSet;
 
 use sea_orm::{
     EntityTrait,
     ColumnTrait,
     QueryFilter,
-    QueryOrder,
-    ActiveModelTrait,
     Set,
     DatabaseConnection,
+    TransactionTrait,
 };
+use sea_orm::sea_query::Expr;
 
 use crate::config::AppConfig;
 use crate::db::init_db;
 use crate::entity::ride::{
     Entity as RideEntity,
     Column as RideColumn,
-    ActiveModel as RideActiveModel,
+    Model as RideModel,
 };
 use crate::entity::driver::{
     Entity as DriverEntity,
     Column as DriverColumn,
+    Model as DriverModel,
 };
+use crate::error::AppError;
+use crate::utils::dispatch_state::{add_to_batch, clear_offers, excluded_drivers, record_offers};
+use crate::utils::redis_geo::nearby_drivers;
+use dispatch::search::{self, DriverIndex, DriverPool};
+use dispatch::strategy::{Candidate, DispatchRide};
+use crate::services::pool_service::{commit_pool_join, find_pool_trip, pool_join_datum, PoolJoin};
+use crate::services::promo_service::release_promo;
+use crate::services::tenant_service::load_tenant_settings;
+use crate::types::tenant_settings::{DispatchMode, DispatchSettings};
 use crate::ws::notify_user;
-use crate::entity::ride_event::ActiveModel as RideEventActiveModel;
+use crate::qrushes::jobs::batch_dispatch_job::BatchDispatchJob;
+use crate::qrushes::jobs::expire_ride_offer_job::ExpireRideOfferJob;
+use crate::services::ride_transition_service::{transition_ride, update_ride, RideTransition};
+use crate::types::ride_status::{RideActor, RideStatus};
 
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -68,6 +84,111 @@ impl DispatchRideJob {
     }
 }
 
+/// Strategy input for one driver row
+fn candidate_from_driver(driver: &DriverModel, distance_km: f64) -> Candidate {
+    let now = chrono::Utc::now();
+
+    Candidate {
+        driver_user_id: driver.user_id,
+        distance_km,
+        idle_secs: driver
+            .idle_since
+            .map(|t| now.signed_duration_since(t).num_seconds().max(0))
+            .unwrap_or(0),
+        acceptance_rate: (driver.offers_received > 0)
+            .then(|| driver.offers_accepted as f64 / driver.offers_received as f64),
+        rating: (driver.rating_count > 0)
+            .then(|| driver.rating_sum as f64 / driver.rating_count as f64),
+    }
+}
+
+pub(crate) fn dispatch_ride_of(ride: &RideModel) -> DispatchRide {
+    DispatchRide {
+        ride_id: ride.id,
+        tenant_id: ride.tenant_id,
+        pickup_lat: ride.pickup_lat,
+        pickup_lon: ride.pickup_lon,
+    }
+}
+
+/// Drivers' live positions: the tenant's Redis GEO set
+struct RedisGeoIndex;
+
+#[async_trait]
+impl DriverIndex for RedisGeoIndex {
+    async fn nearby(
+        &self,
+        tenant_id: Uuid,
+        lat: f64,
+        lon: f64,
+        radius_km: f64,
+        max_results: usize,
+    ) -> Result<Vec<(Uuid, f64)>> {
+        nearby_drivers(tenant_id, lat, lon, radius_km, max_results).await
+    }
+}
+
+/// Eligibility from the `driver` and `ride` tables
+struct DbDriverPool<'a> {
+    db: &'a DatabaseConnection,
+}
+
+#[async_trait]
+impl DriverPool for DbDriverPool<'_> {
+    async fn available(&self, tenant_id: Uuid, nearby: &[(Uuid, f64)]) -> Result<Vec<Candidate>> {
+        let ids: Vec<Uuid> = nearby.iter().map(|(id, _)| *id).collect();
+
+        // GEO set can be stale: confirm against the driver table
+        let drivers = DriverEntity::find()
+            .filter(DriverColumn::TenantId.eq(tenant_id))
+            .filter(DriverColumn::UserId.is_in(ids.clone()))
+            .filter(DriverColumn::IsOnline.eq(true))
+            .all(self.db)
+            .await?;
+
+        // drivers already on an active ride
+        let busy: HashSet<Uuid> = RideEntity::find()
+            .filter(RideColumn::DriverId.is_in(ids))
+            .filter(RideColumn::Status.is_in(RideStatus::DRIVER_BUSY))
+            .all(self.db)
+            .await?
+            .into_iter()
+            .filter_map(|r| r.driver_id)
+            .collect();
+
+        Ok(nearby
+            .iter()
+            .filter(|(id, _)| !busy.contains(id))
+            .filter_map(|(id, distance_km)| {
+                drivers
+                    .iter()
+                    .find(|d| d.user_id == *id)
+                    .map(|d| candidate_from_driver(d, *distance_km))
+            })
+            .collect())
+    }
+}
+
+/// Online, same-tenant, non-excluded, non-busy drivers around pickup
+/// (see `dispatch::search::find_candidates`)
+pub(crate) async fn find_candidates(
+    db: &DatabaseConnection,
+    ride: &RideModel,
+    dispatch: &DispatchSettings,
+    excluded: &HashSet<Uuid>,
+    wanted: usize,
+) -> Result<(Vec<Candidate>, Option<f64>)> {
+    search::find_candidates(
+        &RedisGeoIndex,
+        &DbDriverPool { db },
+        &dispatch_ride_of(ride),
+        dispatch,
+        excluded,
+        wanted,
+    )
+    .await
+}
+
 async fn dispatch_ride(db: &DatabaseConnection, ride_id: Uuid) -> Result<()> {
     // load the ride
     let ride = RideEntity::find()
@@ -76,7 +197,7 @@ async fn dispatch_ride(db: &DatabaseConnection, ride_id: Uuid) -> Result<()> {
         .await?
         .ok_or_else(|| anyhow!("Ride not found: {}", ride_id))?;
 
-    if ride.status != "requested" {
+    if ride.status != RideStatus::Requested {
         println!(
             "Ride {} not in requested state ({}), skipping",
             ride_id, ride.status
@@ -84,44 +205,319 @@ async fn dispatch_ride(db: &DatabaseConnection, ride_id: Uuid) -> Result<()> {
         return Ok(());
     }
 
-    // find online driver (for now: any online driver of any tenant)
-    let driver = DriverEntity::find()
-        .filter(DriverColumn::IsOnline.eq(true))
-        .order_by_asc(DriverColumn::UpdatedAt)
-        .one(db)
-        .await?;
+    let settings = load_tenant_settings(db, ride.tenant_id).await?;
+
+    // pooled rides first try to squeeze into a shared trip already on the road
+    if settings.pool.is_pool_tier(&ride.tier)
+        && ride.dispatch_attempts < settings.dispatch.max_dispatch_attempts
+    {
+        let excluded = excluded_drivers(ride_id).await.unwrap_or_default();
+        match find_pool_trip(db, &ride, &settings.pool, &excluded).await {
+            Ok(Some(join)) => return offer_pool_join(db, ride, &settings.dispatch, join).await,
+            Ok(None) => {}
+            Err(e) => tracing::warn!("pool matching failed for ride {}: {}", ride_id, e),
+        }
+    }
+
+    // batch tenants: park the ride, BatchDispatchJob matches the whole window at once
+    if settings.dispatch.mode == DispatchMode::Batch {
+        return queue_for_batch_dispatch(&ride, &settings.dispatch).await;
+    }
+
+    let max_attempts = settings.dispatch.max_dispatch_attempts;
 
-    let Some(driver) = driver else {
-        println!("No online driver for tenant {}", ride.tenant_id);
+    if ride.dispatch_attempts >= max_attempts {
+        return give_up_dispatch(db, ride).await;
+    }
+
+    let attempt = ride.dispatch_attempts + 1;
+
+    // drivers who already declined / dropped this ride
+    let excluded = excluded_drivers(ride_id).await.unwrap_or_else(|e| {
+        tracing::warn!("failed to load excluded drivers for ride {}: {}", ride_id, e);
+        Default::default()
+    });
+    let excluded_count = excluded.len();
+
+    let wanted = settings.dispatch.offers_per_round();
+
+    // eligible drivers of the ride's tenant, ring by ring, ranked by the tenant's strategy
+    let (candidates, radius_km) =
+        find_candidates(db, &ride, &settings.dispatch, &excluded, wanted).await?;
+
+    let strategy = settings.dispatch.strategy.build(&settings.dispatch.weighted);
+    let ranked = strategy.rank(&dispatch_ride_of(&ride), candidates);
+
+    if ranked.is_empty() {
+        return record_no_driver(
+            db,
+            ride,
+            &settings.dispatch,
+            attempt,
+            json!({
+                "excluded_drivers": excluded_count,
+                "search_radii_km": settings.dispatch.search_radii_km,
+            }),
+        )
+        .await;
+    }
+
+    let offered: Vec<Uuid> = ranked
+        .iter()
+        .take(wanted)
+        .map(|c| c.candidate.driver_user_id)
+        .collect();
+
+    offer_ride(
+        db,
+        ride,
+        &settings.dispatch,
+        attempt,
+        &offered,
+        json!({
+            "mode": settings.dispatch.mode,
+            "radius_km": radius_km,
+            "excluded_drivers": excluded_count,
+            "strategy": strategy.name(),
+            "candidates": ranked,
+        }),
+    )
+    .await
+}
+
+/// Offer a pooled ride to the driver of the trip it joins. The re-planned
+/// route and the offer land together: if another join re-planned the trip
+/// first, dispatch runs again; if the ride moved, the route stays as it was.
+async fn offer_pool_join(
+    db: &DatabaseConnection,
+    ride: RideModel,
+    dispatch: &DispatchSettings,
+    join: PoolJoin,
+) -> Result<()> {
+    let ride_id = ride.id;
+    let attempt = ride.dispatch_attempts + 1;
+    let driver_user_id = join.trip.driver_user_id;
+
+    let txn = db.begin().await?;
+
+    match commit_pool_join(&txn, &ride, &join).await {
+        Ok(()) => {}
+        Err(AppError::Conflict(msg)) => {
+            println!("Ride {} pool join lost race, retrying: {}", ride_id, msg);
+            enqueue(DispatchRideJob { ride_id }).await?;
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let audit = json!({
+        "mode": "pool",
+        "pool_trip_id": join.trip.id,
+        "added_km": join.added_km,
+    });
+
+    // the trip already has its driver: offer to them alone, whatever the tenant's mode
+    let one_driver = DispatchSettings {
+        mode: DispatchMode::Sequential,
+        ..dispatch.clone()
+    };
+    let offered = [driver_user_id];
+
+    // dropping `txn` un-does the join
+    let Some(updated) = assign_offer(&txn, ride, &one_driver, attempt, &offered, audit).await? else {
         return Ok(());
     };
+    txn.commit().await?;
 
-    // IMPORTANT: ride.driver_id should store the DRIVER USER_ID (FK to user)
-    let mut ride_am: RideActiveModel = ride.clone().into();
-    ride_am.driver_id = Set(Some(driver.user_id));
-    ride_am.status = Set("assigned".to_string());
-    let updated = ride_am.update(db).await?;
-
-
-    let ev = RideEventActiveModel {
-        id: sea_orm::ActiveValue::NotSet,
-        tenant_id: Set(updated.tenant_id),
-        ride_id: Set(updated.id),
-        actor_user_id: Set(None), // system
-        kind: Set("ride_assigned".to_string()),
-        payload: Set(Some(json!({
-            "driver_user_id": updated.driver_id,
-        }))),
-        ..Default::default()
+    announce_offer(db, &updated, &one_driver, attempt, &offered).await;
+
+    let _ = notify_user(driver_user_id, "pool_route_updated", pool_join_datum(&join)).await;
+
+    Ok(())
+}
+
+/// Add the ride to its tenant's open batch; the first ride of a window
+/// schedules the BatchDispatchJob that closes it.
+async fn queue_for_batch_dispatch(ride: &RideModel, dispatch: &DispatchSettings) -> Result<()> {
+    let window_secs = dispatch.batch_window_secs.max(1);
+    let opens_window = add_to_batch(ride.tenant_id, ride.id, window_secs).await?;
+
+    if opens_window {
+        let job = BatchDispatchJob { tenant_id: ride.tenant_id };
+        if let Err(e) = enqueue_in(job, window_secs).await {
+            tracing::error!("Failed to enqueue BatchDispatchJob for tenant {}: {:?}", ride.tenant_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Nobody eligible this round: count the attempt, then retry later or give up.
+/// `audit` is merged into the `dispatch_no_driver` event.
+pub(crate) async fn record_no_driver(
+    db: &DatabaseConnection,
+    ride: RideModel,
+    dispatch: &DispatchSettings,
+    attempt: i32,
+    audit: Value,
+) -> Result<()> {
+    let ride_id = ride.id;
+    println!("No nearby driver for ride {} tenant {} (attempt {})", ride_id, ride.tenant_id, attempt);
+
+    let mut payload = json!({ "attempt": attempt });
+    merge_json(&mut payload, audit);
+
+    // versioned like any other ride write, so give_up_dispatch sees the new count
+    let result = update_ride(db, ride, None, "dispatch_no_driver", payload, |am| {
+        am.dispatch_attempts = Set(attempt);
+    })
+    .await;
+
+    let updated = match result {
+        Ok(r) => r,
+        Err(AppError::Conflict(msg)) => {
+            println!("Ride {} no-driver bookkeeping lost race, skipping: {}", ride_id, msg);
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if attempt >= dispatch.max_dispatch_attempts {
+        return give_up_dispatch(db, updated).await;
+    }
+
+    // try again a bit later, drivers may come online / free up
+    let retry = DispatchRideJob { ride_id };
+    if let Err(e) = enqueue_in(retry, dispatch.retry_delay_secs).await {
+        tracing::error!("Failed to enqueue DispatchRideJob retry for {}: {:?}", ride_id, e);
+    }
+
+    Ok(())
+}
+
+/// Offer the ride to `offered` (one driver: assigned outright; several:
+/// broadcast, first accept wins), notify everyone and arm the expiry job.
+/// `audit` (strategy, candidates, ...) is merged into the `ride_assigned` event.
+pub(crate) async fn offer_ride(
+    db: &DatabaseConnection,
+    ride: RideModel,
+    dispatch: &DispatchSettings,
+    attempt: i32,
+    offered: &[Uuid],
+    audit: Value,
+) -> Result<()> {
+    let ride_id = ride.id;
+    let broadcast = dispatch.mode == DispatchMode::Broadcast;
+
+    // offers must be visible before any driver hears about them
+    if broadcast {
+        record_offers(ride_id, offered).await?;
+    }
+
+    let Some(updated) = assign_offer(db, ride, dispatch, attempt, offered, audit).await? else {
+        if broadcast {
+            let _ = clear_offers(ride_id).await;
+        }
+        return Ok(());
     };
-    let _ = ev.insert(db).await;
 
+    announce_offer(db, &updated, dispatch, attempt, offered).await;
+
+    Ok(())
+}
+
+/// The `assigned` transition of `offer_ride`; None if another job / the
+/// rider moved the ride while we were picking a driver
+async fn assign_offer<C: TransactionTrait>(
+    db: &C,
+    ride: RideModel,
+    dispatch: &DispatchSettings,
+    attempt: i32,
+    offered: &[Uuid],
+    audit: Value,
+) -> Result<Option<RideModel>> {
+    let ride_id = ride.id;
+    let broadcast = dispatch.mode == DispatchMode::Broadcast;
+
+    let offer_expires_at =
+        chrono::Utc::now() + chrono::Duration::seconds(dispatch.offer_ttl_secs as i64);
+
+    // Sequential / batch: the one driver is assigned right away.
+    // Broadcast: nobody is until the first /accept claims the ride.
+    let assigned_driver = if broadcast { None } else { offered.first().copied() };
+
+    let mut event_payload = json!({
+        "driver_user_id": assigned_driver,
+        "offered_drivers": offered,
+        "attempt": attempt,
+        "offer_expires_at": offer_expires_at.to_rfc3339(),
+    });
+    merge_json(&mut event_payload, audit);
+
+    // IMPORTANT: ride.driver_id should store the DRIVER USER_ID (FK to user)
+    let result = transition_ride(
+        db,
+        ride,
+        RideTransition {
+            to: RideStatus::Assigned,
+            actor: RideActor::System,
+            actor_user_id: None,
+            event_kind: "ride_assigned",
+            event_payload,
+        },
+        |am| {
+            am.driver_id = Set(assigned_driver);
+            am.dispatch_attempts = Set(attempt);
+            am.offer_expires_at = Set(Some(offer_expires_at.into()));
+        },
+    )
+    .await;
+
+    match result {
+        Ok(r) => Ok(Some(r)),
+        Err(AppError::Conflict(msg)) => {
+            println!("Ride {} dispatch lost race, skipping: {}", ride_id, msg);
+            Ok(None)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// After `assign_offer` committed: stats, notifications and the expiry job
+async fn announce_offer(
+    db: &DatabaseConnection,
+    updated: &RideModel,
+    dispatch: &DispatchSettings,
+    attempt: i32,
+    offered: &[Uuid],
+) {
+    let broadcast = dispatch.mode == DispatchMode::Broadcast;
+    let offer_ttl_secs = dispatch.offer_ttl_secs;
+    let offer_expires_at = updated
+        .offer_expires_at
+        .map(|t| t.to_utc())
+        .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::seconds(offer_ttl_secs as i64));
+    let assigned_driver = updated.driver_id;
 
     println!(
-        "Ride {} assigned to driver_user={} (driver_row={} tenant={})",
-        updated.id, driver.user_id, driver.id, driver.tenant_id
+        "Ride {} offered to {} driver(s) {:?} (mode={:?} tenant={})",
+        updated.id, offered.len(), offered, dispatch.mode, updated.tenant_id
     );
 
+    // acceptance-rate denominator (best-effort)
+    let bumped = DriverEntity::update_many()
+        .col_expr(
+            DriverColumn::OffersReceived,
+            Expr::col(DriverColumn::OffersReceived).add(1),
+        )
+        .filter(DriverColumn::TenantId.eq(updated.tenant_id))
+        .filter(DriverColumn::UserId.is_in(offered.to_vec()))
+        .exec(db)
+        .await;
+    if let Err(e) = bumped {
+        tracing::warn!("failed to bump offers_received for ride {}: {}", updated.id, e);
+    }
+
     // Build a richer payload used by FE for both rider & driver panels
     let common_payload = json!({
         "ride": {
@@ -145,23 +541,99 @@ async fn dispatch_ride(db: &DatabaseConnection, ride_id: Uuid) -> Result<()> {
         }
     });
 
-    // 🔔 Notify rider: they see driver assigned
-    let _ = notify_user(
-        updated.rider_id,
-        "ride_assigned",
-        common_payload.clone(),
-    )
-    .await;
+    // 🔔 Notify rider: they see driver assigned (broadcast: once someone accepts)
+    if !broadcast {
+        let _ = notify_user(
+            updated.rider_id,
+            "ride_assigned",
+            common_payload.clone(),
+        )
+        .await;
+    }
+
+    // 🔔 Notify driver(s) (user_id): they see "incoming ride request" to accept / reject
+    let mut driver_payload = common_payload;
+    driver_payload["offer"] = json!({
+        "expires_at": offer_expires_at.to_rfc3339(),
+        "ttl_secs": offer_ttl_secs,
+        "attempt": attempt,
+        "broadcast": broadcast,
+    });
 
-    // 🔔 Notify driver (user_id): they see "incoming ride request" to accept / reject
-    if let Some(driver_user_id) = updated.driver_id {
+    for driver_user_id in offered {
         let _ = notify_user(
-            driver_user_id,
+            *driver_user_id,
             "ride_assigned_to_driver",
-            common_payload,
+            driver_payload.clone(),
         )
         .await;
     }
 
+    // ⏱ revoke the offer(s) if nobody answers in time
+    let expire = ExpireRideOfferJob {
+        ride_id: updated.id,
+        driver_user_id: assigned_driver,
+        attempt,
+    };
+    if let Err(e) = enqueue_in(expire, offer_ttl_secs).await {
+        tracing::error!("Failed to enqueue ExpireRideOfferJob for {}: {:?}", updated.id, e);
+    }
+}
+
+/// Shallow-merge the keys of `extra` into `target` (both JSON objects)
+fn merge_json(target: &mut Value, extra: Value) {
+    if let (Value::Object(t), Value::Object(e)) = (target, extra) {
+        t.extend(e);
+    }
+}
+
+/// Terminal: nobody took the ride within the tenant's attempt budget
+pub(crate) async fn give_up_dispatch(db: &DatabaseConnection, ride: RideModel) -> Result<()> {
+    let ride_id = ride.id;
+    let attempts = ride.dispatch_attempts;
+
+    let result = transition_ride(
+        db,
+        ride,
+        RideTransition {
+            to: RideStatus::NoDriverFound,
+            actor: RideActor::System,
+            actor_user_id: None,
+            event_kind: "ride_no_driver_found",
+            event_payload: json!({
+                "attempts": attempts,
+            }),
+        },
+        |am| {
+            am.driver_id = Set(None);
+            am.pool_trip_id = Set(None);
+            am.offer_expires_at = Set(None);
+        },
+    )
+    .await;
+
+    let updated = match result {
+        Ok(r) => r,
+        Err(AppError::Conflict(msg)) => {
+            println!("Ride {} give-up lost race, skipping: {}", ride_id, msg);
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    println!("Ride {} ended with no driver after {} attempts", updated.id, attempts);
+    release_promo(db, updated.id).await;
+
+    let _ = notify_user(
+        updated.rider_id,
+        "ride_no_driver_found",
+        json!({
+            "ride_id": updated.id,
+            "status": updated.status,
+            "attempts": attempts,
+        }),
+    )
+    .await;
+
     Ok(())
 }
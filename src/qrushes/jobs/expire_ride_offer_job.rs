@@ -0,0 +1,143 @@
+// src/qrushes/jobs/expire_ride_offer_job.rs
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use qrush::job::Job;
+use qrush::queue::enqueue;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use anyhow::Result;
+use serde_json::json;
+
+use sea_orm::{EntityTrait, Set, DatabaseConnection};
+
+use crate::config::AppConfig;
+use crate::db::init_db;
+use crate::entity::ride::Entity as RideEntity;
+use crate::error::AppError;
+use crate::qrushes::jobs::dispatch_ride_job::DispatchRideJob;
+use crate::services::ride_transition_service::{transition_ride, RideTransition};
+use crate::types::ride_status::{RideActor, RideStatus};
+use crate::utils::dispatch_state::{clear_offers, exclude_driver};
+use crate::ws::notify_user;
+
+/// Fires `offer_ttl_secs` after a ride was offered.
+/// If that exact offer round is still unanswered, it is revoked and the ride
+/// goes back to dispatch without the driver(s) it was offered to.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ExpireRideOfferJob {
+    pub ride_id: Uuid,
+    /// `None` for a broadcast round (offers are tracked in Redis)
+    pub driver_user_id: Option<Uuid>,
+    /// Dispatch attempt the offer belongs to
+    pub attempt: i32,
+}
+
+#[async_trait]
+impl Job for ExpireRideOfferJob {
+    async fn perform(&self) -> Result<()> {
+        let cfg = AppConfig::from_env()?;
+        let db = init_db(&cfg.database.url).await?;
+
+        expire_ride_offer(&db, self).await
+    }
+
+    fn name(&self) -> &'static str {
+        "ExpireRideOfferJob"
+    }
+
+    fn queue(&self) -> &'static str {
+        "dispatch"
+    }
+}
+
+impl ExpireRideOfferJob {
+    pub fn name() -> &'static str {
+        "ExpireRideOfferJob"
+    }
+
+    pub fn handler(payload: String) -> BoxFuture<'static, Result<Box<dyn Job>>> {
+        Box::pin(async move {
+            let job: ExpireRideOfferJob = serde_json::from_str(&payload)?;
+            Ok(Box::new(job) as Box<dyn Job>)
+        })
+    }
+}
+
+async fn expire_ride_offer(db: &DatabaseConnection, job: &ExpireRideOfferJob) -> Result<()> {
+    let Some(ride) = RideEntity::find_by_id(job.ride_id).one(db).await? else {
+        return Ok(());
+    };
+
+    // answered, cancelled or re-offered in the meantime
+    if ride.status != RideStatus::Assigned
+        || ride.driver_id != job.driver_user_id
+        || ride.dispatch_attempts != job.attempt
+    {
+        return Ok(());
+    }
+
+    let result = transition_ride(
+        db,
+        ride,
+        RideTransition {
+            to: RideStatus::Requested,
+            actor: RideActor::System,
+            actor_user_id: None,
+            event_kind: "ride_offer_expired",
+            event_payload: json!({
+                "driver_user_id": job.driver_user_id,
+                "attempt": job.attempt,
+            }),
+        },
+        |am| {
+            am.driver_id = Set(None);
+            am.pool_trip_id = Set(None);
+            am.offer_expires_at = Set(None);
+        },
+    )
+    .await;
+
+    // driver accepted / rejected at the last second
+    let updated = match result {
+        Ok(r) => r,
+        Err(AppError::Conflict(_)) => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+
+    // everyone who sat on this round
+    let holders: Vec<Uuid> = match job.driver_user_id {
+        Some(driver_user_id) => vec![driver_user_id],
+        None => match clear_offers(updated.id).await {
+            Ok(drivers) => drivers.into_iter().collect(),
+            Err(e) => {
+                tracing::warn!("failed to clear offers for ride {}: {}", updated.id, e);
+                Vec::new()
+            }
+        },
+    };
+
+    println!(
+        "Ride {} offer to driver_user(s) {:?} expired (attempt {})",
+        updated.id, holders, job.attempt
+    );
+
+    let payload = json!({
+        "ride_id": updated.id,
+        "status": updated.status,
+    });
+
+    for driver_user_id in &holders {
+        if let Err(e) = exclude_driver(updated.id, *driver_user_id).await {
+            tracing::warn!("failed to exclude driver {} from ride {}: {}", driver_user_id, updated.id, e);
+        }
+        let _ = notify_user(*driver_user_id, "ride_offer_expired", payload.clone()).await;
+    }
+    let _ = notify_user(updated.rider_id, "ride_reassigning", payload).await;
+
+    // 🔹 next candidate
+    if let Err(e) = enqueue(DispatchRideJob { ride_id: updated.id }).await {
+        tracing::error!("Failed to enqueue DispatchRideJob for {}: {:?}", updated.id, e);
+    }
+
+    Ok(())
+}
@@ -1,3 +1,7 @@
 // src/qrushes/jobs/mod.rs
 pub mod notify_user;
-pub mod dispatch_ride_job;
\ No newline at end of file
+pub mod dispatch_ride_job;
+pub mod expire_ride_offer_job;
+pub mod batch_dispatch_job;
+pub mod start_scheduled_ride_job;
+pub mod scheduled_ride_reminder_job;
\ No newline at end of file
@@ -0,0 +1,79 @@
+// src/qrushes/jobs/scheduled_ride_reminder_job.rs
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use qrush::job::Job;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use anyhow::Result;
+use serde_json::json;
+
+use sea_orm::EntityTrait;
+
+use crate::config::AppConfig;
+use crate::db::init_db;
+use crate::entity::ride::Entity as RideEntity;
+use crate::types::ride_status::RideStatus;
+use crate::ws::notify_user;
+
+/// Reminds the rider of an upcoming scheduled pickup.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ScheduledRideReminderJob {
+    pub ride_id: Uuid,
+    pub minutes_before: i64,
+}
+
+#[async_trait]
+impl Job for ScheduledRideReminderJob {
+    async fn perform(&self) -> Result<()> {
+        let cfg = AppConfig::from_env()?;
+        let db = init_db(&cfg.database.url).await?;
+
+        let Some(ride) = RideEntity::find_by_id(self.ride_id).one(&db).await? else {
+            return Ok(());
+        };
+
+        // cancelled since; once dispatching the rider hears about it anyway
+        if ride.status != RideStatus::Scheduled {
+            return Ok(());
+        }
+
+        println!(
+            "Reminding rider {} of scheduled ride {} ({} min)",
+            ride.rider_id, ride.id, self.minutes_before
+        );
+
+        let _ = notify_user(
+            ride.rider_id,
+            "scheduled_ride_reminder",
+            json!({
+                "ride_id": ride.id,
+                "scheduled_for": ride.scheduled_for.map(|t| t.to_string()),
+                "minutes_before": self.minutes_before,
+            }),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "ScheduledRideReminderJob"
+    }
+
+    fn queue(&self) -> &'static str {
+        "default"
+    }
+}
+
+impl ScheduledRideReminderJob {
+    pub fn name() -> &'static str {
+        "ScheduledRideReminderJob"
+    }
+
+    pub fn handler(payload: String) -> BoxFuture<'static, Result<Box<dyn Job>>> {
+        Box::pin(async move {
+            let job: ScheduledRideReminderJob = serde_json::from_str(&payload)?;
+            Ok(Box::new(job) as Box<dyn Job>)
+        })
+    }
+}
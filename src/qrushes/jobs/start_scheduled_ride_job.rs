@@ -0,0 +1,121 @@
+// src/qrushes/jobs/start_scheduled_ride_job.rs
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use qrush::job::Job;
+use qrush::queue::enqueue;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use anyhow::Result;
+use serde_json::json;
+
+use sea_orm::{EntityTrait, Set, DatabaseConnection};
+
+use crate::config::AppConfig;
+use crate::db::init_db;
+use crate::entity::ride::Entity as RideEntity;
+use crate::error::AppError;
+use crate::qrushes::jobs::dispatch_ride_job::DispatchRideJob;
+use crate::services::ride_transition_service::{transition_ride, RideTransition};
+use crate::types::ride_status::{RideActor, RideStatus};
+use crate::utils::surge::{current_multiplier, record_demand};
+use crate::ws::notify_user;
+
+/// Fires `scheduling.dispatch_lead_mins` before a scheduled pickup and hands
+/// the ride to normal dispatch.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct StartScheduledRideJob {
+    pub ride_id: Uuid,
+}
+
+#[async_trait]
+impl Job for StartScheduledRideJob {
+    async fn perform(&self) -> Result<()> {
+        let cfg = AppConfig::from_env()?;
+        let db = init_db(&cfg.database.url).await?;
+
+        start_scheduled_ride(&db, self.ride_id).await
+    }
+
+    fn name(&self) -> &'static str {
+        "StartScheduledRideJob"
+    }
+
+    fn queue(&self) -> &'static str {
+        "dispatch"
+    }
+}
+
+impl StartScheduledRideJob {
+    pub fn name() -> &'static str {
+        "StartScheduledRideJob"
+    }
+
+    pub fn handler(payload: String) -> BoxFuture<'static, Result<Box<dyn Job>>> {
+        Box::pin(async move {
+            let job: StartScheduledRideJob = serde_json::from_str(&payload)?;
+            Ok(Box::new(job) as Box<dyn Job>)
+        })
+    }
+}
+
+async fn start_scheduled_ride(db: &DatabaseConnection, ride_id: Uuid) -> Result<()> {
+    let Some(ride) = RideEntity::find_by_id(ride_id).one(db).await? else {
+        return Ok(());
+    };
+
+    // cancelled (or already started) in the meantime
+    if ride.status != RideStatus::Scheduled {
+        return Ok(());
+    }
+
+    // demand counts now; surge is priced now unless a quote locked it
+    let _ = record_demand(ride.tenant_id, ride.pickup_lat, ride.pickup_lon).await;
+    let surge_multiplier = match ride.quote {
+        Some(_) => ride.surge_multiplier,
+        None => current_multiplier(ride.tenant_id, ride.pickup_lat, ride.pickup_lon)
+            .await
+            .unwrap_or(1.0),
+    };
+
+    let result = transition_ride(
+        db,
+        ride,
+        RideTransition {
+            to: RideStatus::Requested,
+            actor: RideActor::System,
+            actor_user_id: None,
+            event_kind: "scheduled_ride_dispatching",
+            event_payload: json!({ "surge_multiplier": surge_multiplier }),
+        },
+        |am| {
+            am.surge_multiplier = Set(surge_multiplier);
+        },
+    )
+    .await;
+
+    // rider cancelled at the same moment
+    let updated = match result {
+        Ok(r) => r,
+        Err(AppError::Conflict(_)) => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+
+    println!("Scheduled ride {} released to dispatch", updated.id);
+
+    let _ = notify_user(
+        updated.rider_id,
+        "scheduled_ride_dispatching",
+        json!({
+            "ride_id": updated.id,
+            "status": updated.status,
+            "scheduled_for": updated.scheduled_for.map(|t| t.to_string()),
+        }),
+    )
+    .await;
+
+    if let Err(e) = enqueue(DispatchRideJob { ride_id: updated.id }).await {
+        tracing::error!("Failed to enqueue DispatchRideJob for {}: {:?}", updated.id, e);
+    }
+
+    Ok(())
+}
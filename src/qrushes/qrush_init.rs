@@ -8,6 +8,10 @@ use qrush::cron::cron_scheduler::CronScheduler;
 use qrush::routes::metrics_route::qrush_metrics_routes;
 use crate::qrushes::jobs::notify_user::NotifyUser;
 use crate::qrushes::jobs::dispatch_ride_job::DispatchRideJob;
+use crate::qrushes::jobs::expire_ride_offer_job::ExpireRideOfferJob;
+use crate::qrushes::jobs::batch_dispatch_job::BatchDispatchJob;
+use crate::qrushes::jobs::start_scheduled_ride_job::StartScheduledRideJob;
+use crate::qrushes::jobs::scheduled_ride_reminder_job::ScheduledRideReminderJob;
 use crate::qrushes::crons::daily_report_job::DailyReportJob;
 use nanoid::nanoid;
 
@@ -59,6 +63,10 @@ impl QrushInit {
         println!("Registering jobs for integrated mode...");
         register_job(NotifyUser::name(), NotifyUser::handler);
         register_job(DispatchRideJob::name(), DispatchRideJob::handler);
+        register_job(ExpireRideOfferJob::name(), ExpireRideOfferJob::handler);
+        register_job(BatchDispatchJob::name(), BatchDispatchJob::handler);
+        register_job(StartScheduledRideJob::name(), StartScheduledRideJob::handler);
+        register_job(ScheduledRideReminderJob::name(), ScheduledRideReminderJob::handler);
         register_job(DailyReportJob::name(), DailyReportJob::handler);
 
         // Initialize queues in background
@@ -1,9 +1,11 @@
 // /Users/xsm/Documents/workspace/xtras/daw/src/requests/structures.rs
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-#[derive(Debug, Deserialize)]
+use crate::types::promo::PromoKind;
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CoordPayload {
     pub lat: f64,
     pub lon: f64,
@@ -15,6 +17,11 @@ pub struct RideRequestPayload {
     pub pickup: CoordPayload,
     pub destination: CoordPayload,
 
+    /// Intermediate stops, in visiting order
+    #[validate(length(max = 5))]
+    #[serde(default)]
+    pub stops: Vec<CoordPayload>,
+
     #[validate(length(min = 1))]
     pub tier: String,
 
@@ -22,6 +29,161 @@ pub struct RideRequestPayload {
     pub payment_method_id: String,
 
     pub rider_note: Option<String>,
+
+    /// From /rides/estimate; locks the quoted surge
+    pub quote_id: Option<String>,
+
+    /// Book ahead: pickup time (must respect the tenant's scheduling window)
+    pub scheduled_for: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Tenant promo code; reserved now, charged at completion
+    #[validate(length(min = 3, max = 32))]
+    pub promo_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RideEstimatePayload {
+    pub pickup: CoordPayload,
+    pub destination: CoordPayload,
+
+    #[validate(length(max = 5))]
+    #[serde(default)]
+    pub stops: Vec<CoordPayload>,
+
+    #[validate(length(min = 1))]
+    pub tier: String,
+
+    #[validate(length(min = 3, max = 32))]
+    pub promo_code: Option<String>,
+}
+
+/// POST /rides/{id}/start (body optional)
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct RideStartPayload {
+    /// Rider's pickup PIN, when the tenant requires one for the tier
+    #[validate(length(equal = 4))]
+    pub pin: Option<String>,
+}
+
+/// PATCH /rides/{id}/destination
+#[derive(Debug, Deserialize, Validate)]
+pub struct RideDestinationPayload {
+    #[validate(range(min = -90.0, max = 90.0))]
+    pub lat: f64,
+
+    #[validate(range(min = -180.0, max = 180.0))]
+    pub lon: f64,
+
+    pub address: Option<String>,
+}
+
+/// POST /rides/{id}/rating
+#[derive(Debug, Deserialize, Validate)]
+pub struct RideRatingPayload {
+    #[validate(range(min = 1, max = 5))]
+    pub stars: i32,
+
+    #[serde(default)]
+    #[validate(length(max = 5), custom(function = "validate_rating_tags"))]
+    pub tags: Vec<String>,
+
+    #[validate(length(max = 500))]
+    pub comment: Option<String>,
+}
+
+fn validate_rating_tags(tags: &[String]) -> Result<(), ValidationError> {
+    if tags.iter().any(|t| t.trim().is_empty() || t.len() > 32) {
+        return Err(ValidationError::new("tags")
+            .with_message("tags must be 1 to 32 characters".into()));
+    }
+    Ok(())
+}
+
+/// POST /rides/{id}/tip
+#[derive(Debug, Deserialize, Validate)]
+pub struct RideTipPayload {
+    /// Minor units of the ride currency
+    #[validate(range(min = 1))]
+    pub amount_minor: i64,
+
+    /// Generated once per tip on the client; retries send the same key
+    #[validate(length(min = 8, max = 64))]
+    pub idempotency_key: String,
+}
+
+/// PUT /tenants/rate-cards/{tier}; amounts in minor units of the tenant currency
+#[derive(Debug, Deserialize, Validate)]
+pub struct RateCardPayload {
+    #[validate(range(min = 0))]
+    pub base_fare: i64,
+
+    #[validate(range(min = 0))]
+    pub per_km: i64,
+
+    #[validate(range(min = 0))]
+    pub per_minute: i64,
+
+    #[validate(range(min = 0))]
+    #[serde(default)]
+    pub minimum_fare: i64,
+
+    #[validate(range(min = 0))]
+    #[serde(default)]
+    pub booking_fee: i64,
+
+    /// Waiting charge per minute beyond `free_wait_mins` at each stop
+    #[validate(range(min = 0))]
+    #[serde(default)]
+    pub wait_per_minute: i64,
+
+    #[validate(range(min = 0.0))]
+    #[serde(default)]
+    pub free_wait_mins: f64,
+
+    /// Highest surge multiplier charged; omit for no cap
+    #[validate(range(min = 1.0))]
+    pub surge_cap: Option<f64>,
+}
+
+/// PUT /tenants/promo-codes/{code}; amounts in minor units of the tenant currency
+#[derive(Debug, Deserialize, Validate)]
+pub struct PromoCodePayload {
+    pub kind: PromoKind,
+
+    /// Required for `percent` codes
+    #[validate(range(exclusive_min = 0.0, max = 100.0))]
+    pub percent: Option<f64>,
+
+    /// Required for `flat` codes
+    #[validate(range(min = 1))]
+    pub amount_minor: Option<i64>,
+
+    /// Cap on one ride's discount; omit for no cap
+    #[validate(range(min = 1))]
+    pub max_discount_minor: Option<i64>,
+
+    #[validate(range(min = 1))]
+    pub max_uses: Option<i32>,
+
+    #[validate(range(min = 1))]
+    pub max_uses_per_user: Option<i32>,
+
+    pub valid_from: Option<chrono::DateTime<chrono::Utc>>,
+    pub valid_until: Option<chrono::DateTime<chrono::Utc>>,
+
+    #[serde(default)]
+    pub first_ride_only: bool,
+
+    /// Empty = every tier
+    #[serde(default)]
+    pub tiers: Vec<String>,
+
+    #[serde(default = "default_true")]
+    pub active: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 #[derive(Debug, Deserialize)]
@@ -35,8 +197,79 @@ pub struct RideListQuery {
     pub offset: Option<u64>,
 }
 
+/// GET /drivers/earnings; both bounds on `completed_at`, optional
+#[derive(Debug, Deserialize)]
+pub struct DriverEarningsQuery {
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DriverLocationPayload {
     pub lat: f64,
     pub lon: f64,
+    /// Horizontal accuracy of the fix in meters
+    pub accuracy_m: Option<f64>,
+    /// When the device took the fix (defaults to now)
+    pub recorded_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Why a rider cancelled
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiderCancelReason {
+    ChangedPlans,
+    DriverTooFar,
+    WaitTooLong,
+    WrongPickup,
+    BookedByMistake,
+    Other,
+}
+
+impl RiderCancelReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiderCancelReason::ChangedPlans => "changed_plans",
+            RiderCancelReason::DriverTooFar => "driver_too_far",
+            RiderCancelReason::WaitTooLong => "wait_too_long",
+            RiderCancelReason::WrongPickup => "wrong_pickup",
+            RiderCancelReason::BookedByMistake => "booked_by_mistake",
+            RiderCancelReason::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RideCancelPayload {
+    pub reason: RiderCancelReason,
+    pub note: Option<String>,
+}
+
+/// Why a driver dropped a ride they had accepted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriverCancelReason {
+    VehicleIssue,
+    CannotReachPickup,
+    RiderUnreachable,
+    Emergency,
+    Other,
+}
+
+impl DriverCancelReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DriverCancelReason::VehicleIssue => "vehicle_issue",
+            DriverCancelReason::CannotReachPickup => "cannot_reach_pickup",
+            DriverCancelReason::RiderUnreachable => "rider_unreachable",
+            DriverCancelReason::Emergency => "emergency",
+            DriverCancelReason::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DriverCancelPayload {
+    pub reason: DriverCancelReason,
+    pub note: Option<String>,
 }
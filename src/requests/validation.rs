@@ -1,9 +1,237 @@
 // /Users/xsm/Documents/workspace/xtras/daw/src/requests/validation.rs
 use crate::error::AppError;
-use crate::requests::structures::RideRequestPayload;
+use crate::requests::structures::{
+    PromoCodePayload, RateCardPayload, RideDestinationPayload, RideEstimatePayload,
+    RideRatingPayload, RideRequestPayload, RideStartPayload, RideTipPayload,
+};
+use crate::types::pricing_rule::Geofence;
+use crate::types::promo::PromoKind;
+use crate::types::tenant_settings::TenantSettings;
 use validator::Validate;
 
 pub fn validate_ride_request(payload: &RideRequestPayload) -> Result<(), AppError> {
+    validate_payload(payload)?;
+
+    // a quote prices the trip for now; scheduled rides are priced when dispatched
+    if payload.quote_id.is_some() && payload.scheduled_for.is_some() {
+        return Err(AppError::Validation {
+            field: "quote_id".into(),
+            message: "Quotes can't be used for scheduled rides".into(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn validate_ride_estimate(payload: &RideEstimatePayload) -> Result<(), AppError> {
+    validate_payload(payload)
+}
+
+pub fn validate_ride_start(payload: &RideStartPayload) -> Result<(), AppError> {
+    validate_payload(payload)
+}
+
+pub fn validate_ride_destination(payload: &RideDestinationPayload) -> Result<(), AppError> {
+    validate_payload(payload)
+}
+
+pub fn validate_ride_rating(payload: &RideRatingPayload) -> Result<(), AppError> {
+    validate_payload(payload)
+}
+
+pub fn validate_ride_tip(payload: &RideTipPayload) -> Result<(), AppError> {
+    validate_payload(payload)
+}
+
+/// Longest tier name a rate card or the settings may use
+pub const MAX_TIER_LEN: usize = 32;
+
+/// Tier names are short slugs: letters, digits, `-` and `_`
+pub fn validate_tier_name(field: &str, tier: &str) -> Result<(), AppError> {
+    let ok = !tier.is_empty()
+        && tier.len() <= MAX_TIER_LEN
+        && tier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if ok {
+        return Ok(());
+    }
+    Err(AppError::Validation {
+        field: field.into(),
+        message: format!(
+            "Tier names are 1-{MAX_TIER_LEN} letters, digits, '-' or '_' (got '{tier}')"
+        ),
+    })
+}
+
+pub fn validate_rate_card(tier: &str, payload: &RateCardPayload) -> Result<(), AppError> {
+    validate_tier_name("tier", tier)?;
+    validate_payload(payload)
+}
+
+pub fn validate_promo_code(payload: &PromoCodePayload) -> Result<(), AppError> {
+    validate_payload(payload)?;
+
+    let invalid = |field: &str, message: &str| AppError::Validation {
+        field: field.into(),
+        message: message.into(),
+    };
+    match payload.kind {
+        PromoKind::Percent if payload.percent.is_none() => {
+            return Err(invalid("percent", "percent codes need a percent"));
+        }
+        PromoKind::Flat if payload.amount_minor.is_none() => {
+            return Err(invalid("amount_minor", "flat codes need an amount_minor"));
+        }
+        _ => {}
+    }
+    if payload
+        .valid_from
+        .zip(payload.valid_until)
+        .is_some_and(|(from, until)| until <= from)
+    {
+        return Err(invalid("valid_until", "valid_until must be after valid_from"));
+    }
+
+    Ok(())
+}
+
+/// PUT /tenants/settings. `rate_card_tiers`: tiers the tenant has a card
+/// for; once it has any, settings may only name those tiers.
+pub fn validate_tenant_settings(
+    settings: &TenantSettings,
+    rate_card_tiers: &[String],
+) -> Result<(), AppError> {
+    let check = |ok: bool, field: &str, message: &str| -> Result<(), AppError> {
+        if ok {
+            return Ok(());
+        }
+        Err(AppError::Validation {
+            field: field.into(),
+            message: message.into(),
+        })
+    };
+
+    let c = &settings.cancellation;
+    check(c.grace_period_secs >= 0, "cancellation.grace_period_secs", "must not be negative")?;
+    check(c.fee_minor >= 0, "cancellation.fee_minor", "must not be negative")?;
+    check(
+        c.waive_if_driver_farther_than_km >= 0.0,
+        "cancellation.waive_if_driver_farther_than_km",
+        "must not be negative",
+    )?;
+
+    let d = &settings.dispatch;
+    check(d.broadcast_size >= 1, "dispatch.broadcast_size", "must be at least 1")?;
+    check(d.batch_window_secs >= 1, "dispatch.batch_window_secs", "must be at least 1")?;
+    check(
+        !d.search_radii_km.is_empty()
+            && d.search_radii_km.iter().all(|r| *r > 0.0)
+            && d.search_radii_km.windows(2).all(|w| w[0] < w[1]),
+        "dispatch.search_radii_km",
+        "must be positive and increasing",
+    )?;
+    check(d.max_candidates_per_ring >= 1, "dispatch.max_candidates_per_ring", "must be at least 1")?;
+    check(d.offer_ttl_secs >= 1, "dispatch.offer_ttl_secs", "must be at least 1")?;
+    check(d.max_dispatch_attempts >= 1, "dispatch.max_dispatch_attempts", "must be at least 1")?;
+    let w = &d.weighted;
+    check(
+        w.eta_weight >= 0.0 && w.acceptance_weight >= 0.0 && w.rating_weight >= 0.0,
+        "dispatch.weighted",
+        "weights must not be negative",
+    )?;
+    check(w.avg_speed_kmh > 0.0, "dispatch.weighted.avg_speed_kmh", "must be positive")?;
+    check(w.max_eta_mins > 0.0, "dispatch.weighted.max_eta_mins", "must be positive")?;
+
+    let p = &settings.pricing;
+    check(p.quote_ttl_secs >= 1, "pricing.quote_ttl_secs", "must be at least 1")?;
+    check(p.avg_speed_kmh > 0.0, "pricing.avg_speed_kmh", "must be positive")?;
+    check(
+        (-14 * 60..=14 * 60).contains(&p.utc_offset_minutes),
+        "pricing.utc_offset_minutes",
+        "must be within ±14 hours",
+    )?;
+    for rule in &p.rules {
+        check(!rule.name.trim().is_empty(), "pricing.rules", "every rule needs a name")?;
+        let zones_ok = [&rule.pickup_zone, &rule.dropoff_zone]
+            .into_iter()
+            .flatten()
+            .all(|z| match z {
+                Geofence::Circle { radius_km, .. } => *radius_km > 0.0,
+                Geofence::Polygon { points } => points.len() >= 3,
+            });
+        check(zones_ok, "pricing.rules", "zones need a positive radius or at least 3 points")?;
+    }
+    let m = &p.trip_meter;
+    check(m.max_accuracy_m > 0.0, "pricing.trip_meter.max_accuracy_m", "must be positive")?;
+    check(m.max_speed_kmh > 0.0, "pricing.trip_meter.max_speed_kmh", "must be positive")?;
+    check(m.min_move_m >= 0.0, "pricing.trip_meter.min_move_m", "must not be negative")?;
+    check(
+        (0.0..=1.0).contains(&m.min_coverage),
+        "pricing.trip_meter.min_coverage",
+        "must be between 0 and 1",
+    )?;
+
+    let s = &settings.scheduling;
+    check(s.max_days_ahead >= 0, "scheduling.max_days_ahead", "must not be negative")?;
+    check(s.min_lead_mins >= 0, "scheduling.min_lead_mins", "must not be negative")?;
+    check(s.dispatch_lead_mins >= 0, "scheduling.dispatch_lead_mins", "must not be negative")?;
+    check(
+        s.reminder_mins_before.iter().all(|m| *m > 0),
+        "scheduling.reminder_mins_before",
+        "must be positive",
+    )?;
+    check(s.zone_radius_km > 0.0, "scheduling.zone_radius_km", "must be positive")?;
+    check(s.overlap_window_mins >= 0, "scheduling.overlap_window_mins", "must not be negative")?;
+
+    check(
+        settings.trip_changes.max_destination_shift_km >= 0.0,
+        "trip_changes.max_destination_shift_km",
+        "must not be negative",
+    )?;
+
+    let a = &settings.arrival;
+    check(a.geofence_m > 0.0, "arrival.geofence_m", "must be positive")?;
+    check(a.no_show_after_mins >= 0, "arrival.no_show_after_mins", "must not be negative")?;
+    check(a.no_show_fee_minor >= 0, "arrival.no_show_fee_minor", "must not be negative")?;
+
+    let pin = &settings.pickup_pin;
+    check(pin.max_attempts >= 1, "pickup_pin.max_attempts", "must be at least 1")?;
+    check(pin.lockout_secs >= 0, "pickup_pin.lockout_secs", "must not be negative")?;
+
+    let pool = &settings.pool;
+    check(pool.max_riders >= 1, "pool.max_riders", "must be at least 1")?;
+    check(pool.max_detour_ratio >= 1.0, "pool.max_detour_ratio", "must be at least 1")?;
+    check(pool.max_pickup_km > 0.0, "pool.max_pickup_km", "must be positive")?;
+
+    let r = &settings.ratings;
+    check(r.window_hours >= 0, "ratings.window_hours", "must not be negative")?;
+    check((1.0..=5.0).contains(&r.flag_below), "ratings.flag_below", "must be between 1 and 5")?;
+    check(r.min_ratings_to_flag >= 1, "ratings.min_ratings_to_flag", "must be at least 1")?;
+
+    let t = &settings.tips;
+    check(t.window_hours >= 0, "tips.window_hours", "must not be negative")?;
+    check(t.max_amount_minor >= 0, "tips.max_amount_minor", "must not be negative")?;
+    check(
+        t.max_percent_of_fare.is_none_or(|pct| pct > 0.0),
+        "tips.max_percent_of_fare",
+        "must be positive",
+    )?;
+
+    for tier in settings.referenced_tiers() {
+        validate_tier_name("tiers", tier)?;
+        if !rate_card_tiers.is_empty() && !rate_card_tiers.iter().any(|t| t == tier) {
+            return Err(AppError::Validation {
+                field: "tiers".into(),
+                message: format!("Unknown tier '{tier}'; add a rate card for it first"),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_payload<T: Validate>(payload: &T) -> Result<(), AppError> {
     if let Err(e) = payload.validate() {
         if let Some((field, errors)) = e.field_errors().iter().next() {
             let msg = errors[0]
@@ -24,3 +252,107 @@ pub fn validate_ride_request(payload: &RideRequestPayload) -> Result<(), AppErro
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::requests::structures::CoordPayload;
+
+    fn tiers(names: &[&str]) -> Vec<String> {
+        names.iter().map(|t| t.to_string()).collect()
+    }
+
+    fn ride_request() -> RideRequestPayload {
+        let at = |lat: f64, lon: f64| CoordPayload {
+            lat,
+            lon,
+            address: None,
+        };
+        RideRequestPayload {
+            pickup: at(12.0, 77.0),
+            destination: at(12.1, 77.1),
+            stops: vec![],
+            tier: "economy".into(),
+            payment_method_id: "card".into(),
+            rider_note: None,
+            quote_id: None,
+            scheduled_for: None,
+            promo_code: None,
+        }
+    }
+
+    #[test]
+    fn tier_names_are_short_slugs() {
+        for tier in ["economy", "xl-plus", "bike_2"] {
+            assert!(validate_tier_name("tier", tier).is_ok(), "{tier}");
+        }
+        let too_long = "a".repeat(MAX_TIER_LEN + 1);
+        for tier in ["", "   ", " economy", "premium cars", "ü", too_long.as_str()] {
+            assert!(validate_tier_name("tier", tier).is_err(), "{tier:?}");
+        }
+    }
+
+    #[test]
+    fn quote_and_scheduled_pickup_are_exclusive() {
+        let tomorrow = chrono::Utc::now() + chrono::Duration::days(1);
+
+        let quoted = RideRequestPayload {
+            quote_id: Some("quote".into()),
+            ..ride_request()
+        };
+        assert!(validate_ride_request(&quoted).is_ok());
+
+        let scheduled = RideRequestPayload {
+            scheduled_for: Some(tomorrow),
+            ..ride_request()
+        };
+        assert!(validate_ride_request(&scheduled).is_ok());
+
+        let both = RideRequestPayload {
+            quote_id: Some("quote".into()),
+            scheduled_for: Some(tomorrow),
+            ..ride_request()
+        };
+        let err = validate_ride_request(&both).unwrap_err();
+        assert!(matches!(err, AppError::Validation { ref field, .. } if field == "quote_id"));
+    }
+
+    #[test]
+    fn default_settings_save_without_a_pool_rate_card() {
+        let settings = TenantSettings::default();
+
+        assert!(validate_tenant_settings(&settings, &tiers(&["economy"])).is_ok());
+        assert!(validate_tenant_settings(&settings, &[]).is_ok());
+    }
+
+    #[test]
+    fn renamed_pool_tier_needs_a_rate_card() {
+        let mut settings = TenantSettings::default();
+        settings.pool.tier = "shared".into();
+
+        let err = validate_tenant_settings(&settings, &tiers(&["economy"])).unwrap_err();
+        assert!(matches!(err, AppError::Validation { ref field, .. } if field == "tiers"));
+        assert!(validate_tenant_settings(&settings, &tiers(&["economy", "shared"])).is_ok());
+    }
+
+    #[test]
+    fn pickup_pin_tiers_are_checked_against_rate_cards() {
+        let mut settings = TenantSettings::default();
+        settings.pickup_pin.tiers = tiers(&["premium"]);
+
+        assert!(validate_tenant_settings(&settings, &tiers(&["economy"])).is_err());
+        // no rate cards yet: any tier name is accepted
+        assert!(validate_tenant_settings(&settings, &[]).is_ok());
+    }
+
+    #[test]
+    fn out_of_range_numbers_name_the_field() {
+        let mut settings = TenantSettings::default();
+        settings.pool.max_detour_ratio = 0.5;
+
+        let err = validate_tenant_settings(&settings, &[]).unwrap_err();
+        assert!(
+            matches!(err, AppError::Validation { ref field, .. } if field == "pool.max_detour_ratio")
+        );
+    }
+}
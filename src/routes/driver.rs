@@ -19,6 +19,7 @@ pub fn configure_driver_routes(cfg: &mut web::ServiceConfig, db: web::Data<Datab
             .app_data(db.clone())
             .route("/online", web::post().to(driver_handler::driver_online_handler))
             .route("/offline", web::post().to(driver_handler::driver_offline_handler))
-            .route("/location", web::post().to(driver_handler::driver_location_handler)),
+            .route("/location", web::post().to(driver_handler::driver_location_handler))
+            .route("/earnings", web::get().to(driver_handler::driver_earnings_handler)),
     );
 }
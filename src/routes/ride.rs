@@ -17,11 +17,23 @@ pub fn configure_ride_routes(cfg: &mut web::ServiceConfig, db: web::Data<Databas
             .wrap(auth)
             .app_data(db.clone())
             .route("", web::get().to(ride_handler::list_rides_handler))
+            .route("/estimate", web::post().to(ride_handler::estimate_ride_handler))
             .route("/request", web::post().to(ride_handler::request_ride_handler))
             .route("/{id}", web::get().to(ride_handler::get_ride_handler))
             .route("/{id}/accept", web::post().to(ride_handler::accept_ride_handler))
             .route("/{id}/reject", web::post().to(ride_handler::reject_ride_handler))
+            .route("/{id}/arrived", web::post().to(ride_handler::driver_arrived_handler))
+            .route("/{id}/no-show", web::post().to(ride_handler::rider_no_show_handler))
             .route("/{id}/start", web::post().to(ride_handler::start_ride_handler))
-            .route("/{id}/complete", web::post().to(ride_handler::complete_ride_handler)),
+            .route("/{id}/complete", web::post().to(ride_handler::complete_ride_handler))
+            .route("/{id}/cancel", web::post().to(ride_handler::cancel_ride_handler))
+            .route("/{id}/driver-cancel", web::post().to(ride_handler::driver_cancel_ride_handler))
+            .route("/{id}/rating", web::post().to(ride_handler::rate_ride_handler))
+            .route("/{id}/tip", web::post().to(ride_handler::tip_ride_handler))
+            .route("/{id}/destination", web::patch().to(ride_handler::change_destination_handler))
+            .route("/{id}/destination/accept", web::post().to(ride_handler::accept_destination_change_handler))
+            .route("/{id}/destination/decline", web::post().to(ride_handler::decline_destination_change_handler))
+            .route("/{id}/stops/{position}/arrive", web::post().to(ride_handler::arrive_at_stop_handler))
+            .route("/{id}/stops/{position}/depart", web::post().to(ride_handler::depart_stop_handler)),
     );
 }
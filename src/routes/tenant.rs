@@ -18,6 +18,15 @@ pub fn configure_tenant_routes(cfg: &mut web::ServiceConfig, db: web::Data<Datab
         web::scope("/tenants")
             .wrap(auth)
             .app_data(db.clone())
-            .route("", web::post().to(tenant_handler::create_tenant_handler)),
+            .route("", web::post().to(tenant_handler::create_tenant_handler))
+            .route("/settings", web::get().to(tenant_handler::get_tenant_settings_handler))
+            .route("/settings", web::put().to(tenant_handler::update_tenant_settings_handler))
+            .route("/flagged-accounts", web::get().to(tenant_handler::list_flagged_accounts_handler))
+            .route("/rate-cards", web::get().to(tenant_handler::list_rate_cards_handler))
+            .route("/rate-cards/{tier}", web::put().to(tenant_handler::upsert_rate_card_handler))
+            .route("/rate-cards/{tier}", web::delete().to(tenant_handler::delete_rate_card_handler))
+            .route("/promo-codes", web::get().to(tenant_handler::list_promo_codes_handler))
+            .route("/promo-codes/{code}", web::put().to(tenant_handler::upsert_promo_code_handler))
+            .route("/promo-codes/{code}", web::delete().to(tenant_handler::deactivate_promo_code_handler)),
     );
 }
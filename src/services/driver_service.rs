@@ -1,7 +1,10 @@
 // /Users/xsm/Documents/workspace/xtras/daw/src/services/driver_service.rs
 use actix_web::{web, HttpRequest, HttpResponse, Error};
+use std::collections::BTreeMap;
+
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, TryIntoModel,
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, Set,
+    TryIntoModel,
 };
 use serde_json::json;
 use uuid::Uuid;
@@ -13,11 +16,67 @@ use crate::entity::driver::{
     Model as DriverModel,
 };
 use crate::entity::user::{Model as UserModel};
-use crate::requests::structures::DriverLocationPayload;
+use crate::jresponse::rating_jresponse::rating_summary_datum;
+use crate::requests::structures::{DriverEarningsQuery, DriverLocationPayload};
 use crate::utils::current_user::get_current_user;
 use crate::utils::redis_geo::{remove_driver_location, upsert_driver_location};
 use crate::utils::surge::record_supply;
+use crate::utils::trip_meter::within_trip;
 use crate::entity::driver_event::ActiveModel as DriverEventActiveModel;
+use crate::entity::ride::{Column as RideColumn, Entity as RideEntity};
+use crate::entity::ride_breadcrumb::ActiveModel as BreadcrumbActiveModel;
+use crate::types::fare::RideFare;
+use crate::types::money::Money;
+use crate::types::ride_status::RideStatus;
+
+/// Best-effort: save a location fix against every ride the driver has in
+/// progress (a pooled driver carries several; each meters its own trip)
+async fn record_breadcrumb(
+    db: &DatabaseConnection,
+    tenant_id: Uuid,
+    driver_user_id: Uuid,
+    payload: &DriverLocationPayload,
+) {
+    let rides = RideEntity::find()
+        .filter(RideColumn::TenantId.eq(tenant_id))
+        .filter(RideColumn::DriverId.eq(driver_user_id))
+        .filter(RideColumn::Status.eq(RideStatus::InProgress))
+        .all(db)
+        .await;
+
+    let rides = match rides {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::warn!("breadcrumb ride lookup failed for driver {}: {}", driver_user_id, e);
+            return;
+        }
+    };
+
+    // device clocks can't claim the future, nor a time before the trip started
+    let now = chrono::Utc::now();
+    let recorded_at = payload.recorded_at.unwrap_or(now).min(now);
+
+    for ride in rides
+        .iter()
+        .filter(|r| within_trip(recorded_at.into(), r.started_at, None))
+    {
+        let crumb = BreadcrumbActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            tenant_id: Set(tenant_id),
+            ride_id: Set(ride.id),
+            driver_user_id: Set(driver_user_id),
+            lat: Set(payload.lat),
+            lon: Set(payload.lon),
+            accuracy_m: Set(payload.accuracy_m),
+            recorded_at: Set(recorded_at.into()),
+            created_at: Set(now.into()),
+        };
+
+        if let Err(e) = crumb.insert(db).await {
+            tracing::warn!("failed to record breadcrumb for ride {}: {}", ride.id, e);
+        }
+    }
+}
 
 /// Helper to load the driver model by (tenant_id, user_id)
 async fn load_driver_model(
@@ -67,8 +126,13 @@ pub async fn driver_go_online_service(
 
     let driver: DriverModel = if let Some(d) = existing {
         // UPDATE path
+        let was_online = d.is_online;
         let mut am: DriverActiveModel = d.into();
         am.is_online = Set(true);
+        if !was_online {
+            // idle clock starts when the driver comes online
+            am.idle_since = Set(Some(chrono::Utc::now().into()));
+        }
         am.lat = Set(Some(payload.lat));
         am.lon = Set(Some(payload.lon));
 
@@ -89,6 +153,7 @@ pub async fn driver_go_online_service(
             is_online: Set(true),
             lat: Set(Some(payload.lat)),
             lon: Set(Some(payload.lon)),
+            idle_since: Set(Some(chrono::Utc::now().into())),
             ..Default::default()
         };
 
@@ -137,6 +202,11 @@ pub async fn driver_go_online_service(
             "is_online": driver.is_online,
             "lat": driver.lat,
             "lon": driver.lon,
+            "rating": rating_summary_datum(
+                driver.rating_sum,
+                driver.rating_count,
+                driver.rating_flagged_at,
+            ),
         }
     })))
 }
@@ -267,6 +337,9 @@ pub async fn driver_update_location_service(
         tracing::warn!("failed to upsert driver location in redis: {}", e);
     }
 
+    // on a trip: keep the fix as a breadcrumb for the metered fare
+    record_breadcrumb(db.get_ref(), tenant_id, user.id, &payload).await;
+
     Ok(HttpResponse::Ok().json(json!({
         "status": 200,
         "code": 200,
@@ -281,3 +354,112 @@ pub async fn driver_update_location_service(
         }
     })))
 }
+
+/// One currency's share of a driver's earnings
+struct EarningsTotals {
+    rides: u64,
+    fares: Money,
+    no_show_fees: Money,
+    tips: Money,
+    /// Completed rides whose stored fare can't be read (left out of `fares`)
+    unreadable_fares: u64,
+}
+
+/// GET /drivers/earnings
+///
+/// Completed rides count by `completed_at` (their fare plus any tip), no-shows
+/// by `arrived_at` (the no-show fee). Totals are per currency.
+pub async fn driver_earnings_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    query: DriverEarningsQuery,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+
+    ensure_driver(&user)?;
+
+    let tenant_id = user
+        .tenant_id
+        .ok_or_else(|| actix_web::error::ErrorForbidden("User has no primary tenant"))?;
+
+    let mut completed = Condition::all().add(RideColumn::Status.eq(RideStatus::Completed));
+    let mut no_show = Condition::all().add(RideColumn::Status.eq(RideStatus::RiderNoShow));
+    if let Some(from) = query.from {
+        completed = completed.add(RideColumn::CompletedAt.gte(from));
+        no_show = no_show.add(RideColumn::ArrivedAt.gte(from));
+    }
+    if let Some(to) = query.to {
+        completed = completed.add(RideColumn::CompletedAt.lt(to));
+        no_show = no_show.add(RideColumn::ArrivedAt.lt(to));
+    }
+
+    let rides = RideEntity::find()
+        .filter(RideColumn::TenantId.eq(tenant_id))
+        .filter(RideColumn::DriverId.eq(user.id))
+        .filter(Condition::any().add(completed).add(no_show))
+        .all(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let mut by_currency: BTreeMap<String, EarningsTotals> = BTreeMap::new();
+    for ride in &rides {
+        let currency = ride.currency.clone();
+        let totals = by_currency
+            .entry(currency.code().to_string())
+            .or_insert_with(|| EarningsTotals {
+                rides: 0,
+                fares: Money::zero(currency.clone()),
+                no_show_fees: Money::zero(currency.clone()),
+                tips: Money::zero(currency.clone()),
+                unreadable_fares: 0,
+            });
+
+        totals.rides += 1;
+        if ride.status == RideStatus::Completed {
+            let fare = ride.fare.clone().map(serde_json::from_value::<RideFare>);
+            match fare {
+                Some(Ok(f)) => {
+                    totals.fares = totals.fares.clone()
+                        + Money::new(f.breakdown.driver_fare().amount_minor, currency.clone());
+                }
+                // never report a fare we can't read as zero earnings
+                other => {
+                    tracing::warn!(
+                        "unreadable fare on completed ride {}: {:?}",
+                        ride.id,
+                        other.map(|r| r.err())
+                    );
+                    totals.unreadable_fares += 1;
+                }
+            }
+        }
+        totals.no_show_fees = totals.no_show_fees.clone()
+            + Money::new(ride.no_show_fee.unwrap_or(0), currency.clone());
+        totals.tips = totals.tips.clone() + Money::new(ride.tip_amount.unwrap_or(0), currency);
+    }
+
+    let earnings: Vec<_> = by_currency
+        .into_values()
+        .map(|t| {
+            json!({
+                "rides": t.rides,
+                "total": t.fares.clone() + t.no_show_fees.clone() + t.tips.clone(),
+                "fares": t.fares,
+                "no_show_fees": t.no_show_fees,
+                "tips": t.tips,
+                "unreadable_fares": t.unreadable_fares,
+            })
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Driver earnings",
+        "data": {
+            "from": query.from.map(|t| t.to_rfc3339()),
+            "to": query.to.map(|t| t.to_rfc3339()),
+            "earnings": earnings,
+        }
+    })))
+}
@@ -5,4 +5,12 @@ pub mod user_service;
 pub mod ride_service;
 pub mod driver_service;
 pub mod debug_ws_service;
-pub mod event_service;
\ No newline at end of file
+pub mod event_service;
+pub mod ride_transition_service;
+pub mod pricing_service;
+pub mod ride_stop_service;
+pub mod ride_destination_service;
+pub mod pool_service;
+pub mod ride_rating_service;
+pub mod ride_tip_service;
+pub mod promo_service;
@@ -0,0 +1,321 @@
+// src/services/pool_service.rs
+use std::collections::HashSet;
+
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set,
+    TransactionTrait,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use dispatch::geo::haversine_km;
+use dispatch::pool::{fare_share, plan_insertion, PoolSettings, PoolShare, Waypoint, WaypointKind};
+
+use crate::entity::driver::{Column as DriverColumn, Entity as DriverEntity};
+use crate::entity::pool_trip::{
+    ActiveModel as PoolTripActiveModel,
+    Column as PoolTripColumn,
+    Model as PoolTripModel,
+};
+use crate::entity::prelude::PoolTrip as PoolTripEntity;
+use crate::entity::ride::{Column as RideColumn, Entity as RideEntity, Model as RideModel};
+use crate::error::AppError;
+use crate::types::ride_status::RideStatus;
+
+/// A pooled ride's pickup and drop-off
+fn waypoints_of(ride: &RideModel) -> (Waypoint, Waypoint) {
+    (
+        Waypoint {
+            ride_id: ride.id,
+            kind: WaypointKind::Pickup,
+            lat: ride.pickup_lat,
+            lon: ride.pickup_lon,
+        },
+        Waypoint {
+            ride_id: ride.id,
+            kind: WaypointKind::Dropoff,
+            lat: ride.dest_lat,
+            lon: ride.dest_lon,
+        },
+    )
+}
+
+fn parse_route(trip: &PoolTripModel) -> Vec<Waypoint> {
+    serde_json::from_value(trip.route.clone()).unwrap_or_default()
+}
+
+/// Rides the trip's driver holds or held on this trip. A ride that was
+/// rejected / dropped has lost its driver and falls off the trip.
+async fn rides_on_trip<C: ConnectionTrait>(
+    db: &C,
+    trip: &PoolTripModel,
+) -> Result<Vec<RideModel>, AppError> {
+    Ok(RideEntity::find()
+        .filter(RideColumn::PoolTripId.eq(trip.id))
+        .filter(RideColumn::DriverId.eq(trip.driver_user_id))
+        .all(db)
+        .await?)
+}
+
+/// Whether `w` still lies ahead of the driver
+fn is_pending(w: &Waypoint, rides: &[RideModel]) -> bool {
+    rides.iter().any(|r| {
+        r.id == w.ride_id
+            && RideStatus::DRIVER_BUSY.contains(&r.status)
+            && (w.kind == WaypointKind::Dropoff || r.status != RideStatus::InProgress)
+    })
+}
+
+/// Whether `w` was reached by a rider who actually rode
+fn is_done(w: &Waypoint, rides: &[RideModel]) -> bool {
+    rides.iter().any(|r| {
+        r.id == w.ride_id
+            && match w.kind {
+                WaypointKind::Pickup => {
+                    matches!(r.status, RideStatus::InProgress | RideStatus::Completed)
+                }
+                WaypointKind::Dropoff => r.status == RideStatus::Completed,
+            }
+    })
+}
+
+/// A trip a pooled ride can join, with its new full route.
+pub struct PoolJoin {
+    pub trip: PoolTripModel,
+    /// Waypoints already reached, then the new remaining plan
+    pub route: Vec<Waypoint>,
+    /// Extra driving the new ride adds (km)
+    pub added_km: f64,
+}
+
+/// The active pooled trip `ride` fits best (least extra driving), if any.
+///
+/// Trips whose driver is in `excluded`, is farther than `max_pickup_km`
+/// from the pickup, or would break anyone's detour / seat limits are skipped.
+pub async fn find_pool_trip<C: ConnectionTrait>(
+    db: &C,
+    ride: &RideModel,
+    settings: &PoolSettings,
+    excluded: &HashSet<Uuid>,
+) -> Result<Option<PoolJoin>, AppError> {
+    // trips with a pooled ride still under way
+    let trip_ids: HashSet<Uuid> = RideEntity::find()
+        .filter(RideColumn::TenantId.eq(ride.tenant_id))
+        .filter(RideColumn::Tier.eq(settings.tier.clone()))
+        .filter(RideColumn::PoolTripId.is_not_null())
+        .filter(RideColumn::Status.is_in(RideStatus::DRIVER_BUSY))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|r| r.pool_trip_id)
+        .collect();
+    if trip_ids.is_empty() {
+        return Ok(None);
+    }
+
+    let trips = PoolTripEntity::find()
+        .filter(PoolTripColumn::Id.is_in(trip_ids))
+        .all(db)
+        .await?;
+
+    let (pickup, dropoff) = waypoints_of(ride);
+    let mut best: Option<PoolJoin> = None;
+
+    for trip in trips {
+        if excluded.contains(&trip.driver_user_id) {
+            continue;
+        }
+
+        let driver = DriverEntity::find()
+            .filter(DriverColumn::TenantId.eq(ride.tenant_id))
+            .filter(DriverColumn::UserId.eq(trip.driver_user_id))
+            .one(db)
+            .await?;
+        let Some(origin) = driver.and_then(|d| d.lat.zip(d.lon)) else {
+            continue;
+        };
+        if haversine_km(origin.0, origin.1, ride.pickup_lat, ride.pickup_lon) > settings.max_pickup_km {
+            continue;
+        }
+
+        let rides = rides_on_trip(db, &trip).await?;
+        let route = parse_route(&trip);
+        let pending: Vec<Waypoint> = route.iter().filter(|w| is_pending(w, &rides)).cloned().collect();
+        if pending.is_empty() {
+            continue;
+        }
+
+        let Some(insertion) = plan_insertion(origin, &pending, pickup.clone(), dropoff.clone(), settings)
+        else {
+            continue;
+        };
+        if best.as_ref().is_some_and(|b| b.added_km <= insertion.added_km) {
+            continue;
+        }
+
+        let mut full: Vec<Waypoint> = route.into_iter().filter(|w| is_done(w, &rides)).collect();
+        full.extend(insertion.route);
+
+        best = Some(PoolJoin {
+            trip,
+            route: full,
+            added_km: insertion.added_km,
+        });
+    }
+
+    Ok(best)
+}
+
+/// Put `ride` on the trip found by `find_pool_trip`. The route only changes
+/// if nobody else re-planned the trip since; otherwise `AppError::Conflict`.
+pub async fn commit_pool_join<C: TransactionTrait>(
+    db: &C,
+    ride: &RideModel,
+    join: &PoolJoin,
+) -> Result<(), AppError> {
+    let route = serde_json::to_value(&join.route).map_err(|e| AppError::Internal(e.to_string()))?;
+    let now: chrono::DateTime<chrono::FixedOffset> = chrono::Utc::now().into();
+
+    let txn = db.begin().await?;
+
+    let res = PoolTripEntity::update_many()
+        .col_expr(PoolTripColumn::Route, Expr::value(route))
+        .col_expr(PoolTripColumn::Version, Expr::col(PoolTripColumn::Version).add(1))
+        .col_expr(PoolTripColumn::UpdatedAt, Expr::value(now))
+        .filter(PoolTripColumn::Id.eq(join.trip.id))
+        .filter(PoolTripColumn::Version.eq(join.trip.version))
+        .exec(&txn)
+        .await?;
+    if res.rows_affected == 0 {
+        return Err(AppError::Conflict(format!(
+            "Pool trip {} was re-planned concurrently",
+            join.trip.id
+        )));
+    }
+
+    RideEntity::update_many()
+        .col_expr(RideColumn::PoolTripId, Expr::value(Some(join.trip.id)))
+        .filter(RideColumn::Id.eq(ride.id))
+        .exec(&txn)
+        .await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Trip for a pooled ride `driver_user_id` is accepting: the one dispatch
+/// joined it to if that trip is theirs, else a new one. Run it in the accept
+/// transaction so a lost accept leaves no trip behind.
+pub async fn pool_trip_for_driver<C: ConnectionTrait>(
+    db: &C,
+    ride: &RideModel,
+    driver_user_id: Uuid,
+) -> Result<Uuid, AppError> {
+    if let Some(trip_id) = ride.pool_trip_id {
+        let trip = PoolTripEntity::find_by_id(trip_id).one(db).await?;
+        if trip.is_some_and(|t| t.driver_user_id == driver_user_id) {
+            return Ok(trip_id);
+        }
+    }
+
+    Ok(start_pool_trip(db, ride, driver_user_id).await?.id)
+}
+
+/// New trip for the first pooled ride a driver accepts
+async fn start_pool_trip<C: ConnectionTrait>(
+    db: &C,
+    ride: &RideModel,
+    driver_user_id: Uuid,
+) -> Result<PoolTripModel, AppError> {
+    let (pickup, dropoff) = waypoints_of(ride);
+    let route = serde_json::to_value(vec![pickup, dropoff])
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let am = PoolTripActiveModel {
+        id: sea_orm::ActiveValue::NotSet,
+        tenant_id: Set(ride.tenant_id),
+        driver_user_id: Set(driver_user_id),
+        route: Set(route),
+        ..Default::default()
+    };
+
+    Ok(am.insert(db).await?)
+}
+
+/// How a pooled ride's trip splits between the riders who rode it;
+/// None for rides that are not pooled
+pub async fn pool_share<C: ConnectionTrait>(
+    db: &C,
+    ride: &RideModel,
+) -> Result<Option<PoolShare>, AppError> {
+    let Some(trip_id) = ride.pool_trip_id else {
+        return Ok(None);
+    };
+    let Some(trip) = PoolTripEntity::find_by_id(trip_id).one(db).await? else {
+        return Ok(None);
+    };
+
+    let rode: Vec<Uuid> = rides_on_trip(db, &trip)
+        .await?
+        .into_iter()
+        .filter(|r| matches!(r.status, RideStatus::InProgress | RideStatus::Completed))
+        .map(|r| r.id)
+        .collect();
+
+    let route: Vec<Waypoint> = parse_route(&trip)
+        .into_iter()
+        .filter(|w| rode.contains(&w.ride_id))
+        .collect();
+
+    Ok(fare_share(&route, ride.id))
+}
+
+/// The ride's place on its pooled trip, for `ride_datum` callers
+pub async fn pool_datum<C: ConnectionTrait>(
+    db: &C,
+    ride: &RideModel,
+) -> Result<Option<Value>, AppError> {
+    let Some(trip_id) = ride.pool_trip_id else {
+        return Ok(None);
+    };
+    let Some(trip) = PoolTripEntity::find_by_id(trip_id).one(db).await? else {
+        return Ok(None);
+    };
+    if ride.driver_id != Some(trip.driver_user_id) {
+        return Ok(None);
+    }
+
+    let rides = rides_on_trip(db, &trip).await?;
+    let pending: Vec<Waypoint> = parse_route(&trip)
+        .into_iter()
+        .filter(|w| is_pending(w, &rides))
+        .collect();
+    let order = |kind: WaypointKind| {
+        pending
+            .iter()
+            .position(|w| w.ride_id == ride.id && w.kind == kind)
+            .map(|i| i + 1)
+    };
+
+    Ok(Some(json!({
+        "trip_id": trip.id,
+        "riders": rides
+            .iter()
+            .filter(|r| RideStatus::DRIVER_BUSY.contains(&r.status))
+            .count(),
+        // 1-based position among the driver's remaining pickups / drop-offs
+        "pickup_order": order(WaypointKind::Pickup),
+        "dropoff_order": order(WaypointKind::Dropoff),
+    })))
+}
+
+/// Trip route after a join, for the driver's `pool_route_updated` event
+pub fn pool_join_datum(join: &PoolJoin) -> Value {
+    json!({
+        "trip_id": join.trip.id,
+        "route": join.route,
+        "added_km": join.added_km,
+    })
+}
@@ -0,0 +1,251 @@
+// src/services/pricing_service.rs
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+use uuid::Uuid;
+
+use dispatch::geo::haversine_km;
+
+use crate::entity::rate_card::{Column as RateCardColumn, Entity as RateCardEntity};
+use crate::entity::ride::Model as RideModel;
+use crate::entity::prelude::RideBreadcrumb as BreadcrumbEntity;
+use crate::entity::ride_breadcrumb::Column as BreadcrumbColumn;
+use crate::entity::ride_stop::Model as RideStopModel;
+use crate::error::AppError;
+use crate::services::pool_service::pool_share;
+use crate::services::ride_stop_service::load_ride_stops;
+use crate::services::tenant_service::load_tenant_settings;
+use crate::types::fare::{FareBreakdown, MeterSource, RateCard, RideFare};
+use crate::types::money::{Currency, Money};
+use crate::utils::redis_geo::nearby_drivers;
+use crate::utils::surge::current_multiplier;
+use crate::utils::trip_meter::{measure_trail, within_trip};
+
+/// Upfront price for a trip, before any ride exists.
+#[derive(Debug, Clone, Serialize)]
+pub struct FareEstimate {
+    pub distance_km: f64,
+    /// Trip time pickup -> destination
+    pub duration_mins: f64,
+    /// Nearest driver to pickup, if any is around
+    pub pickup_eta_mins: Option<f64>,
+    pub surge_multiplier: f64,
+    pub fare: FareBreakdown,
+}
+
+/// The tenant's card for `tier`; tiers without one use the built-in default.
+/// `currency` is the tenant's for new trips, the one frozen on the ride otherwise.
+pub async fn rate_card_for<C: ConnectionTrait>(
+    db: &C,
+    tenant_id: Uuid,
+    tier: &str,
+    currency: Currency,
+) -> Result<RateCard, AppError> {
+    let card = RateCardEntity::find()
+        .filter(RateCardColumn::TenantId.eq(tenant_id))
+        .filter(RateCardColumn::Tier.eq(tier))
+        .one(db)
+        .await?;
+
+    Ok(match card {
+        Some(card) => RateCard::from_model(&card, currency),
+        None => RateCard::default_for(currency),
+    })
+}
+
+/// Quotes may drift this far from the requested pickup / stops / destination (km)
+pub const QUOTE_LOCATION_TOLERANCE_KM: f64 = 0.2;
+
+/// The quoted total of a ride booked on a quote. Destination changes the
+/// rider could make without asking (within `max_shift_km` of the quoted
+/// destination) keep the cap; one the driver had to approve voids it.
+fn quoted_total(ride: &RideModel, max_shift_km: f64) -> Option<Money> {
+    let quote = ride.quote.as_ref()?;
+    let dest = (quote["dest"]["lat"].as_f64()?, quote["dest"]["lon"].as_f64()?);
+    let shift_km = haversine_km(dest.0, dest.1, ride.dest_lat, ride.dest_lon);
+    if shift_km > max_shift_km.max(QUOTE_LOCATION_TOLERANCE_KM) {
+        return None;
+    }
+    serde_json::from_value(quote["fare_total"].clone()).ok()
+}
+
+/// Straight-line legs pickup -> stops... -> destination
+fn route_km(pickup: (f64, f64), stops: &[(f64, f64)], dest: (f64, f64)) -> f64 {
+    let mut km = 0.0;
+    let mut from = pickup;
+    for &to in stops.iter().chain(std::iter::once(&dest)) {
+        km += haversine_km(from.0, from.1, to.0, to.1);
+        from = to;
+    }
+    km
+}
+
+/// Minutes the driver spent at each stop they reached; a stop still open at
+/// completion counts until `completed_at`
+fn stop_wait_mins(stops: &[RideStopModel], completed_at: chrono::DateTime<chrono::Utc>) -> Vec<f64> {
+    stops
+        .iter()
+        .filter_map(|s| {
+            let arrived = s.arrived_at?.to_utc();
+            let departed = s.departed_at.map(|t| t.to_utc()).unwrap_or(completed_at);
+            Some((departed - arrived).num_seconds().max(0) as f64 / 60.0)
+        })
+        .collect()
+}
+
+/// Price a pickup -> stops -> destination trip.
+/// `surge_multiplier` = None uses the live multiplier for the pickup cell.
+pub async fn estimate_fare<C: ConnectionTrait>(
+    db: &C,
+    tenant_id: Uuid,
+    tier: &str,
+    pickup: (f64, f64),
+    stops: &[(f64, f64)],
+    dest: (f64, f64),
+    surge_multiplier: Option<f64>,
+) -> Result<FareEstimate, AppError> {
+    let settings = load_tenant_settings(db, tenant_id).await?;
+    let card = rate_card_for(db, tenant_id, tier, settings.currency.clone()).await?;
+
+    let surge_multiplier = match surge_multiplier {
+        Some(m) => m,
+        None => current_multiplier(tenant_id, pickup.0, pickup.1)
+            .await
+            .unwrap_or(1.0),
+    };
+    let surge_multiplier = card.capped_surge(surge_multiplier);
+
+    let distance_km = route_km(pickup, stops, dest);
+    let duration_mins = settings.pricing.eta_mins(distance_km);
+    let rules = settings
+        .pricing
+        .matching_rules(tier, pickup, dest, chrono::Utc::now());
+
+    // best-effort: nearest driver in the widest dispatch ring
+    let widest_km = settings
+        .dispatch
+        .search_radii_km
+        .iter()
+        .copied()
+        .fold(0.0, f64::max);
+    let pickup_eta_mins = match nearby_drivers(tenant_id, pickup.0, pickup.1, widest_km, 1).await {
+        Ok(hits) => hits.first().map(|(_, km)| settings.pricing.eta_mins(*km)),
+        Err(e) => {
+            tracing::warn!("pickup ETA lookup failed for tenant {}: {}", tenant_id, e);
+            None
+        }
+    };
+
+    Ok(FareEstimate {
+        distance_km,
+        duration_mins,
+        pickup_eta_mins,
+        surge_multiplier,
+        fare: card.fare(distance_km, duration_mins, &[], surge_multiplier, &rules),
+    })
+}
+
+/// Final fare for a ride finishing at `completed_at`.
+///
+/// Distance and time come from the driver's GPS trail. If the trail is too
+/// thin, falls back to the estimate: straight lines pickup -> stops ->
+/// destination and `started_at` -> `completed_at` (or the ETA if the start
+/// time is missing). Time waited at pickup (`arrived_at` -> `started_at`) and
+/// at stops is billed as waiting, not driving.
+/// Pooled rides pay their share of the trip instead: each planned leg split
+/// between the riders in the car, time scaled the same way.
+/// Surge is the multiplier locked on the ride; pricing rules match on the
+/// pickup time. A ride booked on a quote costs at most the quoted total,
+/// plus waiting.
+pub async fn final_fare<C: ConnectionTrait>(
+    db: &C,
+    ride: &RideModel,
+    completed_at: chrono::DateTime<chrono::Utc>,
+) -> Result<RideFare, AppError> {
+    let settings = load_tenant_settings(db, ride.tenant_id).await?;
+    let rate_card = rate_card_for(db, ride.tenant_id, &ride.tier, ride.currency.clone()).await?;
+
+    let stops = load_ride_stops(db, ride.id).await?;
+    let completed: chrono::DateTime<chrono::FixedOffset> = completed_at.into();
+    let mut crumbs = BreadcrumbEntity::find()
+        .filter(BreadcrumbColumn::RideId.eq(ride.id))
+        .order_by_asc(BreadcrumbColumn::RecordedAt)
+        .all(db)
+        .await?;
+    crumbs.retain(|c| within_trip(c.recorded_at, ride.started_at, Some(completed)));
+
+    let trip_secs = ride
+        .started_at
+        .map(|t| completed_at.signed_duration_since(t).num_seconds().max(0));
+    let gps = measure_trail(&crumbs, trip_secs, &settings.pricing.trip_meter);
+
+    // the driver's trail covers co-riders too, so pooled rides bill their share of the plan
+    let pool = pool_share(db, ride).await?;
+
+    let (meter_source, distance_km, duration_mins) = if let Some(share) = &pool {
+        let duration_mins = match trip_secs {
+            Some(secs) => secs as f64 / 60.0,
+            None => settings.pricing.eta_mins(share.in_vehicle_km),
+        };
+        (MeterSource::Pool, share.shared_km, duration_mins * share.ratio())
+    } else if gps.is_reliable(&settings.pricing.trip_meter) {
+        (MeterSource::Gps, gps.distance_km, gps.duration_mins)
+    } else {
+        let legs: Vec<(f64, f64)> = stops.iter().map(|s| (s.lat, s.lon)).collect();
+        let distance_km = route_km(
+            (ride.pickup_lat, ride.pickup_lon),
+            &legs,
+            (ride.dest_lat, ride.dest_lon),
+        );
+        let duration_mins = match trip_secs {
+            Some(secs) => secs as f64 / 60.0,
+            None => settings.pricing.eta_mins(distance_km),
+        };
+        (MeterSource::Estimate, distance_km, duration_mins)
+    };
+
+    // rules are matched at pickup time
+    let picked_up_at = ride.started_at.map(|t| t.to_utc()).unwrap_or(completed_at);
+    let rules = settings.pricing.matching_rules(
+        &ride.tier,
+        (ride.pickup_lat, ride.pickup_lon),
+        (ride.dest_lat, ride.dest_lon),
+        picked_up_at,
+    );
+    let stop_wait_mins = stop_wait_mins(&stops, completed_at);
+    let moving_mins = (duration_mins - stop_wait_mins.iter().sum::<f64>()).max(0.0);
+
+    // pickup wait happens before the trip clock starts
+    let pickup_wait_mins = match (ride.arrived_at, ride.started_at) {
+        (Some(arrived), Some(started)) => {
+            Some((started - arrived).num_seconds().max(0) as f64 / 60.0)
+        }
+        _ => None,
+    };
+    let wait_mins: Vec<f64> = pickup_wait_mins
+        .into_iter()
+        .chain(stop_wait_mins.iter().copied())
+        .collect();
+
+    let mut breakdown = rate_card.fare(
+        distance_km,
+        moving_mins,
+        &wait_mins,
+        ride.surge_multiplier,
+        &rules,
+    );
+    if let Some(quoted) = quoted_total(ride, settings.trip_changes.max_destination_shift_km) {
+        breakdown.cap_at_quote(&quoted);
+    }
+
+    Ok(RideFare {
+        distance_km,
+        duration_mins,
+        meter_source,
+        pickup_wait_mins,
+        stop_wait_mins,
+        pool,
+        gps,
+        rate_card,
+        breakdown,
+    })
+}
@@ -0,0 +1,261 @@
+// src/services/promo_service.rs
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter,
+    QuerySelect, Set,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::entity::promo_code::{Column as PromoCodeColumn, Model as PromoCodeModel};
+use crate::entity::promo_redemption::{
+    ActiveModel as PromoRedemptionActiveModel,
+    Column as PromoRedemptionColumn,
+    Model as PromoRedemptionModel,
+};
+use crate::entity::prelude::{
+    PromoCode as PromoCodeEntity, PromoRedemption as PromoRedemptionEntity,
+};
+use crate::entity::ride::{Column as RideColumn, Entity as RideEntity, Model as RideModel};
+use crate::error::AppError;
+use crate::types::money::Money;
+use crate::types::promo::{PromoKind, RedemptionStatus};
+use crate::types::ride_status::RideStatus;
+
+/// Codes are matched case-insensitively and stored upper-case
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_ascii_uppercase()
+}
+
+fn invalid(message: impl Into<String>) -> AppError {
+    AppError::Validation {
+        field: "promo_code".into(),
+        message: message.into(),
+    }
+}
+
+/// Tiers the code works for; empty = every tier
+pub fn promo_tiers(promo: &PromoCodeModel) -> Vec<String> {
+    serde_json::from_value(promo.tiers.clone()).unwrap_or_default()
+}
+
+/// What `promo` takes off a fare of `total`: never more than the cap or the fare itself
+pub fn promo_discount(promo: &PromoCodeModel, total: &Money) -> Money {
+    let amount = match promo.kind {
+        PromoKind::Percent => total.scale(promo.percent.unwrap_or(0.0) / 100.0).amount_minor,
+        PromoKind::Flat => promo.amount_minor.unwrap_or(0),
+    };
+    let amount = match promo.max_discount_minor {
+        Some(cap) => amount.min(cap),
+        None => amount,
+    };
+
+    Money::new(amount.clamp(0, total.amount_minor.max(0)), total.currency.clone())
+}
+
+/// Reserved + consumed uses of a code, optionally by one rider
+pub async fn held_uses<C: ConnectionTrait>(
+    db: &C,
+    promo_code_id: Uuid,
+    user_id: Option<Uuid>,
+) -> Result<u64, AppError> {
+    let mut query = PromoRedemptionEntity::find()
+        .filter(PromoRedemptionColumn::PromoCodeId.eq(promo_code_id))
+        .filter(PromoRedemptionColumn::Status.is_in(RedemptionStatus::HELD.iter().copied()));
+    if let Some(user_id) = user_id {
+        query = query.filter(PromoRedemptionColumn::UserId.eq(user_id));
+    }
+
+    Ok(query.count(db).await?)
+}
+
+/// Every rule a code has for this rider, tier and moment
+async fn ensure_usable<C: ConnectionTrait>(
+    db: &C,
+    promo: &PromoCodeModel,
+    user_id: Uuid,
+    tier: &str,
+) -> Result<(), AppError> {
+    let now = chrono::Utc::now();
+
+    if !promo.active {
+        return Err(invalid("Promo code is not valid"));
+    }
+    if promo.valid_from.is_some_and(|t| now < t.to_utc()) {
+        return Err(invalid("Promo code is not active yet"));
+    }
+    if promo.valid_until.is_some_and(|t| now > t.to_utc()) {
+        return Err(invalid("Promo code has expired"));
+    }
+
+    let tiers = promo_tiers(promo);
+    if !tiers.is_empty() && !tiers.iter().any(|t| t == tier) {
+        return Err(invalid(format!("Promo code can't be used for {tier} rides")));
+    }
+
+    if promo.first_ride_only {
+        let completed = RideEntity::find()
+            .filter(RideColumn::TenantId.eq(promo.tenant_id))
+            .filter(RideColumn::RiderId.eq(user_id))
+            .filter(RideColumn::Status.eq(RideStatus::Completed))
+            .count(db)
+            .await?;
+        // an open ride already holding a first-ride code is the first ride
+        let held_first_ride = PromoRedemptionEntity::find()
+            .inner_join(PromoCodeEntity)
+            .filter(PromoRedemptionColumn::TenantId.eq(promo.tenant_id))
+            .filter(PromoRedemptionColumn::UserId.eq(user_id))
+            .filter(PromoRedemptionColumn::Status.is_in(RedemptionStatus::HELD.iter().copied()))
+            .filter(PromoCodeColumn::FirstRideOnly.eq(true))
+            .count(db)
+            .await?;
+        if completed > 0 || held_first_ride > 0 {
+            return Err(invalid("Promo code is only for a first ride"));
+        }
+    }
+
+    match promo.max_uses {
+        Some(max) if held_uses(db, promo.id, None).await? >= max as u64 => {
+            return Err(invalid("Promo code has reached its usage limit"));
+        }
+        _ => {}
+    }
+    match promo.max_uses_per_user {
+        Some(max) if held_uses(db, promo.id, Some(user_id)).await? >= max as u64 => {
+            return Err(invalid("You have already used this promo code"));
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// The tenant's `code`, if this rider can use it on a `tier` ride right now
+pub async fn check_promo<C: ConnectionTrait>(
+    db: &C,
+    tenant_id: Uuid,
+    user_id: Uuid,
+    tier: &str,
+    code: &str,
+) -> Result<PromoCodeModel, AppError> {
+    let promo = PromoCodeEntity::find()
+        .filter(PromoCodeColumn::TenantId.eq(tenant_id))
+        .filter(PromoCodeColumn::Code.eq(normalize_code(code)))
+        .one(db)
+        .await?
+        .ok_or_else(|| invalid("Promo code is not valid"))?;
+
+    ensure_usable(db, &promo, user_id, tier).await?;
+
+    Ok(promo)
+}
+
+/// Hold one use of `code` for a just-created ride. Run inside the ride's
+/// transaction: the code row stays locked until it commits, so concurrent
+/// requests can't overrun the usage limits.
+pub async fn reserve_promo<C: ConnectionTrait>(
+    db: &C,
+    ride: &RideModel,
+    code: &str,
+    discount: &Money,
+) -> Result<PromoRedemptionModel, AppError> {
+    let promo = PromoCodeEntity::find()
+        .filter(PromoCodeColumn::TenantId.eq(ride.tenant_id))
+        .filter(PromoCodeColumn::Code.eq(normalize_code(code)))
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or_else(|| invalid("Promo code is not valid"))?;
+
+    ensure_usable(db, &promo, ride.rider_id, &ride.tier).await?;
+
+    let am = PromoRedemptionActiveModel {
+        id: sea_orm::ActiveValue::NotSet,
+        tenant_id: Set(ride.tenant_id),
+        promo_code_id: Set(promo.id),
+        ride_id: Set(ride.id),
+        user_id: Set(ride.rider_id),
+        status: Set(RedemptionStatus::Reserved),
+        discount: Set(discount.amount_minor),
+        ..Default::default()
+    };
+
+    Ok(am.insert(db).await?)
+}
+
+/// The code still reserved for a ride, with its redemption
+pub async fn reserved_promo<C: ConnectionTrait>(
+    db: &C,
+    ride_id: Uuid,
+) -> Result<Option<(PromoRedemptionModel, PromoCodeModel)>, AppError> {
+    let found = PromoRedemptionEntity::find()
+        .filter(PromoRedemptionColumn::RideId.eq(ride_id))
+        .filter(PromoRedemptionColumn::Status.eq(RedemptionStatus::Reserved))
+        .find_also_related(PromoCodeEntity)
+        .one(db)
+        .await?;
+
+    Ok(found.and_then(|(redemption, promo)| promo.map(|p| (redemption, p))))
+}
+
+/// Move a ride's reserved use to `status`, recording the final `discount` if given
+async fn settle_redemption<C: ConnectionTrait>(
+    db: &C,
+    ride_id: Uuid,
+    status: RedemptionStatus,
+    discount: Option<i64>,
+) -> Result<(), AppError> {
+    let now: chrono::DateTime<chrono::FixedOffset> = chrono::Utc::now().into();
+
+    let mut update = PromoRedemptionEntity::update_many()
+        .col_expr(PromoRedemptionColumn::Status, Expr::value(status))
+        .col_expr(PromoRedemptionColumn::UpdatedAt, Expr::value(now));
+    if let Some(discount) = discount {
+        update = update.col_expr(PromoRedemptionColumn::Discount, Expr::value(discount));
+    }
+
+    update
+        .filter(PromoRedemptionColumn::RideId.eq(ride_id))
+        .filter(PromoRedemptionColumn::Status.eq(RedemptionStatus::Reserved))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Ride completed: the use is spent, at the discount actually charged
+pub async fn consume_promo<C: ConnectionTrait>(
+    db: &C,
+    ride_id: Uuid,
+    discount: &Money,
+) -> Result<(), AppError> {
+    settle_redemption(db, ride_id, RedemptionStatus::Consumed, Some(discount.amount_minor)).await
+}
+
+/// Ride ended without completing: give the use back (best-effort)
+pub async fn release_promo<C: ConnectionTrait>(db: &C, ride_id: Uuid) {
+    if let Err(e) = settle_redemption(db, ride_id, RedemptionStatus::Released, None).await {
+        tracing::warn!("failed to release promo code for ride {}: {:?}", ride_id, e);
+    }
+}
+
+/// A ride's promo code, for `ride_datum` callers
+pub async fn promo_datum<C: ConnectionTrait>(
+    db: &C,
+    ride: &RideModel,
+) -> Result<Option<Value>, AppError> {
+    let found = PromoRedemptionEntity::find()
+        .filter(PromoRedemptionColumn::RideId.eq(ride.id))
+        .find_also_related(PromoCodeEntity)
+        .one(db)
+        .await?;
+
+    Ok(found.map(|(redemption, promo)| {
+        json!({
+            "code": promo.map(|p| p.code),
+            "status": redemption.status,
+            // estimated while reserved
+            "discount": Money::new(redemption.discount, ride.currency.clone()),
+        })
+    }))
+}
@@ -0,0 +1,342 @@
+// src/services/ride_destination_service.rs
+use actix_web::{web, HttpRequest, HttpResponse, Error};
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use dispatch::geo::haversine_km;
+
+use crate::entity::ride::{Entity as RideEntity, Model as RideModel};
+use crate::error::AppError;
+use crate::requests::structures::RideDestinationPayload;
+use crate::requests::validation::validate_ride_destination;
+use crate::services::pricing_service::{estimate_fare, FareEstimate};
+use crate::services::ride_service::ride_json;
+use crate::services::ride_stop_service::load_ride_stops;
+use crate::services::ride_transition_service::update_ride;
+use crate::services::tenant_service::load_tenant_settings;
+use crate::types::ride_status::RideStatus;
+use crate::utils::current_user::get_current_user;
+use crate::ws::notify_user;
+
+/// A destination change the driver still has to accept (`ride.pending_destination`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingDestination {
+    lat: f64,
+    lon: f64,
+    address: Option<String>,
+    /// Straight line from the booked destination
+    shift_km: f64,
+    requested_at: chrono::DateTime<chrono::Utc>,
+}
+
+async fn load_ride(db: &DatabaseConnection, ride_id: Uuid) -> Result<RideModel, Error> {
+    RideEntity::find_by_id(ride_id)
+        .one(db)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("Ride not found"))
+}
+
+/// Only once a driver has taken the ride and until it ends
+fn ensure_changeable(ride: &RideModel) -> Result<(), Error> {
+    match ride.status {
+        RideStatus::Accepted | RideStatus::DriverArrived | RideStatus::InProgress => Ok(()),
+        _ => Err(actix_web::error::ErrorBadRequest(
+            "Destination can only be changed once a driver has accepted the ride",
+        )),
+    }
+}
+
+/// New price for the whole trip to `dest`, at the ride's locked surge
+async fn requote(
+    db: &DatabaseConnection,
+    ride: &RideModel,
+    dest: (f64, f64),
+) -> Result<FareEstimate, AppError> {
+    let stops: Vec<(f64, f64)> = load_ride_stops(db, ride.id)
+        .await?
+        .iter()
+        .map(|s| (s.lat, s.lon))
+        .collect();
+
+    estimate_fare(
+        db,
+        ride.tenant_id,
+        &ride.tier,
+        (ride.pickup_lat, ride.pickup_lon),
+        &stops,
+        dest,
+        Some(ride.surge_multiplier),
+    )
+    .await
+}
+
+fn destination_json(lat: f64, lon: f64, address: &Option<String>) -> serde_json::Value {
+    json!({
+        "lat": lat,
+        "lon": lon,
+        "address": address,
+    })
+}
+
+/// Where the ride was booked to go. Shifts are measured from here, so a run
+/// of small changes can't walk the drop-off away without the driver's ok.
+fn original_destination(ride: &RideModel) -> serde_json::Value {
+    ride.original_destination
+        .clone()
+        .unwrap_or_else(|| destination_json(ride.dest_lat, ride.dest_lon, &ride.dest_address))
+}
+
+fn shift_from_original(ride: &RideModel, lat: f64, lon: f64) -> f64 {
+    let original = original_destination(ride);
+    let from = (
+        original["lat"].as_f64().unwrap_or(ride.dest_lat),
+        original["lon"].as_f64().unwrap_or(ride.dest_lon),
+    );
+    haversine_km(from.0, from.1, lat, lon)
+}
+
+/// Move the ride to `dest`, dropping any change still waiting on the driver
+async fn apply_destination(
+    db: &DatabaseConnection,
+    ride: RideModel,
+    actor_user_id: Uuid,
+    dest: PendingDestination,
+    approved_by_driver: bool,
+) -> Result<(RideModel, FareEstimate), Error> {
+    let estimate = requote(db, &ride, (dest.lat, dest.lon)).await?;
+    let original = original_destination(&ride);
+
+    let payload = json!({
+        "old_destination": destination_json(ride.dest_lat, ride.dest_lon, &ride.dest_address),
+        "new_destination": destination_json(dest.lat, dest.lon, &dest.address),
+        "shift_km": dest.shift_km,
+        "approved_by_driver": approved_by_driver,
+        "fare_estimate": estimate.fare,
+    });
+
+    let updated = update_ride(db, ride, Some(actor_user_id), "destination_changed", payload, |am| {
+        am.dest_lat = Set(dest.lat);
+        am.dest_lon = Set(dest.lon);
+        am.dest_address = Set(dest.address.clone());
+        am.pending_destination = Set(None);
+        am.original_destination = Set(Some(original.clone()));
+    })
+    .await?;
+
+    Ok((updated, estimate))
+}
+
+/// PATCH /rides/{id}/destination
+///
+/// Small changes apply straight away; a new destination further than the
+/// tenant's `trip_changes.max_destination_shift_km` from the one the ride was
+/// booked to waits for the driver to accept it.
+pub async fn change_destination_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    ride_id: Uuid,
+    payload: web::Json<RideDestinationPayload>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+
+    validate_ride_destination(&payload)
+        .map_err(actix_web::error::ErrorUnprocessableEntity)?;
+
+    let ride = load_ride(db.get_ref(), ride_id).await?;
+
+    if ride.rider_id != user.id {
+        return Err(actix_web::error::ErrorForbidden(
+            "You are not allowed to access this ride",
+        ));
+    }
+    ensure_changeable(&ride)?;
+
+    // co-riders' detours were planned around this drop-off
+    if ride.pool_trip_id.is_some() {
+        return Err(actix_web::error::ErrorBadRequest(
+            "Pooled rides can't change destination",
+        ));
+    }
+
+    let settings = load_tenant_settings(db.get_ref(), ride.tenant_id).await?;
+    let dest = PendingDestination {
+        lat: payload.lat,
+        lon: payload.lon,
+        address: payload.address.clone(),
+        shift_km: shift_from_original(&ride, payload.lat, payload.lon),
+        requested_at: chrono::Utc::now(),
+    };
+
+    // 🔹 close enough: the driver just gets told
+    if dest.shift_km <= settings.trip_changes.max_destination_shift_km {
+        let (updated, estimate) = apply_destination(db.get_ref(), ride, user.id, dest, false).await?;
+
+        if let Some(driver_id) = updated.driver_id {
+            let _ = notify_user(driver_id, "ride_destination_changed", json!({
+                "ride_id": updated.id,
+                "destination": destination_json(updated.dest_lat, updated.dest_lon, &updated.dest_address),
+                "fare_estimate": estimate.fare,
+            }))
+            .await;
+        }
+
+        let data = ride_json(db.get_ref(), &updated).await?;
+
+        return Ok(HttpResponse::Ok().json(json!({
+            "status": 200,
+            "code": 200,
+            "message": "Destination changed",
+            "data": {
+                "ride": data,
+                "fare_estimate": estimate,
+            }
+        })));
+    }
+
+    // 🔹 too far: park it until the driver answers (a newer request replaces it)
+    let estimate = requote(db.get_ref(), &ride, (dest.lat, dest.lon)).await?;
+    let pending = serde_json::to_value(&dest)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let original = original_destination(&ride);
+
+    let event = json!({
+        "old_destination": destination_json(ride.dest_lat, ride.dest_lon, &ride.dest_address),
+        "new_destination": destination_json(dest.lat, dest.lon, &dest.address),
+        "shift_km": dest.shift_km,
+        "fare_estimate": estimate.fare,
+    });
+
+    let updated = update_ride(
+        db.get_ref(),
+        ride,
+        Some(user.id),
+        "destination_change_requested",
+        event,
+        |am| {
+            am.pending_destination = Set(Some(pending));
+            am.original_destination = Set(Some(original.clone()));
+        },
+    )
+    .await?;
+
+    if let Some(driver_id) = updated.driver_id {
+        let _ = notify_user(driver_id, "ride_destination_change_requested", json!({
+            "ride_id": updated.id,
+            "destination": destination_json(dest.lat, dest.lon, &dest.address),
+            "shift_km": dest.shift_km,
+            "fare_estimate": estimate.fare,
+        }))
+        .await;
+    }
+
+    let data = ride_json(db.get_ref(), &updated).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Destination change sent to the driver for approval",
+        "data": {
+            "ride": data,
+            "fare_estimate": estimate,
+        }
+    })))
+}
+
+/// The assigned driver's ride with a change waiting on them
+async fn load_pending_change(
+    req: &HttpRequest,
+    db: &DatabaseConnection,
+    ride_id: Uuid,
+) -> Result<(Uuid, RideModel, PendingDestination), Error> {
+    let user = get_current_user(req, db).await?;
+    let ride = load_ride(db, ride_id).await?;
+
+    if ride.driver_id != Some(user.id) {
+        return Err(actix_web::error::ErrorForbidden(
+            "You are not the assigned driver for this ride",
+        ));
+    }
+    ensure_changeable(&ride)?;
+
+    let pending = ride
+        .pending_destination
+        .clone()
+        .and_then(|v| serde_json::from_value::<PendingDestination>(v).ok())
+        .ok_or_else(|| actix_web::error::ErrorBadRequest("No destination change is pending"))?;
+
+    Ok((user.id, ride, pending))
+}
+
+/// POST /rides/{id}/destination/accept
+pub async fn accept_destination_change_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    ride_id: Uuid,
+) -> Result<HttpResponse, Error> {
+    let (driver_id, ride, pending) = load_pending_change(&req, db.get_ref(), ride_id).await?;
+
+    let (updated, estimate) = apply_destination(db.get_ref(), ride, driver_id, pending, true).await?;
+
+    let _ = notify_user(updated.rider_id, "ride_destination_changed", json!({
+        "ride_id": updated.id,
+        "destination": destination_json(updated.dest_lat, updated.dest_lon, &updated.dest_address),
+        "fare_estimate": estimate.fare,
+    }))
+    .await;
+
+    let data = ride_json(db.get_ref(), &updated).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Destination change accepted",
+        "data": {
+            "ride": data,
+            "fare_estimate": estimate,
+        }
+    })))
+}
+
+/// POST /rides/{id}/destination/decline
+pub async fn decline_destination_change_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    ride_id: Uuid,
+) -> Result<HttpResponse, Error> {
+    let (driver_id, ride, pending) = load_pending_change(&req, db.get_ref(), ride_id).await?;
+
+    let declined = destination_json(pending.lat, pending.lon, &pending.address);
+
+    let updated = update_ride(
+        db.get_ref(),
+        ride,
+        Some(driver_id),
+        "destination_change_declined",
+        json!({
+            "declined_destination": declined,
+            "shift_km": pending.shift_km,
+        }),
+        |am| {
+            am.pending_destination = Set(None);
+        },
+    )
+    .await?;
+
+    let _ = notify_user(updated.rider_id, "ride_destination_change_declined", json!({
+        "ride_id": updated.id,
+        "destination": declined,
+    }))
+    .await;
+
+    let data = ride_json(db.get_ref(), &updated).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Destination change declined",
+        "data": data
+    })))
+}
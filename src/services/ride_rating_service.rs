@@ -0,0 +1,319 @@
+// src/services/ride_rating_service.rs
+use actix_web::{web, HttpRequest, HttpResponse, Error};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    Set, SqlErr, TransactionTrait,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::entity::driver::{Column as DriverColumn, Entity as DriverEntity};
+use crate::entity::driver_event::ActiveModel as DriverEventActiveModel;
+use crate::entity::ride::{Entity as RideEntity, Model as RideModel};
+use crate::entity::ride_rating::{ActiveModel as RideRatingActiveModel, Model as RideRatingModel};
+use crate::entity::user::{Column as UserColumn, Entity as UserEntity};
+use crate::error::AppError;
+use crate::jresponse::rating_jresponse::{rating_summary_datum, ride_rating_datum};
+use crate::requests::structures::RideRatingPayload;
+use crate::requests::validation::validate_ride_rating;
+use crate::services::ride_transition_service::record_ride_event;
+use crate::services::tenant_service::load_tenant_settings;
+use crate::types::ride_status::RideStatus;
+use crate::types::tenant_settings::RatingSettings;
+use crate::utils::current_user::get_current_user;
+
+/// Which side of the ride is rating
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RaterRole {
+    Rider,
+    Driver,
+}
+
+impl RaterRole {
+    fn as_str(self) -> &'static str {
+        match self {
+            RaterRole::Rider => "rider",
+            RaterRole::Driver => "driver",
+        }
+    }
+}
+
+/// Flag state change an account's new average caused
+#[derive(Clone, Copy)]
+enum FlagChange {
+    Flagged,
+    Unflagged,
+}
+
+impl FlagChange {
+    fn as_str(self) -> &'static str {
+        match self {
+            FlagChange::Flagged => "flagged",
+            FlagChange::Unflagged => "unflagged",
+        }
+    }
+}
+
+/// New totals of the rated account, after this rating
+struct RatingTotals {
+    sum: i64,
+    count: i32,
+    flagged_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    change: Option<FlagChange>,
+}
+
+/// Set / clear the flag for totals `sum` / `count`; returns the new flag time
+fn apply_flag_rule(
+    settings: &RatingSettings,
+    sum: i64,
+    count: i32,
+    flagged_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+) -> (Option<chrono::DateTime<chrono::FixedOffset>>, Option<FlagChange>) {
+    match (settings.should_flag(sum, count), flagged_at) {
+        (true, None) => (Some(chrono::Utc::now().into()), Some(FlagChange::Flagged)),
+        (false, Some(_)) => (None, Some(FlagChange::Unflagged)),
+        (_, current) => (current, None),
+    }
+}
+
+/// Add `stars` to the driver's totals in the ride's tenant
+async fn add_driver_rating<C: ConnectionTrait>(
+    db: &C,
+    tenant_id: Uuid,
+    driver_user_id: Uuid,
+    stars: i32,
+    settings: &RatingSettings,
+) -> Result<RatingTotals, AppError> {
+    DriverEntity::update_many()
+        .col_expr(DriverColumn::RatingSum, Expr::col(DriverColumn::RatingSum).add(stars as i64))
+        .col_expr(DriverColumn::RatingCount, Expr::col(DriverColumn::RatingCount).add(1))
+        .filter(DriverColumn::TenantId.eq(tenant_id))
+        .filter(DriverColumn::UserId.eq(driver_user_id))
+        .exec(db)
+        .await?;
+
+    let driver = DriverEntity::find()
+        .filter(DriverColumn::TenantId.eq(tenant_id))
+        .filter(DriverColumn::UserId.eq(driver_user_id))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Driver {driver_user_id} not found")))?;
+
+    let (flagged_at, change) =
+        apply_flag_rule(settings, driver.rating_sum, driver.rating_count, driver.rating_flagged_at);
+    if change.is_some() {
+        DriverEntity::update_many()
+            .col_expr(DriverColumn::RatingFlaggedAt, Expr::value(flagged_at))
+            .filter(DriverColumn::Id.eq(driver.id))
+            .exec(db)
+            .await?;
+    }
+
+    Ok(RatingTotals {
+        sum: driver.rating_sum,
+        count: driver.rating_count,
+        flagged_at,
+        change,
+    })
+}
+
+/// Add `stars` to the rider's totals (kept on their account)
+async fn add_rider_rating<C: ConnectionTrait>(
+    db: &C,
+    rider_id: Uuid,
+    stars: i32,
+    settings: &RatingSettings,
+) -> Result<RatingTotals, AppError> {
+    UserEntity::update_many()
+        .col_expr(UserColumn::RiderRatingSum, Expr::col(UserColumn::RiderRatingSum).add(stars as i64))
+        .col_expr(UserColumn::RiderRatingCount, Expr::col(UserColumn::RiderRatingCount).add(1))
+        .filter(UserColumn::Id.eq(rider_id))
+        .exec(db)
+        .await?;
+
+    let rider = UserEntity::find_by_id(rider_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("User {rider_id} not found")))?;
+
+    let (flagged_at, change) = apply_flag_rule(
+        settings,
+        rider.rider_rating_sum,
+        rider.rider_rating_count,
+        rider.rider_rating_flagged_at,
+    );
+    if change.is_some() {
+        UserEntity::update_many()
+            .col_expr(UserColumn::RiderRatingFlaggedAt, Expr::value(flagged_at))
+            .filter(UserColumn::Id.eq(rider.id))
+            .exec(db)
+            .await?;
+    }
+
+    Ok(RatingTotals {
+        sum: rider.rider_rating_sum,
+        count: rider.rider_rating_count,
+        flagged_at,
+        change,
+    })
+}
+
+/// Store the rating and fold it into the ratee's totals, all or nothing
+async fn save_rating(
+    db: &DatabaseConnection,
+    ride: &RideModel,
+    rater_user_id: Uuid,
+    ratee_user_id: Uuid,
+    role: RaterRole,
+    payload: &RideRatingPayload,
+    settings: &RatingSettings,
+) -> Result<(RideRatingModel, RatingTotals), AppError> {
+    let tags: Vec<String> = payload.tags.iter().map(|t| t.trim().to_string()).collect();
+    let comment = payload
+        .comment
+        .as_ref()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+
+    let txn = db.begin().await?;
+
+    let am = RideRatingActiveModel {
+        id: sea_orm::ActiveValue::NotSet,
+        tenant_id: Set(ride.tenant_id),
+        ride_id: Set(ride.id),
+        rater_user_id: Set(rater_user_id),
+        ratee_user_id: Set(ratee_user_id),
+        rater_role: Set(role.as_str().to_string()),
+        stars: Set(payload.stars),
+        tags: Set(json!(tags)),
+        comment: Set(comment),
+        ..Default::default()
+    };
+
+    // (ride_id, rater_user_id) is unique: a second tap loses the race here
+    let rating = am.insert(&txn).await.map_err(|e| match e.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => {
+            AppError::Conflict("You have already rated this ride".into())
+        }
+        _ => AppError::Db(e),
+    })?;
+
+    let totals = match role {
+        RaterRole::Rider => {
+            add_driver_rating(&txn, ride.tenant_id, ratee_user_id, payload.stars, settings).await?
+        }
+        RaterRole::Driver => add_rider_rating(&txn, ratee_user_id, payload.stars, settings).await?,
+    };
+
+    txn.commit().await?;
+
+    Ok((rating, totals))
+}
+
+/// POST /rides/{id}/rating
+///
+/// The rider rates the driver or the driver rates the rider, once each,
+/// within `ratings.window_hours` of completion.
+pub async fn rate_ride_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    ride_id: Uuid,
+    payload: web::Json<RideRatingPayload>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+
+    validate_ride_rating(&payload)
+        .map_err(actix_web::error::ErrorUnprocessableEntity)?;
+
+    let ride = RideEntity::find_by_id(ride_id)
+        .one(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("Ride not found"))?;
+
+    let (role, ratee_user_id) = if ride.rider_id == user.id {
+        (RaterRole::Rider, ride.driver_id)
+    } else if ride.driver_id == Some(user.id) {
+        (RaterRole::Driver, Some(ride.rider_id))
+    } else {
+        return Err(actix_web::error::ErrorForbidden(
+            "You are not allowed to access this ride",
+        ));
+    };
+
+    let (Some(ratee_user_id), Some(completed_at)) = (ratee_user_id, ride.completed_at) else {
+        return Err(actix_web::error::ErrorBadRequest("Only completed rides can be rated"));
+    };
+    if ride.status != RideStatus::Completed {
+        return Err(actix_web::error::ErrorBadRequest("Only completed rides can be rated"));
+    }
+
+    let settings = load_tenant_settings(db.get_ref(), ride.tenant_id).await?.ratings;
+    let closes_at = completed_at.to_utc() + chrono::Duration::hours(settings.window_hours);
+    if chrono::Utc::now() > closes_at {
+        return Err(actix_web::error::ErrorBadRequest(
+            "The rating window for this ride has closed",
+        ));
+    }
+
+    let (rating, totals) = save_rating(
+        db.get_ref(),
+        &ride,
+        user.id,
+        ratee_user_id,
+        role,
+        &payload,
+        &settings,
+    )
+    .await?;
+
+    let _ = record_ride_event(
+        db.get_ref(),
+        &ride,
+        Some(user.id),
+        "ride_rated",
+        json!({
+            "rater_role": role.as_str(),
+            "ratee_user_id": ratee_user_id,
+            "stars": rating.stars,
+            "tags": rating.tags,
+            "ratee_flag": totals.change.map(FlagChange::as_str),
+        }),
+    )
+    .await;
+
+    // drivers: keep flag changes on their timeline for review (best-effort)
+    if let (RaterRole::Rider, Some(change)) = (role, totals.change) {
+        let driver = DriverEntity::find()
+            .filter(DriverColumn::TenantId.eq(ride.tenant_id))
+            .filter(DriverColumn::UserId.eq(ratee_user_id))
+            .one(db.get_ref())
+            .await
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+        if let Some(driver) = driver {
+            let ev = DriverEventActiveModel {
+                id: sea_orm::ActiveValue::NotSet,
+                tenant_id: Set(ride.tenant_id),
+                driver_id: Set(driver.id),
+                actor_user_id: Set(Some(user.id)),
+                kind: Set(format!("rating_{}", change.as_str())),
+                payload: Set(Some(json!({
+                    "ride_id": ride.id,
+                    "rating": rating_summary_datum(totals.sum, totals.count, totals.flagged_at),
+                    "flag_below": settings.flag_below,
+                }))),
+                ..Default::default()
+            };
+            let _ = ev.insert(db.get_ref()).await;
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Rating saved",
+        "data": ride_rating_datum(&rating)
+    })))
+}
@@ -3,8 +3,9 @@ use actix_web::{web, HttpRequest, HttpResponse, Error};
 use actix_web::error::ErrorForbidden;
 use sea_orm::{
     EntityTrait, ActiveModelTrait, ColumnTrait, QueryFilter, Set, DatabaseConnection, QueryOrder,
-    QuerySelect,
+    QuerySelect, TransactionTrait,
 };
+use sea_orm::sea_query::Expr;
 use serde_json::json;
 use uuid::Uuid;
 
@@ -12,23 +13,55 @@ use crate::entity::ride::{
     Entity as RideEntity,
     Column as RideColumn,
     ActiveModel as RideActiveModel,
+    Model as RideModel,
 };
 
 use crate::entity::user::{
     Model as UserModel,
 };
+use crate::entity::driver::{
+    Entity as DriverEntity,
+    Column as DriverColumn,
+};
+use crate::entity::driver_event::ActiveModel as DriverEventActiveModel;
+use crate::error::AppError;
 
-use crate::requests::structures::{RideRequestPayload, RideListQuery};
-use crate::requests::validation::validate_ride_request;
+use crate::requests::structures::{
+    RideRequestPayload, RideEstimatePayload, RideListQuery, RideCancelPayload, DriverCancelPayload,
+    RideStartPayload,
+};
+use crate::requests::validation::{validate_ride_estimate, validate_ride_request, validate_ride_start};
+use crate::services::pricing_service::{
+    estimate_fare, final_fare, rate_card_for, QUOTE_LOCATION_TOLERANCE_KM,
+};
+use crate::services::pool_service::{pool_datum, pool_trip_for_driver};
+use crate::services::promo_service::{
+    check_promo, consume_promo, promo_datum, promo_discount, release_promo, reserve_promo,
+    reserved_promo,
+};
+use crate::services::ride_stop_service::{insert_ride_stops, load_ride_stops, load_stops_for_rides};
+use crate::utils::jwt_util::{decode_quote_token, generate_quote_token, RideQuoteClaims};
+use crate::utils::ride_quote::redeem_quote;
 use crate::jresponse::ride_jresponse::ride_datum;
-use qrush::queue::enqueue;
+use qrush::queue::{enqueue, enqueue_in};
 use crate::qrushes::jobs::dispatch_ride_job::DispatchRideJob;
+use crate::qrushes::jobs::scheduled_ride_reminder_job::ScheduledRideReminderJob;
+use crate::qrushes::jobs::start_scheduled_ride_job::StartScheduledRideJob;
 use crate::utils::surge::{record_demand, current_multiplier};
 use crate::utils::current_user::get_current_user;
+use crate::utils::pickup_pin::{
+    clear_pin_attempts, failed_pin_attempts, generate_pickup_pin, record_failed_pin_attempt,
+};
 use crate::ws::notify_user; // 🔔 WebSocket notifications
 use crate::entity::ride_event::ActiveModel as RideEventActiveModel;
-
-/// Simple Haversine distance in KM (for naive fare on completion)
+use crate::services::ride_transition_service::{record_ride_event, transition_ride, RideTransition};
+use crate::services::tenant_service::load_tenant_settings;
+use crate::utils::dispatch_state::{clear_offers, exclude_driver, offered_drivers, remove_offer};
+use crate::types::ride_status::{RideActor, RideStatus};
+use crate::types::money::Money;
+use crate::types::tenant_settings::SchedulingSettings;
+
+/// Simple Haversine distance in KM
 fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     let r = 6371.0_f64; // Earth radius in km
     let dlat = (lat2 - lat1).to_radians();
@@ -51,7 +84,250 @@ fn ensure_rider(user: &UserModel) -> Result<(), Error> {
     Ok(())
 }
 
+fn ensure_driver(user: &UserModel) -> Result<(), Error> {
+    if !user.driver {
+        return Err(ErrorForbidden("Not a driver account"));
+    }
+    Ok(())
+}
+
+/// Straight-line distance from the driver's last known position to pickup
+async fn driver_distance_to_pickup_km(
+    db: &DatabaseConnection,
+    ride: &RideModel,
+    driver_user_id: Uuid,
+) -> Result<Option<f64>, Error> {
+    let driver = DriverEntity::find()
+        .filter(DriverColumn::TenantId.eq(ride.tenant_id))
+        .filter(DriverColumn::UserId.eq(driver_user_id))
+        .one(db)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    Ok(driver.and_then(|d| match (d.lat, d.lon) {
+        (Some(lat), Some(lon)) => Some(haversine_km(lat, lon, ride.pickup_lat, ride.pickup_lon)),
+        _ => None,
+    }))
+}
+
+/// Restart the driver's idle clock (longest-idle dispatch), best-effort
+async fn mark_driver_idle(db: &DatabaseConnection, tenant_id: Uuid, driver_user_id: Uuid) {
+    let res = DriverEntity::update_many()
+        .col_expr(
+            DriverColumn::IdleSince,
+            Expr::value(Some(chrono::DateTime::<chrono::FixedOffset>::from(chrono::Utc::now()))),
+        )
+        .filter(DriverColumn::TenantId.eq(tenant_id))
+        .filter(DriverColumn::UserId.eq(driver_user_id))
+        .exec(db)
+        .await;
+    if let Err(e) = res {
+        tracing::warn!("failed to mark driver {} idle: {}", driver_user_id, e);
+    }
+}
+
+
+
+/// `ride_datum` with the ride's stops (its place on a pooled trip, its promo code)
+pub(crate) async fn ride_json(db: &DatabaseConnection, ride: &RideModel) -> Result<serde_json::Value, Error> {
+    let stops = load_ride_stops(db, ride.id).await?;
+    let mut data = ride_datum(ride, &stops);
+    if let Some(pool) = pool_datum(db, ride).await? {
+        data["pool"] = pool;
+    }
+    if let Some(promo) = promo_datum(db, ride).await? {
+        data["promo"] = promo;
+    }
+    Ok(data)
+}
+
+/// The quote must be unexpired, ours, and for this very trip
+fn verify_quote(
+    quote_id: &str,
+    user_id: Uuid,
+    tenant_id: Uuid,
+    payload: &RideRequestPayload,
+) -> Result<RideQuoteClaims, Error> {
+    let invalid = |message: &str| -> Error {
+        AppError::Validation {
+            field: "quote_id".into(),
+            message: message.into(),
+        }
+        .into()
+    };
+
+    let claims = decode_quote_token(quote_id).ok_or_else(|| invalid("Quote is invalid or has expired"))?;
 
+    if claims.sub != user_id || claims.tenant_id != tenant_id {
+        return Err(invalid("Quote belongs to another rider"));
+    }
+    if claims.tier != payload.tier {
+        return Err(invalid("Quote is for a different tier"));
+    }
+
+    let pickup_drift = haversine_km(claims.pickup_lat, claims.pickup_lon, payload.pickup.lat, payload.pickup.lon);
+    let dest_drift = haversine_km(claims.dest_lat, claims.dest_lon, payload.destination.lat, payload.destination.lon);
+    let stops_match = claims.stops.len() == payload.stops.len()
+        && claims.stops.iter().zip(&payload.stops).all(|(q, s)| {
+            haversine_km(q.0, q.1, s.lat, s.lon) <= QUOTE_LOCATION_TOLERANCE_KM
+        });
+    if pickup_drift > QUOTE_LOCATION_TOLERANCE_KM || dest_drift > QUOTE_LOCATION_TOLERANCE_KM || !stops_match {
+        return Err(invalid("Quote is for a different trip; request a new estimate"));
+    }
+
+    Ok(claims)
+}
+
+/// A booking must fall inside the tenant's scheduling window and, if the
+/// tenant caps it, not overbook the pickup zone around that time
+async fn check_schedule(
+    db: &DatabaseConnection,
+    tenant_id: Uuid,
+    scheduling: &SchedulingSettings,
+    scheduled_for: chrono::DateTime<chrono::Utc>,
+    pickup: (f64, f64),
+) -> Result<(), Error> {
+    let now = chrono::Utc::now();
+    let invalid = |message: String| -> Error {
+        AppError::Validation {
+            field: "scheduled_for".into(),
+            message,
+        }
+        .into()
+    };
+
+    if scheduled_for < now + chrono::Duration::minutes(scheduling.min_lead_mins) {
+        return Err(invalid(format!(
+            "Scheduled pickup must be at least {} minutes ahead; request an immediate ride instead",
+            scheduling.min_lead_mins
+        )));
+    }
+    if scheduled_for > now + chrono::Duration::days(scheduling.max_days_ahead) {
+        return Err(invalid(format!(
+            "Rides can be booked at most {} days ahead",
+            scheduling.max_days_ahead
+        )));
+    }
+
+    let Some(cap) = scheduling.max_overlapping_per_zone else {
+        return Ok(());
+    };
+
+    let window = chrono::Duration::minutes(scheduling.overlap_window_mins);
+    let nearby_times = RideEntity::find()
+        .filter(RideColumn::TenantId.eq(tenant_id))
+        .filter(RideColumn::Status.eq(RideStatus::Scheduled))
+        .filter(RideColumn::ScheduledFor.gte(scheduled_for - window))
+        .filter(RideColumn::ScheduledFor.lte(scheduled_for + window))
+        .all(db)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let overlapping = nearby_times
+        .iter()
+        .filter(|r| {
+            haversine_km(r.pickup_lat, r.pickup_lon, pickup.0, pickup.1) <= scheduling.zone_radius_km
+        })
+        .count() as u64;
+
+    if overlapping >= cap {
+        return Err(AppError::Conflict(
+            "Too many rides are already scheduled around that pickup time and place".into(),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Queue the dispatch start and rider reminders for a scheduled ride
+async fn schedule_ride_jobs(ride: &RideModel, scheduling: &SchedulingSettings) {
+    let Some(scheduled_for) = ride.scheduled_for.map(|t| t.to_utc()) else {
+        return;
+    };
+    let now = chrono::Utc::now();
+    let secs_until = |at: chrono::DateTime<chrono::Utc>| (at - now).num_seconds().max(0) as u64;
+
+    let start_at = scheduled_for - chrono::Duration::minutes(scheduling.dispatch_lead_mins);
+    if let Err(e) = enqueue_in(StartScheduledRideJob { ride_id: ride.id }, secs_until(start_at)).await {
+        tracing::error!("Failed to enqueue StartScheduledRideJob for {}: {:?}", ride.id, e);
+    }
+
+    for &minutes_before in &scheduling.reminder_mins_before {
+        let remind_at = scheduled_for - chrono::Duration::minutes(minutes_before);
+        if remind_at <= now {
+            continue;
+        }
+
+        let job = ScheduledRideReminderJob {
+            ride_id: ride.id,
+            minutes_before,
+        };
+        if let Err(e) = enqueue_in(job, secs_until(remind_at)).await {
+            tracing::warn!("Failed to enqueue ScheduledRideReminderJob for {}: {:?}", ride.id, e);
+        }
+    }
+}
+
+/// POST /rides/estimate
+pub async fn estimate_ride_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    payload: web::Json<RideEstimatePayload>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+
+    ensure_rider(&user)?;
+
+    let tenant_id = user
+        .tenant_id
+        .ok_or_else(|| actix_web::error::ErrorForbidden("User has no primary tenant"))?;
+
+    validate_ride_estimate(&payload)?;
+
+    let pickup = (payload.pickup.lat, payload.pickup.lon);
+    let dest = (payload.destination.lat, payload.destination.lon);
+
+    let stops: Vec<(f64, f64)> = payload.stops.iter().map(|s| (s.lat, s.lon)).collect();
+    let mut estimate = estimate_fare(db.get_ref(), tenant_id, &payload.tier, pickup, &stops, dest, None).await?;
+    let settings = load_tenant_settings(db.get_ref(), tenant_id).await?;
+
+    // the quote caps the fare itself; a promo code comes off at completion
+    let expires_at = chrono::Utc::now() + chrono::Duration::seconds(settings.pricing.quote_ttl_secs as i64);
+    let quote_id = generate_quote_token(&RideQuoteClaims {
+        sub: user.id,
+        tenant_id,
+        tier: payload.tier.clone(),
+        pickup_lat: pickup.0,
+        pickup_lon: pickup.1,
+        dest_lat: dest.0,
+        dest_lon: dest.1,
+        stops,
+        surge_multiplier: estimate.surge_multiplier,
+        fare_total: estimate.fare.total.clone(),
+        jti: Uuid::new_v4(),
+        exp: expires_at.timestamp() as usize,
+    })?;
+
+    // 🔹 promo code: checked, not reserved, until the ride is requested
+    if let Some(code) = payload.promo_code.as_deref() {
+        let promo = check_promo(db.get_ref(), tenant_id, user.id, &payload.tier, code).await?;
+        let discount = promo_discount(&promo, &estimate.fare.total);
+        estimate.fare.apply_promo(&promo.code, discount);
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Ride estimate",
+        "data": {
+            "tier": payload.tier,
+            "estimate": estimate,
+            "quote_id": quote_id,
+            "quote_expires_at": expires_at.to_rfc3339(),
+        }
+    })))
+}
 
 /// POST /rides/request
 pub async fn request_ride_service(
@@ -74,11 +350,65 @@ pub async fn request_ride_service(
     let pickup = &payload.pickup;
     let dest = &payload.destination;
 
+    // 🔹 a valid quote locks the surge and caps the fare the rider was shown
+    let quote = match payload.quote_id.as_deref() {
+        Some(quote_id) => Some(verify_quote(quote_id, user_id, tenant_id, &payload)?),
+        None => None,
+    };
+
+    let settings = load_tenant_settings(db.get_ref(), tenant_id).await?;
+
+    // co-riders' detours are planned on pickup -> drop-off only
+    if settings.pool.is_pool_tier(&payload.tier) && !payload.stops.is_empty() {
+        return Err(AppError::Validation {
+            field: "stops".into(),
+            message: "Pooled rides can't have stops".into(),
+        }
+        .into());
+    }
+
+    // 🔹 advance booking: checked now, dispatched later
+    let scheduled_for = payload.scheduled_for;
+    if let Some(at) = scheduled_for {
+        check_schedule(db.get_ref(), tenant_id, &settings.scheduling, at, (pickup.lat, pickup.lon)).await?;
+    }
+
     // 🔹 record demand & compute surge (best-effort)
-    let _ = record_demand(tenant_id, pickup.lat, pickup.lon).await;
-    let surge_multiplier = current_multiplier(tenant_id, pickup.lat, pickup.lon)
-        .await
-        .unwrap_or(1.0);
+    if scheduled_for.is_none() {
+        let _ = record_demand(tenant_id, pickup.lat, pickup.lon).await;
+    }
+    let surge_multiplier = match &quote {
+        Some(q) => q.surge_multiplier,
+        // priced when its dispatch starts
+        None if scheduled_for.is_some() => 1.0,
+        None => current_multiplier(tenant_id, pickup.lat, pickup.lon)
+            .await
+            .unwrap_or(1.0),
+    };
+    let status = match scheduled_for {
+        Some(_) => RideStatus::Scheduled,
+        None => RideStatus::Requested,
+    };
+
+    // 🔹 promo code: the discount held now is an estimate; completion charges the real one
+    let promo_hold = match payload.promo_code.as_deref() {
+        Some(code) => {
+            let promo = check_promo(db.get_ref(), tenant_id, user_id, &payload.tier, code).await?;
+            let stops: Vec<(f64, f64)> = payload.stops.iter().map(|s| (s.lat, s.lon)).collect();
+            let estimate = estimate_fare(
+                db.get_ref(),
+                tenant_id,
+                &payload.tier,
+                (pickup.lat, pickup.lon),
+                &stops,
+                (dest.lat, dest.lon),
+                Some(surge_multiplier),
+            )
+            .await?;
+            Some((promo.code.clone(), promo_discount(&promo, &estimate.fare.total)))
+        }
+        None => None,
+    };
 
     let mut am = RideActiveModel {
         id: sea_orm::ActiveValue::NotSet,
@@ -93,12 +423,50 @@ pub async fn request_ride_service(
         dest_address: Set(dest.address.clone()),
         tier: Set(payload.tier.clone()),
         payment_method_id: Set(payload.payment_method_id.clone()),
-        status: Set("requested".to_string()),
+        status: Set(status),
+        scheduled_for: Set(scheduled_for.map(Into::into)),
+        currency: Set(settings.currency.clone()),
+        surge_multiplier: Set(surge_multiplier),
+        quote: Set(quote.as_ref().map(|q| json!({
+            "surge_multiplier": q.surge_multiplier,
+            "fare_total": q.fare_total,
+            "dest": { "lat": q.dest_lat, "lon": q.dest_lon },
+            "expires_at": q.exp,
+        }))),
         ..Default::default()
     };
 
+    // every check passed: the quote is spent on this ride
+    if let Some(q) = &quote {
+        let ttl_secs = q.exp as i64 - chrono::Utc::now().timestamp();
+        let fresh = redeem_quote(q.jti, ttl_secs)
+            .await
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+        if !fresh {
+            return Err(AppError::Validation {
+                field: "quote_id".into(),
+                message: "Quote was already used for another ride; request a new estimate".into(),
+            }
+            .into());
+        }
+    }
+
+    // ride and its stops land together
+    let txn = db
+        .begin()
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
     let ride = am
-        .insert(db.get_ref())
+        .insert(&txn)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let stops = insert_ride_stops(&txn, &ride, &payload.stops).await?;
+    if let Some((code, discount)) = &promo_hold {
+        reserve_promo(&txn, &ride, code, discount).await?;
+    }
+
+    txn.commit()
         .await
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
@@ -108,7 +476,11 @@ pub async fn request_ride_service(
         tenant_id: Set(tenant_id),
         ride_id: Set(ride.id),
         actor_user_id: Set(Some(user_id)),
-        kind: Set("ride_requested".to_string()),
+        kind: Set(match scheduled_for {
+            Some(_) => "ride_scheduled",
+            None => "ride_requested",
+        }
+        .to_string()),
         payload: Set(Some(json!({
             "pickup": {
                 "lat": pickup.lat,
@@ -123,6 +495,10 @@ pub async fn request_ride_service(
             "tier": payload.tier,
             "payment_method_id": payload.payment_method_id,
             "surge_multiplier": surge_multiplier,
+            "quoted": quote.is_some(),
+            "scheduled_for": scheduled_for,
+            "stops": payload.stops.len(),
+            "promo_code": promo_hold.as_ref().map(|(code, _)| code),
         }))),
         ..Default::default()
     };
@@ -130,23 +506,34 @@ pub async fn request_ride_service(
 
 
 
-    // 🔹 enqueue dispatch job
-    if let Err(e) = enqueue(DispatchRideJob { ride_id: ride.id }).await {
-        tracing::error!("Failed to enqueue DispatchRideJob for {}: {:?}", ride.id, e);
-    } else {
-        tracing::info!("Enqueued DispatchRideJob for {}", ride.id);
+    match scheduled_for {
+        // 🔹 dispatch starts `dispatch_lead_mins` before pickup
+        Some(_) => schedule_ride_jobs(&ride, &settings.scheduling).await,
+        // 🔹 enqueue dispatch job
+        None => {
+            if let Err(e) = enqueue(DispatchRideJob { ride_id: ride.id }).await {
+                tracing::error!("Failed to enqueue DispatchRideJob for {}: {:?}", ride.id, e);
+            } else {
+                tracing::info!("Enqueued DispatchRideJob for {}", ride.id);
+            }
+        }
     }
 
-    let data = ride_datum(&ride);
+    let data = ride_datum(&ride, &stops);
 
     Ok(HttpResponse::Ok().json(json!({
         "status": 200,
         "code": 200,
-        "message": "Ride requested",
+        "message": if scheduled_for.is_some() { "Ride scheduled" } else { "Ride requested" },
         "data": {
             "ride": data,
             "pricing": {
-                "surge_multiplier": surge_multiplier
+                "surge_multiplier": surge_multiplier,
+                "quote_locked": quote.is_some(),
+                "promo": promo_hold.as_ref().map(|(code, discount)| json!({
+                    "code": code,
+                    "estimated_discount": discount,
+                })),
             }
         }
     })))
@@ -184,7 +571,11 @@ pub async fn get_ride_service(
         ));
     }
 
-    let data = ride_datum(&ride);
+    let mut data = ride_json(db.get_ref(), &ride).await?;
+    // rider's eyes only; ride_datum is shared with drivers
+    if matches!(ride.status, RideStatus::Accepted | RideStatus::DriverArrived) {
+        data["pickup_pin"] = json!(ride.pickup_pin);
+    }
 
     Ok(HttpResponse::Ok().json(json!({
         "status": 200,
@@ -226,7 +617,12 @@ pub async fn list_rides_service(
         .await
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
-    let items: Vec<_> = rides.iter().map(ride_datum).collect();
+    let ride_ids: Vec<Uuid> = rides.iter().map(|r| r.id).collect();
+    let stops = load_stops_for_rides(db.get_ref(), &ride_ids).await?;
+    let items: Vec<_> = rides
+        .iter()
+        .map(|r| ride_datum(r, stops.get(&r.id).map(Vec::as_slice).unwrap_or(&[])))
+        .collect();
 
     Ok(HttpResponse::Ok().json(json!({
         "status": 200,
@@ -263,40 +659,122 @@ pub async fn accept_ride_service(
         None => return Err(actix_web::error::ErrorNotFound("Ride not found")),
     };
 
-    // driver_user must match assigned driver_id
-    if ride.driver_id != Some(user_id) {
+    // Broadcast round: nobody is assigned yet, any driver holding an offer may claim it
+    let broadcast_offer = ride.status == RideStatus::Assigned && ride.driver_id.is_none();
+
+    if broadcast_offer {
+        let offered = offered_drivers(ride.id)
+            .await
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+        if !offered.contains(&user_id) {
+            return Err(actix_web::error::ErrorForbidden(
+                "You were not offered this ride",
+            ));
+        }
+    } else if ride.driver_id != Some(user_id) {
+        // driver_user must match assigned driver_id
         return Err(actix_web::error::ErrorForbidden(
             "You are not the assigned driver for this ride",
         ));
     }
 
-    if ride.status != "assigned" {
-        return Err(actix_web::error::ErrorBadRequest(
-            "Ride is not in 'assigned' status",
-        ));
+    // offer lapsed; ExpireRideOfferJob will move the ride on
+    if ride.offer_expires_at.is_some_and(|t| t < chrono::Utc::now()) {
+        return Err(AppError::Conflict("Ride offer has expired".into()).into());
     }
 
-    let mut ride_am: RideActiveModel = ride.into();
-    ride_am.status = Set("accepted".to_string());
+    // PIN the rider will hand the driver at pickup
+    let settings = load_tenant_settings(db.get_ref(), ride.tenant_id).await?;
+    let pickup_pin = settings
+        .pickup_pin
+        .required_for(&ride.tier)
+        .then(generate_pickup_pin);
 
-    let updated = ride_am
-        .update(db.get_ref())
+    // one driver's accepts run one at a time: the driver row stays locked until commit
+    let txn = db
+        .begin()
         .await
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
-    let ev = RideEventActiveModel {
-        id: sea_orm::ActiveValue::NotSet,
-        tenant_id: Set(updated.tenant_id),
-        ride_id: Set(updated.id),
-        actor_user_id: Set(Some(user_id)),
-        kind: Set("ride_accepted".to_string()),
-        payload: Set(Some(json!({
-            "status": updated.status,
-            "driver_user_id": updated.driver_id,
-        }))),
-        ..Default::default()
+    DriverEntity::find()
+        .filter(DriverColumn::TenantId.eq(ride.tenant_id))
+        .filter(DriverColumn::UserId.eq(user_id))
+        .lock_exclusive()
+        .one(&txn)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    // first pooled ride of a driver opens their shared trip; later ones joined one in dispatch
+    let pool_trip_id = if settings.pool.is_pool_tier(&ride.tier) {
+        Some(pool_trip_for_driver(&txn, &ride, user_id).await?)
+    } else {
+        None
     };
-    let _ = ev.insert(db.get_ref()).await;
+
+    // a driver can hold offers for several rides at once (broadcast, a
+    // re-dispatch after a timeout); only one may be claimed, bar co-riders
+    let engaged = RideEntity::find()
+        .filter(RideColumn::DriverId.eq(user_id))
+        .filter(RideColumn::Status.is_in(RideStatus::DRIVER_ENGAGED))
+        .all(&txn)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    if engaged.iter().any(|r| r.blocks_accept(ride.id, pool_trip_id)) {
+        return Err(AppError::Conflict("You already have an active ride".into()).into());
+    }
+
+    // First accept wins: the version check turns every later claim into a 409
+    let updated = transition_ride(
+        &txn,
+        ride,
+        RideTransition {
+            to: RideStatus::Accepted,
+            actor: RideActor::Driver,
+            actor_user_id: Some(user_id),
+            event_kind: "ride_accepted",
+            event_payload: json!({
+                "driver_user_id": user_id,
+                "broadcast": broadcast_offer,
+            }),
+        },
+        |am| {
+            am.driver_id = Set(Some(user_id));
+            am.accepted_at = Set(Some(chrono::Utc::now().into()));
+            am.offer_expires_at = Set(None);
+            am.pickup_pin = Set(pickup_pin.clone());
+            am.pool_trip_id = Set(pool_trip_id);
+        },
+    )
+    .await?;
+
+    txn.commit()
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    // acceptance rate + idle clock stops while on the ride (best-effort)
+    let stats = DriverEntity::update_many()
+        .col_expr(
+            DriverColumn::OffersAccepted,
+            Expr::col(DriverColumn::OffersAccepted).add(1),
+        )
+        .col_expr(
+            DriverColumn::IdleSince,
+            Expr::value(Option::<chrono::DateTime<chrono::FixedOffset>>::None),
+        )
+        .filter(DriverColumn::TenantId.eq(updated.tenant_id))
+        .filter(DriverColumn::UserId.eq(user_id))
+        .exec(db.get_ref())
+        .await;
+    if let Err(e) = stats {
+        tracing::warn!("failed to update stats for driver {}: {}", user_id, e);
+    }
+
+    // 🔔 everyone else who held an offer loses it
+    if broadcast_offer {
+        withdraw_offers(updated.id, Some(user_id), "accepted_by_another_driver").await;
+    }
 
     // 🔔 notify rider & driver
     let payload = json!({
@@ -305,12 +783,14 @@ pub async fn accept_ride_service(
         "driver_user_id": updated.driver_id,
     });
 
-    // rider
-    let _ = notify_user(updated.rider_id, "ride_accepted", payload.clone()).await;
+    // rider (only they get the PIN)
+    let mut rider_payload = payload.clone();
+    rider_payload["pickup_pin"] = json!(updated.pickup_pin);
+    let _ = notify_user(updated.rider_id, "ride_accepted", rider_payload).await;
     // driver (you)
     let _ = notify_user(user_id, "ride_accepted_for_driver", payload.clone()).await;
 
-    let data = ride_datum(&updated);
+    let data = ride_json(db.get_ref(), &updated).await?;
 
     Ok(HttpResponse::Ok().json(json!({
         "status": 200,
@@ -343,27 +823,46 @@ pub async fn reject_ride_service(
         None => return Err(actix_web::error::ErrorNotFound("Ride not found")),
     };
 
+    // Broadcast round: declining only drops this driver's offer
+    if ride.status == RideStatus::Assigned && ride.driver_id.is_none() {
+        return decline_broadcast_offer(db.get_ref(), ride, user_id).await;
+    }
+
     if ride.driver_id != Some(user_id) {
         return Err(actix_web::error::ErrorForbidden(
             "You are not the assigned driver for this ride",
         ));
     }
 
-    if ride.status != "assigned" {
-        return Err(actix_web::error::ErrorBadRequest(
-            "Ride is not in 'assigned' status",
-        ));
+    // accepted rides go through /driver-cancel instead
+    if ride.status != RideStatus::Assigned {
+        return Err(AppError::InvalidTransition {
+            from: ride.status,
+            to: RideStatus::Requested,
+        }
+        .into());
     }
 
     // Simple behaviour for now: clear driver_id, set back to "requested"
-    let mut ride_am: RideActiveModel = ride.into();
-    ride_am.driver_id = Set(None);
-    ride_am.status = Set("requested".to_string());
-
-    let updated = ride_am
-        .update(db.get_ref())
-        .await
-        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let updated = transition_ride(
+        db.get_ref(),
+        ride,
+        RideTransition {
+            to: RideStatus::Requested,
+            actor: RideActor::Driver,
+            actor_user_id: Some(user_id),
+            event_kind: "ride_rejected",
+            event_payload: json!({
+                "driver_user_id": user_id,
+            }),
+        },
+        |am| {
+            am.driver_id = Set(None);
+            am.pool_trip_id = Set(None);
+            am.offer_expires_at = Set(None);
+        },
+    )
+    .await?;
 
     // 🔔 notify rider that driver rejected
     let payload = json!({
@@ -374,9 +873,15 @@ pub async fn reject_ride_service(
     let _ = notify_user(updated.rider_id, "ride_rejected_by_driver", payload.clone()).await;
     let _ = notify_user(user_id, "ride_rejected_for_driver", payload.clone()).await;
 
-    // (Optionally enqueue again for re-dispatch later)
+    // 🔹 re-dispatch, skipping this driver from now on
+    if let Err(e) = exclude_driver(updated.id, user_id).await {
+        tracing::warn!("failed to exclude driver {} from ride {}: {}", user_id, updated.id, e);
+    }
+    if let Err(e) = enqueue(DispatchRideJob { ride_id: updated.id }).await {
+        tracing::error!("Failed to enqueue DispatchRideJob for {}: {:?}", updated.id, e);
+    }
 
-    let data = ride_datum(&updated);
+    let data = ride_json(db.get_ref(), &updated).await?;
 
     Ok(HttpResponse::Ok().json(json!({
         "status": 200,
@@ -386,11 +891,211 @@ pub async fn reject_ride_service(
     })))
 }
 
+/// A driver declines their copy of a broadcast offer. The ride only goes
+/// back to dispatch once every offered driver has declined.
+async fn decline_broadcast_offer(
+    db: &DatabaseConnection,
+    ride: RideModel,
+    user_id: Uuid,
+) -> Result<HttpResponse, Error> {
+    let offered = offered_drivers(ride.id)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    if !offered.contains(&user_id) {
+        return Err(actix_web::error::ErrorForbidden(
+            "You were not offered this ride",
+        ));
+    }
+
+    let remaining = remove_offer(ride.id, user_id)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    if let Err(e) = exclude_driver(ride.id, user_id).await {
+        tracing::warn!("failed to exclude driver {} from ride {}: {}", user_id, ride.id, e);
+    }
+
+    let _ = record_ride_event(
+        db,
+        &ride,
+        Some(user_id),
+        "ride_offer_declined",
+        json!({
+            "driver_user_id": user_id,
+            "remaining_offers": remaining,
+        }),
+    )
+    .await;
+
+    let _ = notify_user(
+        user_id,
+        "ride_rejected_for_driver",
+        json!({ "ride_id": ride.id, "status": ride.status }),
+    )
+    .await;
+
+    if remaining > 0 {
+        return Ok(HttpResponse::Ok().json(json!({
+            "status": 200,
+            "code": 200,
+            "message": "Ride offer declined",
+            "data": ride_json(db, &ride).await?
+        })));
+    }
+
+    // last open offer gone: back to dispatch
+    let result = transition_ride(
+        db,
+        ride.clone(),
+        RideTransition {
+            to: RideStatus::Requested,
+            actor: RideActor::Driver,
+            actor_user_id: Some(user_id),
+            event_kind: "ride_rejected",
+            event_payload: json!({
+                "driver_user_id": user_id,
+                "broadcast": true,
+            }),
+        },
+        |am| {
+            am.offer_expires_at = Set(None);
+        },
+    )
+    .await;
+
+    // someone accepted / the offer expired meanwhile; our decline still stands
+    let updated = match result {
+        Ok(r) => r,
+        Err(AppError::Conflict(_)) => ride,
+        Err(e) => return Err(e.into()),
+    };
+
+    if updated.status == RideStatus::Requested {
+        let payload = json!({
+            "ride_id": updated.id,
+            "status": updated.status,
+        });
+        let _ = notify_user(updated.rider_id, "ride_rejected_by_driver", payload).await;
+
+        if let Err(e) = enqueue(DispatchRideJob { ride_id: updated.id }).await {
+            tracing::error!("Failed to enqueue DispatchRideJob for {}: {:?}", updated.id, e);
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Ride offer declined",
+        "data": ride_json(db, &updated).await?
+    })))
+}
+
+/// Close the ride's open broadcast offers and tell the holders
+/// (except `keep`, usually the driver who just won the ride).
+async fn withdraw_offers(ride_id: Uuid, keep: Option<Uuid>, reason: &str) {
+    let holders = match clear_offers(ride_id).await {
+        Ok(h) => h,
+        Err(e) => {
+            tracing::warn!("failed to clear offers for ride {}: {}", ride_id, e);
+            return;
+        }
+    };
+
+    let payload = json!({
+        "ride_id": ride_id,
+        "reason": reason,
+    });
+
+    for driver_user_id in holders.into_iter().filter(|d| Some(*d) != keep) {
+        let _ = notify_user(driver_user_id, "ride_offer_withdrawn", payload.clone()).await;
+    }
+}
+
+/// Refuse to start unless the driver has the rider's PIN (when the ride has
+/// one). Wrong PINs are counted per ride and logged as `pickup_pin_failed`;
+/// after `max_attempts` in `lockout_secs` the driver gets 429 until the
+/// window resets. Returns whether a PIN was checked.
+async fn verify_pickup_pin(
+    db: &DatabaseConnection,
+    ride: &RideModel,
+    driver_user_id: Uuid,
+    pin: Option<&str>,
+) -> Result<bool, Error> {
+    let Some(expected) = ride.pickup_pin.as_deref() else {
+        return Ok(false);
+    };
+    let settings = load_tenant_settings(db, ride.tenant_id).await?.pickup_pin;
+    if !settings.required_for(&ride.tier) {
+        return Ok(false);
+    }
+
+    // best-effort limiter: if Redis is down the PIN is still checked
+    match failed_pin_attempts(ride.id).await {
+        Ok((fails, ttl)) if fails >= settings.max_attempts => {
+            return Err(AppError::RateLimited {
+                retry_after_seconds: ttl,
+            }
+            .into());
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("PIN attempt lookup failed for ride {}: {}", ride.id, e),
+    }
+
+    let Some(pin) = pin else {
+        return Err(AppError::Validation {
+            field: "pin".into(),
+            message: "Ask the rider for their pickup PIN".into(),
+        }
+        .into());
+    };
+
+    if pin == expected {
+        if let Err(e) = clear_pin_attempts(ride.id).await {
+            tracing::warn!("failed to clear PIN attempts for ride {}: {}", ride.id, e);
+        }
+        return Ok(true);
+    }
+
+    let attempt = match record_failed_pin_attempt(ride.id, settings.lockout_secs).await {
+        Ok(n) => Some(n),
+        Err(e) => {
+            tracing::warn!("failed to count PIN attempt for ride {}: {}", ride.id, e);
+            None
+        }
+    };
+    let locked = attempt.is_some_and(|n| n >= settings.max_attempts);
+
+    let event = json!({
+        "driver_user_id": driver_user_id,
+        "attempt": attempt,
+        "max_attempts": settings.max_attempts,
+        "locked": locked,
+    });
+    if let Err(e) = record_ride_event(db, ride, Some(driver_user_id), "pickup_pin_failed", event).await {
+        tracing::warn!("failed to record pickup_pin_failed for ride {}: {}", ride.id, e);
+    }
+
+    if locked {
+        return Err(AppError::RateLimited {
+            retry_after_seconds: settings.lockout_secs,
+        }
+        .into());
+    }
+
+    Err(AppError::Validation {
+        field: "pin".into(),
+        message: "Incorrect pickup PIN".into(),
+    }
+    .into())
+}
+
 /// POST /rides/{id}/start
 pub async fn start_ride_service(
     req: HttpRequest,
     db: web::Data<DatabaseConnection>,
     ride_id: Uuid,
+    payload: web::Json<RideStartPayload>,
 ) -> Result<HttpResponse, Error> {
     let user = get_current_user(&req, db.get_ref()).await?;
 
@@ -415,34 +1120,257 @@ pub async fn start_ride_service(
         ));
     }
 
-    if ride.status != "accepted" {
-        return Err(actix_web::error::ErrorBadRequest(
-            "Ride is not in 'accepted' status",
+    validate_ride_start(&payload)?;
+
+    // only a ride that can actually start burns a PIN attempt
+    if !ride.status.can_transition(RideStatus::InProgress, RideActor::Driver) {
+        return Err(AppError::InvalidTransition {
+            from: ride.status,
+            to: RideStatus::InProgress,
+        }
+        .into());
+    }
+
+    let pin_verified = verify_pickup_pin(db.get_ref(), &ride, user_id, payload.pin.as_deref()).await?;
+
+    let updated = transition_ride(
+        db.get_ref(),
+        ride,
+        RideTransition {
+            to: RideStatus::InProgress,
+            actor: RideActor::Driver,
+            actor_user_id: Some(user_id),
+            event_kind: "ride_started",
+            event_payload: json!({ "pin_verified": pin_verified }),
+        },
+        |am| {
+            am.started_at = Set(Some(chrono::Utc::now().into()));
+        },
+    )
+    .await?;
+
+    let payload = json!({
+        "ride_id": updated.id,
+        "status": updated.status,
+    });
+
+    let _ = notify_user(updated.rider_id, "ride_started", payload.clone()).await;
+    let _ = notify_user(user_id, "ride_started_for_driver", payload.clone()).await;
+
+    let data = ride_json(db.get_ref(), &updated).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Ride started",
+        "data": data
+    })))
+}
+
+/// POST /rides/{id}/arrived
+///
+/// Only once the driver's last reported position is inside the tenant's
+/// pickup geofence. Starts the rider's free waiting window (rate card).
+pub async fn driver_arrived_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    ride_id: Uuid,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+
+    ensure_driver(&user)?;
+
+    let user_id = user.id;
+
+    let ride = RideEntity::find()
+        .filter(RideColumn::Id.eq(ride_id))
+        .one(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("Ride not found"))?;
+
+    if ride.driver_id != Some(user_id) {
+        return Err(actix_web::error::ErrorForbidden(
+            "You are not the assigned driver for this ride",
         ));
     }
 
-    let mut ride_am: RideActiveModel = ride.into();
-    ride_am.status = Set("in_progress".to_string());
+    let settings = load_tenant_settings(db.get_ref(), ride.tenant_id).await?;
+
+    // 🔹 geofence check against the last location the driver sent
+    let distance_m = driver_distance_to_pickup_km(db.get_ref(), &ride, user_id)
+        .await?
+        .map(|km| km * 1000.0)
+        .ok_or_else(|| AppError::Validation {
+            field: "location".into(),
+            message: "No recent location; update your location first".into(),
+        })?;
+
+    if distance_m > settings.arrival.geofence_m {
+        return Err(AppError::Validation {
+            field: "location".into(),
+            message: format!(
+                "You are {:.0} m from pickup; arrive within {:.0} m",
+                distance_m, settings.arrival.geofence_m
+            ),
+        }
+        .into());
+    }
+
+    let rate_card =
+        rate_card_for(db.get_ref(), ride.tenant_id, &ride.tier, ride.currency.clone()).await?;
+    let arrived_at = chrono::Utc::now();
+
+    let updated = transition_ride(
+        db.get_ref(),
+        ride,
+        RideTransition {
+            to: RideStatus::DriverArrived,
+            actor: RideActor::Driver,
+            actor_user_id: Some(user_id),
+            event_kind: "driver_arrived",
+            event_payload: json!({ "distance_m": distance_m }),
+        },
+        |am| {
+            am.arrived_at = Set(Some(arrived_at.into()));
+        },
+    )
+    .await?;
 
-    let updated = ride_am
-        .update(db.get_ref())
+    let payload = json!({
+        "ride_id": updated.id,
+        "status": updated.status,
+        "arrived_at": arrived_at,
+        "free_wait_mins": rate_card.free_wait_mins,
+        "wait_per_minute": Money::new(rate_card.wait_per_minute, updated.currency.clone()),
+    });
+
+    let _ = notify_user(updated.rider_id, "driver_arrived", payload.clone()).await;
+    let _ = notify_user(user_id, "driver_arrived_for_driver", payload.clone()).await;
+
+    let data = ride_json(db.get_ref(), &updated).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Arrived at pickup",
+        "data": data
+    })))
+}
+
+/// POST /rides/{id}/no-show
+///
+/// The driver ends the ride after waiting `arrival.no_show_after_mins` at
+/// pickup; the rider is charged the no-show fee, which goes to the driver.
+pub async fn rider_no_show_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    ride_id: Uuid,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+
+    ensure_driver(&user)?;
+
+    let user_id = user.id;
+
+    let ride = RideEntity::find()
+        .filter(RideColumn::Id.eq(ride_id))
+        .one(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("Ride not found"))?;
+
+    if ride.driver_id != Some(user_id) {
+        return Err(actix_web::error::ErrorForbidden(
+            "You are not the assigned driver for this ride",
+        ));
+    }
+
+    let Some(arrived_at) = ride.arrived_at.map(|t| t.to_utc()) else {
+        return Err(AppError::InvalidTransition {
+            from: ride.status,
+            to: RideStatus::RiderNoShow,
+        }
+        .into());
+    };
+
+    let settings = load_tenant_settings(db.get_ref(), ride.tenant_id).await?;
+    let now = chrono::Utc::now();
+    let waited_secs = now.signed_duration_since(arrived_at).num_seconds().max(0);
+    let required_secs = settings.arrival.no_show_after_mins * 60;
+
+    if waited_secs < required_secs {
+        return Err(actix_web::error::ErrorBadRequest(format!(
+            "Wait {} more seconds before reporting a no-show",
+            required_secs - waited_secs
+        )));
+    }
+
+    let fee = Money::new(settings.arrival.no_show_fee_minor, ride.currency.clone());
+
+    let updated = transition_ride(
+        db.get_ref(),
+        ride,
+        RideTransition {
+            to: RideStatus::RiderNoShow,
+            actor: RideActor::Driver,
+            actor_user_id: Some(user_id),
+            event_kind: "rider_no_show",
+            event_payload: json!({
+                "driver_user_id": user_id,
+                "waited_secs": waited_secs,
+                "no_show_fee": fee,
+            }),
+        },
+        |am| {
+            am.no_show_fee = Set(Some(fee.amount_minor));
+        },
+    )
+    .await?;
+
+    mark_driver_idle(db.get_ref(), updated.tenant_id, user_id).await;
+    release_promo(db.get_ref(), updated.id).await;
+
+    // the fee is the driver's; keep it on their timeline (best-effort)
+    let driver = DriverEntity::find()
+        .filter(DriverColumn::TenantId.eq(updated.tenant_id))
+        .filter(DriverColumn::UserId.eq(user_id))
+        .one(db.get_ref())
         .await
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
+    if let Some(driver) = driver {
+        let ev = DriverEventActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            tenant_id: Set(updated.tenant_id),
+            driver_id: Set(driver.id),
+            actor_user_id: Set(Some(user_id)),
+            kind: Set("no_show_fee_earned".to_string()),
+            payload: Set(Some(json!({
+                "ride_id": updated.id,
+                "fee": fee,
+            }))),
+            ..Default::default()
+        };
+        let _ = ev.insert(db.get_ref()).await;
+    }
+
     let payload = json!({
         "ride_id": updated.id,
         "status": updated.status,
+        "waited_secs": waited_secs,
+        "no_show_fee": fee,
     });
 
-    let _ = notify_user(updated.rider_id, "ride_started", payload.clone()).await;
-    let _ = notify_user(user_id, "ride_started_for_driver", payload.clone()).await;
+    let _ = notify_user(updated.rider_id, "ride_rider_no_show", payload.clone()).await;
+    let _ = notify_user(user_id, "ride_rider_no_show_for_driver", payload.clone()).await;
 
-    let data = ride_datum(&updated);
+    let data = ride_json(db.get_ref(), &updated).await?;
 
     Ok(HttpResponse::Ok().json(json!({
         "status": 200,
         "code": 200,
-        "message": "Ride started",
+        "message": "Ride ended as rider no-show",
         "data": data
     })))
 }
@@ -477,58 +1405,64 @@ pub async fn complete_ride_service(
         ));
     }
 
-    if ride.status != "in_progress" {
-        return Err(actix_web::error::ErrorBadRequest(
-            "Ride is not in 'in_progress' status",
-        ));
+    let completed_at = chrono::Utc::now();
+    let mut fare = final_fare(db.get_ref(), &ride, completed_at).await?;
+
+    // 🔹 the promo reserved at request comes off the final fare
+    let promo = reserved_promo(db.get_ref(), ride.id).await?;
+    if let Some((_, code)) = &promo {
+        let discount = promo_discount(code, &fare.breakdown.total);
+        fare.breakdown.apply_promo(&code.code, discount);
     }
 
-    let mut ride_am: RideActiveModel = ride.into();
-    ride_am.status = Set("completed".to_string());
+    let fare_json = serde_json::to_value(&fare)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
-    let updated = ride_am
-        .update(db.get_ref())
+    // 🔹 the promo use is spent with the completion, or not at all
+    let txn = db
+        .begin()
         .await
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
+    let updated = transition_ride(
+        &txn,
+        ride,
+        RideTransition {
+            to: RideStatus::Completed,
+            actor: RideActor::Driver,
+            actor_user_id: Some(user_id),
+            event_kind: "ride_completed",
+            event_payload: json!({ "fare": fare_json }),
+        },
+        |am| {
+            am.completed_at = Set(Some(completed_at.into()));
+            am.fare = Set(Some(fare_json.clone()));
+        },
+    )
+    .await?;
+
+    if let Some(line) = &fare.breakdown.promo {
+        consume_promo(&txn, updated.id, &line.discount).await?;
+    }
 
-    // Naive fare calculation: straight-line distance * 20
-    let distance_km = haversine_km(
-        updated.pickup_lat,
-        updated.pickup_lon,
-        updated.dest_lat,
-        updated.dest_lon,
-    );
-    let base_per_km = 20.0_f64;
-    let fare_amount = (distance_km * base_per_km).round(); // e.g. ₹
+    txn.commit()
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
-    // record even
-    let ev = RideEventActiveModel {
-        id: sea_orm::ActiveValue::NotSet,
-        tenant_id: Set(updated.tenant_id),
-        ride_id: Set(updated.id),
-        actor_user_id: Set(Some(user_id)),
-        kind: Set("ride_completed".to_string()),
-        payload: Set(Some(json!({
-            "status": updated.status,
-            "distance_km": distance_km,
-            "fare_amount": fare_amount,
-        }))),
-        ..Default::default()
-    };
-    let _ = ev.insert(db.get_ref()).await;
+    mark_driver_idle(db.get_ref(), updated.tenant_id, user_id).await;
 
     let payload = json!({
         "ride_id": updated.id,
         "status": updated.status,
-        "distance_km": distance_km,
-        "fare_amount": fare_amount,
+        "distance_km": fare.distance_km,
+        "fare_amount": fare.breakdown.total,
+        "fare": fare_json,
     });
 
     let _ = notify_user(updated.rider_id, "ride_completed", payload.clone()).await;
     let _ = notify_user(user_id, "ride_completed_for_driver", payload.clone()).await;
 
-    let data = ride_datum(&updated);
+    let data = ride_json(db.get_ref(), &updated).await?;
 
     Ok(HttpResponse::Ok().json(json!({
         "status": 200,
@@ -537,3 +1471,245 @@ pub async fn complete_ride_service(
         "data": data
     })))
 }
+
+/// POST /rides/{id}/cancel
+pub async fn cancel_ride_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    ride_id: Uuid,
+    payload: web::Json<RideCancelPayload>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+
+    ensure_rider(&user)?;
+
+    let user_id = user.id;
+
+    let ride = RideEntity::find()
+        .filter(RideColumn::Id.eq(ride_id))
+        .one(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let ride = match ride {
+        Some(r) => r,
+        None => return Err(actix_web::error::ErrorNotFound("Ride not found")),
+    };
+
+    if ride.rider_id != user_id {
+        return Err(actix_web::error::ErrorForbidden(
+            "You are not allowed to cancel this ride",
+        ));
+    }
+
+    // Fee depends on how long ago the driver accepted and how close they are
+    let settings = load_tenant_settings(db.get_ref(), ride.tenant_id).await?;
+    let now = chrono::Utc::now();
+
+    let accepted_secs_ago = match ride.status {
+        RideStatus::Accepted | RideStatus::DriverArrived => ride
+            .accepted_at
+            .map(|t| now.signed_duration_since(t).num_seconds()),
+        _ => None,
+    };
+
+    let driver_user_id = ride.driver_id;
+    let broadcast_offer = ride.status == RideStatus::Assigned && driver_user_id.is_none();
+    let driver_distance_km = match driver_user_id {
+        Some(d) => driver_distance_to_pickup_km(db.get_ref(), &ride, d).await?,
+        None => None,
+    };
+
+    let fee = settings
+        .cancellation
+        .rider_fee(accepted_secs_ago, driver_distance_km, ride.currency.clone());
+    let reason = payload.reason.as_str();
+
+    // Driver is released from the ride so dispatch can use them again
+    let updated = transition_ride(
+        db.get_ref(),
+        ride,
+        RideTransition {
+            to: RideStatus::Cancelled,
+            actor: RideActor::Rider,
+            actor_user_id: Some(user_id),
+            event_kind: "ride_cancelled",
+            event_payload: json!({
+                "cancelled_by": "rider",
+                "reason": reason,
+                "note": payload.note,
+                "driver_user_id": driver_user_id,
+                "accepted_secs_ago": accepted_secs_ago,
+                "driver_distance_km": driver_distance_km,
+                "cancellation_fee": fee,
+            }),
+        },
+        |am| {
+            am.driver_id = Set(None);
+            am.pool_trip_id = Set(None);
+            am.cancelled_at = Set(Some(now.into()));
+            am.cancel_reason = Set(Some(reason.to_string()));
+            am.cancellation_fee = Set(Some(fee.amount_minor));
+        },
+    )
+    .await?;
+
+    let payload = json!({
+        "ride_id": updated.id,
+        "status": updated.status,
+        "reason": reason,
+        "cancellation_fee": fee,
+    });
+
+    release_promo(db.get_ref(), updated.id).await;
+
+    let _ = notify_user(updated.rider_id, "ride_cancelled", payload.clone()).await;
+    if let Some(driver_user_id) = driver_user_id {
+        mark_driver_idle(db.get_ref(), updated.tenant_id, driver_user_id).await;
+        let _ = notify_user(driver_user_id, "ride_cancelled_for_driver", payload.clone()).await;
+    }
+    if broadcast_offer {
+        withdraw_offers(updated.id, None, "cancelled_by_rider").await;
+    }
+
+    let data = ride_json(db.get_ref(), &updated).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Ride cancelled",
+        "data": data
+    })))
+}
+
+/// POST /rides/{id}/driver-cancel
+pub async fn driver_cancel_ride_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    ride_id: Uuid,
+    payload: web::Json<DriverCancelPayload>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+
+    ensure_driver(&user)?;
+
+    let user_id = user.id;
+
+    let ride = RideEntity::find()
+        .filter(RideColumn::Id.eq(ride_id))
+        .one(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let ride = match ride {
+        Some(r) => r,
+        None => return Err(actix_web::error::ErrorNotFound("Ride not found")),
+    };
+
+    if ride.driver_id != Some(user_id) {
+        return Err(actix_web::error::ErrorForbidden(
+            "You are not the assigned driver for this ride",
+        ));
+    }
+
+    // offers that were never accepted go through /reject instead
+    if !matches!(ride.status, RideStatus::Accepted | RideStatus::DriverArrived) {
+        return Err(AppError::InvalidTransition {
+            from: ride.status,
+            to: RideStatus::Requested,
+        }
+        .into());
+    }
+
+    let reason = payload.reason.as_str();
+
+    // Back to dispatch without this driver
+    let updated = transition_ride(
+        db.get_ref(),
+        ride,
+        RideTransition {
+            to: RideStatus::Requested,
+            actor: RideActor::Driver,
+            actor_user_id: Some(user_id),
+            event_kind: "ride_cancelled_by_driver",
+            event_payload: json!({
+                "driver_user_id": user_id,
+                "reason": reason,
+                "note": payload.note,
+            }),
+        },
+        |am| {
+            am.driver_id = Set(None);
+            am.pool_trip_id = Set(None);
+            am.accepted_at = Set(None);
+            am.arrived_at = Set(None);
+            am.pickup_pin = Set(None);
+        },
+    )
+    .await?;
+
+    if let Err(e) = exclude_driver(updated.id, user_id).await {
+        tracing::warn!("failed to exclude driver {} from ride {}: {}", user_id, updated.id, e);
+    }
+
+    mark_driver_idle(db.get_ref(), updated.tenant_id, user_id).await;
+
+    // driver cancellation metric (best-effort)
+    let driver = DriverEntity::find()
+        .filter(DriverColumn::TenantId.eq(updated.tenant_id))
+        .filter(DriverColumn::UserId.eq(user_id))
+        .one(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    if let Some(driver) = driver {
+        let bumped = DriverEntity::update_many()
+            .col_expr(
+                DriverColumn::CancellationCount,
+                Expr::col(DriverColumn::CancellationCount).add(1),
+            )
+            .filter(DriverColumn::Id.eq(driver.id))
+            .exec(db.get_ref())
+            .await;
+        if let Err(e) = bumped {
+            tracing::warn!("failed to bump cancellation_count for driver {}: {}", driver.id, e);
+        }
+
+        let ev = DriverEventActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            tenant_id: Set(updated.tenant_id),
+            driver_id: Set(driver.id),
+            actor_user_id: Set(Some(user_id)),
+            kind: Set("driver_cancelled_ride".to_string()),
+            payload: Set(Some(json!({
+                "ride_id": updated.id,
+                "reason": reason,
+            }))),
+            ..Default::default()
+        };
+        let _ = ev.insert(db.get_ref()).await;
+    }
+
+    // 🔹 re-dispatch
+    if let Err(e) = enqueue(DispatchRideJob { ride_id: updated.id }).await {
+        tracing::error!("Failed to enqueue DispatchRideJob for {}: {:?}", updated.id, e);
+    }
+
+    let payload = json!({
+        "ride_id": updated.id,
+        "status": updated.status,
+        "reason": reason,
+    });
+
+    let _ = notify_user(updated.rider_id, "ride_reassigning", payload.clone()).await;
+    let _ = notify_user(user_id, "ride_released_for_driver", payload.clone()).await;
+
+    let data = ride_json(db.get_ref(), &updated).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Ride cancelled by driver; finding a new driver",
+        "data": data
+    })))
+}
@@ -0,0 +1,209 @@
+// src/services/ride_stop_service.rs
+use std::collections::HashMap;
+
+use actix_web::{web, HttpRequest, HttpResponse, Error};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, Set,
+};
+use sea_orm::sea_query::Expr;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::entity::prelude::RideStop as RideStopEntity;
+use crate::entity::ride::{Entity as RideEntity, Model as RideModel};
+use crate::entity::ride_stop::{
+    ActiveModel as RideStopActiveModel,
+    Column as RideStopColumn,
+    Model as RideStopModel,
+};
+use crate::error::AppError;
+use crate::jresponse::ride_jresponse::ride_datum;
+use crate::requests::structures::CoordPayload;
+use crate::services::ride_transition_service::record_ride_event;
+use crate::types::ride_status::RideStatus;
+use crate::utils::current_user::get_current_user;
+use crate::ws::notify_user;
+
+/// A ride's stops in visiting order
+pub async fn load_ride_stops<C: ConnectionTrait>(
+    db: &C,
+    ride_id: Uuid,
+) -> Result<Vec<RideStopModel>, AppError> {
+    Ok(RideStopEntity::find()
+        .filter(RideStopColumn::RideId.eq(ride_id))
+        .order_by_asc(RideStopColumn::Position)
+        .all(db)
+        .await?)
+}
+
+/// Stops for a page of rides, keyed by ride id
+pub async fn load_stops_for_rides<C: ConnectionTrait>(
+    db: &C,
+    ride_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<RideStopModel>>, AppError> {
+    let stops = RideStopEntity::find()
+        .filter(RideStopColumn::RideId.is_in(ride_ids.iter().copied()))
+        .order_by_asc(RideStopColumn::Position)
+        .all(db)
+        .await?;
+
+    let mut by_ride: HashMap<Uuid, Vec<RideStopModel>> = HashMap::new();
+    for stop in stops {
+        by_ride.entry(stop.ride_id).or_default().push(stop);
+    }
+    Ok(by_ride)
+}
+
+/// Save the requested stops of a new ride (positions 1..n)
+pub async fn insert_ride_stops<C: ConnectionTrait>(
+    db: &C,
+    ride: &RideModel,
+    stops: &[CoordPayload],
+) -> Result<Vec<RideStopModel>, AppError> {
+    let mut saved = Vec::with_capacity(stops.len());
+    for (i, stop) in stops.iter().enumerate() {
+        let am = RideStopActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            tenant_id: Set(ride.tenant_id),
+            ride_id: Set(ride.id),
+            position: Set(i as i32 + 1),
+            lat: Set(stop.lat),
+            lon: Set(stop.lon),
+            address: Set(stop.address.clone()),
+            ..Default::default()
+        };
+        saved.push(am.insert(db).await?);
+    }
+    Ok(saved)
+}
+
+/// Which end of a stop the driver is marking
+#[derive(Clone, Copy)]
+enum StopMark {
+    Arrived,
+    Departed,
+}
+
+/// POST /rides/{id}/stops/{position}/arrive
+pub async fn arrive_at_stop_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    ride_id: Uuid,
+    position: i32,
+) -> Result<HttpResponse, Error> {
+    mark_stop(req, db, ride_id, position, StopMark::Arrived).await
+}
+
+/// POST /rides/{id}/stops/{position}/depart
+pub async fn depart_stop_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    ride_id: Uuid,
+    position: i32,
+) -> Result<HttpResponse, Error> {
+    mark_stop(req, db, ride_id, position, StopMark::Departed).await
+}
+
+async fn mark_stop(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    ride_id: Uuid,
+    position: i32,
+    mark: StopMark,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+
+    let ride = RideEntity::find_by_id(ride_id)
+        .one(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("Ride not found"))?;
+
+    if ride.driver_id != Some(user.id) {
+        return Err(actix_web::error::ErrorForbidden(
+            "You are not the assigned driver for this ride",
+        ));
+    }
+    if ride.status != RideStatus::InProgress {
+        return Err(actix_web::error::ErrorBadRequest(
+            "Stops can only be marked while the ride is in progress",
+        ));
+    }
+
+    let stops = load_ride_stops(db.get_ref(), ride.id).await?;
+    let Some(stop) = stops.iter().find(|s| s.position == position) else {
+        return Err(actix_web::error::ErrorNotFound("Stop not found"));
+    };
+
+    let now = chrono::Utc::now();
+    let (column, kind) = match mark {
+        StopMark::Arrived => {
+            // stops are visited in order
+            if stops
+                .iter()
+                .any(|s| s.position < position && s.departed_at.is_none())
+            {
+                return Err(AppError::Validation {
+                    field: "position".into(),
+                    message: "Leave the previous stop first".into(),
+                }
+                .into());
+            }
+            (RideStopColumn::ArrivedAt, "ride_stop_arrived")
+        }
+        StopMark::Departed => {
+            if stop.arrived_at.is_none() {
+                return Err(AppError::Validation {
+                    field: "position".into(),
+                    message: "Stop has not been reached yet".into(),
+                }
+                .into());
+            }
+            (RideStopColumn::DepartedAt, "ride_stop_departed")
+        }
+    };
+
+    // only the first mark counts
+    let now_tz: chrono::DateTime<chrono::FixedOffset> = now.into();
+    let res = RideStopEntity::update_many()
+        .col_expr(column, Expr::value(Some(now_tz)))
+        .col_expr(RideStopColumn::UpdatedAt, Expr::value(now_tz))
+        .filter(RideStopColumn::Id.eq(stop.id))
+        .filter(column.is_null())
+        .exec(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    if res.rows_affected == 0 {
+        return Err(AppError::Conflict(format!("Stop {position} is already marked")).into());
+    }
+
+    let wait_secs = match (mark, stop.arrived_at) {
+        (StopMark::Departed, Some(arrived)) => Some((now - arrived.to_utc()).num_seconds().max(0)),
+        _ => None,
+    };
+
+    let payload = json!({
+        "ride_id": ride.id,
+        "position": position,
+        "wait_secs": wait_secs,
+    });
+
+    if let Err(e) = record_ride_event(db.get_ref(), &ride, Some(user.id), kind, payload.clone()).await {
+        tracing::warn!("failed to record {} for ride {}: {}", kind, ride.id, e);
+    }
+    let _ = notify_user(ride.rider_id, kind, payload).await;
+
+    let stops = load_ride_stops(db.get_ref(), ride.id).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": match mark {
+            StopMark::Arrived => "Arrived at stop",
+            StopMark::Departed => "Departed stop",
+        },
+        "data": ride_datum(&ride, &stops)
+    })))
+}
@@ -0,0 +1,193 @@
+// src/services/ride_tip_service.rs
+use actix_web::{web, HttpRequest, HttpResponse, Error};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::entity::driver::{Column as DriverColumn, Entity as DriverEntity};
+use crate::entity::driver_event::ActiveModel as DriverEventActiveModel;
+use crate::entity::ride::{Entity as RideEntity, Model as RideModel};
+use crate::error::AppError;
+use crate::jresponse::ride_jresponse::ride_datum;
+use crate::requests::structures::RideTipPayload;
+use crate::requests::validation::validate_ride_tip;
+use crate::services::ride_stop_service::load_ride_stops;
+use crate::services::ride_transition_service::update_ride;
+use crate::services::tenant_service::load_tenant_settings;
+use crate::types::fare::RideFare;
+use crate::types::money::Money;
+use crate::types::ride_status::RideStatus;
+use crate::utils::current_user::get_current_user;
+use crate::ws::notify_user;
+
+async fn load_ride(db: &DatabaseConnection, ride_id: Uuid) -> Result<RideModel, Error> {
+    RideEntity::find_by_id(ride_id)
+        .one(db)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("Ride not found"))
+}
+
+/// Response for a ride that already carries a tip
+async fn tip_response(
+    db: &DatabaseConnection,
+    ride: &RideModel,
+    message: &str,
+) -> Result<HttpResponse, Error> {
+    let stops = load_ride_stops(db, ride.id).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": message,
+        "data": {
+            "ride": ride_datum(ride, &stops),
+            "tip": ride.tip_amount.map(|m| Money::new(m, ride.currency.clone())),
+        }
+    })))
+}
+
+/// A ride takes one tip; the same key again is a retry of that tip
+fn existing_tip(ride: &RideModel, idempotency_key: &str) -> Result<bool, Error> {
+    if ride.tip_amount.is_none() {
+        return Ok(false);
+    }
+    if ride.tip_idempotency_key.as_deref() == Some(idempotency_key) {
+        return Ok(true);
+    }
+    Err(AppError::Conflict("This ride has already been tipped".into()).into())
+}
+
+/// POST /rides/{id}/tip
+///
+/// The rider tips the driver once per completed ride, within
+/// `tips.window_hours`. Retrying with the same `idempotency_key` returns the
+/// tip already recorded instead of charging again.
+pub async fn tip_ride_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    ride_id: Uuid,
+    payload: web::Json<RideTipPayload>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+
+    validate_ride_tip(&payload)
+        .map_err(actix_web::error::ErrorUnprocessableEntity)?;
+
+    let ride = load_ride(db.get_ref(), ride_id).await?;
+
+    if ride.rider_id != user.id {
+        return Err(actix_web::error::ErrorForbidden(
+            "You are not allowed to access this ride",
+        ));
+    }
+
+    // 🔹 double tap: hand back the tip we already have
+    if existing_tip(&ride, &payload.idempotency_key)? {
+        return tip_response(db.get_ref(), &ride, "Tip already recorded").await;
+    }
+
+    let settings = load_tenant_settings(db.get_ref(), ride.tenant_id).await?.tips;
+    if !settings.enabled {
+        return Err(actix_web::error::ErrorBadRequest("Tipping is not enabled"));
+    }
+
+    let (Some(driver_id), Some(completed_at)) = (ride.driver_id, ride.completed_at) else {
+        return Err(actix_web::error::ErrorBadRequest("Only completed rides can be tipped"));
+    };
+    if ride.status != RideStatus::Completed {
+        return Err(actix_web::error::ErrorBadRequest("Only completed rides can be tipped"));
+    }
+
+    let closes_at = completed_at.to_utc() + chrono::Duration::hours(settings.window_hours);
+    if chrono::Utc::now() > closes_at {
+        return Err(actix_web::error::ErrorBadRequest(
+            "The tipping window for this ride has closed",
+        ));
+    }
+
+    // a completed ride always has a fare; one we can't read is an error, not a looser cap
+    let fare = ride
+        .fare
+        .clone()
+        .and_then(|f| serde_json::from_value::<RideFare>(f).ok())
+        .ok_or_else(|| AppError::Internal(format!("Ride {} has no readable fare", ride.id)))?;
+    let max_tip = settings.max_tip_minor(fare.breakdown.driver_fare().amount_minor);
+    if payload.amount_minor > max_tip {
+        return Err(AppError::Validation {
+            field: "amount_minor".into(),
+            message: format!(
+                "Tip can be at most {} (minor units of {})",
+                max_tip,
+                ride.currency.code()
+            ),
+        }
+        .into());
+    }
+
+    let tip = Money::new(payload.amount_minor, ride.currency.clone());
+    let key = payload.idempotency_key.clone();
+
+    let saved = update_ride(
+        db.get_ref(),
+        ride,
+        Some(user.id),
+        "ride_tipped",
+        json!({
+            "driver_user_id": driver_id,
+            "tip": tip,
+            "idempotency_key": key,
+        }),
+        |am| {
+            am.tip_amount = Set(Some(tip.amount_minor));
+            am.tip_idempotency_key = Set(Some(key.clone()));
+            am.tipped_at = Set(Some(chrono::Utc::now().into()));
+        },
+    )
+    .await;
+
+    let updated = match saved {
+        Ok(updated) => updated,
+        // both taps passed the check above; whoever lost the race sees the winner's tip
+        Err(AppError::Conflict(msg)) => {
+            let ride = load_ride(db.get_ref(), ride_id).await?;
+            if existing_tip(&ride, &payload.idempotency_key)? {
+                return tip_response(db.get_ref(), &ride, "Tip already recorded").await;
+            }
+            return Err(AppError::Conflict(msg).into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    // the tip is the driver's; keep it on their timeline (best-effort)
+    let driver = DriverEntity::find()
+        .filter(DriverColumn::TenantId.eq(updated.tenant_id))
+        .filter(DriverColumn::UserId.eq(driver_id))
+        .one(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    if let Some(driver) = driver {
+        let ev = DriverEventActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            tenant_id: Set(updated.tenant_id),
+            driver_id: Set(driver.id),
+            actor_user_id: Set(Some(user.id)),
+            kind: Set("tip_received".to_string()),
+            payload: Set(Some(json!({
+                "ride_id": updated.id,
+                "tip": tip,
+            }))),
+            ..Default::default()
+        };
+        let _ = ev.insert(db.get_ref()).await;
+    }
+
+    let _ = notify_user(driver_id, "tip_received", json!({
+        "ride_id": updated.id,
+        "tip": tip,
+    }))
+    .await;
+
+    tip_response(db.get_ref(), &updated, "Tip sent").await
+}
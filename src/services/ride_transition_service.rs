@@ -0,0 +1,169 @@
+// src/services/ride_transition_service.rs
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set,
+    TransactionTrait,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::entity::ride::{
+    ActiveModel as RideActiveModel,
+    Column as RideColumn,
+    Entity as RideEntity,
+    Model as RideModel,
+};
+use crate::entity::ride_event::ActiveModel as RideEventActiveModel;
+use crate::error::AppError;
+use crate::types::ride_status::{RideActor, RideStatus};
+
+/// A requested status change plus the `ride_event` that records it.
+pub struct RideTransition {
+    pub to: RideStatus,
+    pub actor: RideActor,
+    /// Null for system moves (dispatch jobs etc.)
+    pub actor_user_id: Option<Uuid>,
+    /// e.g. "ride_accepted"
+    pub event_kind: &'static str,
+    /// Extra event data; `from` / `status` / `version` are added automatically.
+    pub event_payload: Value,
+}
+
+/// The only way a ride changes status.
+///
+/// Checks the move against the transition table, applies any extra column
+/// changes from `apply`, then saves the ride and records the event in one
+/// transaction. The update only lands if `ride.version` is still the one we
+/// read; otherwise somebody else moved the ride first and we return
+/// `AppError::Conflict` (409).
+pub async fn transition_ride<C, F>(
+    db: &C,
+    ride: RideModel,
+    transition: RideTransition,
+    apply: F,
+) -> Result<RideModel, AppError>
+where
+    C: TransactionTrait,
+    F: FnOnce(&mut RideActiveModel),
+{
+    let from = ride.status;
+    let ride_id = ride.id;
+    let read_version = ride.version;
+
+    if !from.can_transition(transition.to, transition.actor) {
+        return Err(AppError::InvalidTransition {
+            from,
+            to: transition.to,
+        });
+    }
+
+    let mut ride_am: RideActiveModel = ride.into();
+    ride_am.status = Set(transition.to);
+    ride_am.updated_at = Set(chrono::Utc::now().into());
+    apply(&mut ride_am);
+
+    let mut payload = transition.event_payload;
+    if let Value::Object(map) = &mut payload {
+        map.insert("from".into(), json!(from));
+    }
+
+    save_ride(
+        db,
+        ride_id,
+        read_version,
+        ride_am,
+        transition.actor_user_id,
+        transition.event_kind,
+        payload,
+    )
+    .await
+}
+
+/// Save changes to a ride that keeps its status (destination edits, ...).
+///
+/// Same guarantees as `transition_ride`: the update only lands if nobody
+/// moved the ride since we read it, and the event is recorded with it.
+pub async fn update_ride<C, F>(
+    db: &C,
+    ride: RideModel,
+    actor_user_id: Option<Uuid>,
+    event_kind: &'static str,
+    event_payload: Value,
+    apply: F,
+) -> Result<RideModel, AppError>
+where
+    C: TransactionTrait,
+    F: FnOnce(&mut RideActiveModel),
+{
+    let ride_id = ride.id;
+    let read_version = ride.version;
+
+    let mut ride_am: RideActiveModel = ride.into();
+    ride_am.updated_at = Set(chrono::Utc::now().into());
+    apply(&mut ride_am);
+
+    save_ride(db, ride_id, read_version, ride_am, actor_user_id, event_kind, event_payload).await
+}
+
+/// Versioned UPDATE + its `ride_event`, in one transaction
+async fn save_ride<C: TransactionTrait>(
+    db: &C,
+    ride_id: Uuid,
+    read_version: i32,
+    ride_am: RideActiveModel,
+    actor_user_id: Option<Uuid>,
+    event_kind: &'static str,
+    mut payload: Value,
+) -> Result<RideModel, AppError> {
+    let txn = db.begin().await?;
+
+    // UPDATE ride SET ..., version = version + 1 WHERE id = ? AND version = ?
+    let updated = RideEntity::update_many()
+        .set(ride_am)
+        .col_expr(RideColumn::Version, Expr::col(RideColumn::Version).add(1))
+        .filter(RideColumn::Id.eq(ride_id))
+        .filter(RideColumn::Version.eq(read_version))
+        .exec_with_returning(&txn)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            AppError::Conflict(format!(
+                "Ride {ride_id} was modified concurrently; reload and retry"
+            ))
+        })?;
+
+    if let Value::Object(map) = &mut payload {
+        map.insert("status".into(), json!(updated.status));
+        map.insert("version".into(), json!(updated.version));
+    }
+
+    record_ride_event(&txn, &updated, actor_user_id, event_kind, payload).await?;
+
+    txn.commit().await?;
+
+    Ok(updated)
+}
+
+/// Append a `ride_event` that is not tied to a status change
+/// (dispatch attempts, offer bookkeeping, ...).
+pub async fn record_ride_event<C: ConnectionTrait>(
+    db: &C,
+    ride: &RideModel,
+    actor_user_id: Option<Uuid>,
+    kind: &str,
+    payload: Value,
+) -> Result<(), AppError> {
+    let ev = RideEventActiveModel {
+        id: sea_orm::ActiveValue::NotSet,
+        tenant_id: Set(ride.tenant_id),
+        ride_id: Set(ride.id),
+        actor_user_id: Set(actor_user_id),
+        kind: Set(kind.to_string()),
+        payload: Set(Some(payload)),
+        ..Default::default()
+    };
+    ev.insert(db).await?;
+
+    Ok(())
+}
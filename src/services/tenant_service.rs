@@ -1,16 +1,74 @@
 // src/services/tenant_service.rs
 use actix_web::{web, HttpRequest, HttpResponse, Error};
-use sea_orm::{ActiveModelTrait, EntityTrait, Set, DatabaseConnection};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set, DatabaseConnection,
+    ConnectionTrait, PaginatorTrait,
+};
 use serde_json::json;
 use uuid::Uuid;
 
 use crate::dto::tenant::CreateTenantRequest;
-use crate::entity::prelude::Tenant as TenantEntity;
-use crate::entity::tenant::ActiveModel as TenantActiveModel;
-use crate::entity::user::ActiveModel as UserActiveModel;
-use crate::jresponse::tenant_jresponse::tenant_datum;
+use crate::entity::driver::{Column as DriverColumn, Entity as DriverEntity};
+use crate::entity::prelude::{
+    PromoCode as PromoCodeEntity, RateCard as RateCardEntity, RideRating as RideRatingEntity,
+    Tenant as TenantEntity, User as UserEntity,
+};
+use crate::entity::promo_code::{ActiveModel as PromoCodeActiveModel, Column as PromoCodeColumn};
+use crate::entity::rate_card::{ActiveModel as RateCardActiveModel, Column as RateCardColumn};
+use crate::entity::ride::{Column as RideColumn, Entity as RideEntity};
+use crate::entity::tenant::{ActiveModel as TenantActiveModel, Model as TenantModel};
+use crate::entity::ride_rating::Column as RideRatingColumn;
+use crate::entity::user::{ActiveModel as UserActiveModel, Column as UserColumn, Model as UserModel};
+use crate::error::AppError;
+use crate::jresponse::rating_jresponse::rating_summary_datum;
+use crate::jresponse::tenant_jresponse::{promo_code_datum, rate_card_datum, tenant_datum};
+use crate::requests::structures::{PromoCodePayload, RateCardPayload};
+use crate::requests::validation::{
+    validate_promo_code, validate_rate_card, validate_tenant_settings,
+};
+use crate::services::promo_service::{held_uses, normalize_code};
+use crate::types::money::Currency;
+use crate::types::ride_status::RideStatus;
+use crate::types::tenant_settings::TenantSettings;
 use crate::utils::current_user::get_current_user;
 
+/// Load the typed settings for a tenant (defaults if unset)
+pub async fn load_tenant_settings<C: ConnectionTrait>(
+    db: &C,
+    tenant_id: Uuid,
+) -> Result<TenantSettings, AppError> {
+    let tenant = TenantEntity::find_by_id(tenant_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Tenant {tenant_id} not found")))?;
+
+    TenantSettings::from_tenant(&tenant)
+}
+
+/// Current user's primary tenant, only if they own it
+async fn load_owned_tenant(
+    db: &DatabaseConnection,
+    user: &UserModel,
+) -> Result<TenantModel, Error> {
+    let tenant_id = user
+        .tenant_id
+        .ok_or_else(|| actix_web::error::ErrorForbidden("User has no primary tenant"))?;
+
+    let tenant = TenantEntity::find_by_id(tenant_id)
+        .one(db)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("Tenant not found"))?;
+
+    if tenant.owner_user_id != Some(user.id) {
+        return Err(actix_web::error::ErrorForbidden(
+            "Only the tenant owner can manage tenant settings",
+        ));
+    }
+
+    Ok(tenant)
+}
+
 /// POST /tenants
 pub async fn create_tenant_service(
     req: HttpRequest,
@@ -26,6 +84,7 @@ pub async fn create_tenant_service(
         id: sea_orm::ActiveValue::NotSet,
         name: Set(body.name.clone()),
         slug: Set(body.slug.clone()),
+        owner_user_id: Set(Some(user.id)),
         ..Default::default()
     };
 
@@ -52,3 +111,426 @@ pub async fn create_tenant_service(
         "data": j_tenant
     })))
 }
+
+/// GET /tenants/settings
+pub async fn get_tenant_settings_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+    let tenant = load_owned_tenant(db.get_ref(), &user).await?;
+
+    let settings = TenantSettings::from_tenant(&tenant)?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Tenant settings",
+        "data": settings
+    })))
+}
+
+/// PUT /tenants/settings
+pub async fn update_tenant_settings_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    body: web::Json<TenantSettings>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+    let tenant = load_owned_tenant(db.get_ref(), &user).await?;
+
+    let settings = body.into_inner();
+
+    let rate_card_tiers: Vec<String> = RateCardEntity::find()
+        .filter(RateCardColumn::TenantId.eq(tenant.id))
+        .all(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .into_iter()
+        .map(|c| c.tier)
+        .collect();
+    validate_tenant_settings(&settings, &rate_card_tiers)?;
+
+    // read straight from the blob: the rest of it may be what this PUT repairs
+    let current_currency: Option<Currency> = match tenant.settings.get("currency") {
+        Some(v) => serde_json::from_value(v.clone()).ok(),
+        None => Some(Currency::default()),
+    };
+    if current_currency.as_ref() != Some(&settings.currency) {
+        ensure_currency_can_change(db.get_ref(), tenant.id, !rate_card_tiers.is_empty()).await?;
+    }
+
+    let value = serde_json::to_value(&settings)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let mut am: TenantActiveModel = tenant.into();
+    am.settings = Set(value);
+    am.updated_at = Set(chrono::Utc::now().into());
+
+    let tenant = am
+        .update(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Tenant settings updated",
+        "data": tenant_datum(&tenant)
+    })))
+}
+
+/// Stored amounts (rate cards, promo codes) are minor units of the current
+/// currency and open rides are priced in it, so it only changes while
+/// neither exists
+async fn ensure_currency_can_change(
+    db: &DatabaseConnection,
+    tenant_id: Uuid,
+    has_rate_cards: bool,
+) -> Result<(), AppError> {
+    let priced_promos = PromoCodeEntity::find()
+        .filter(PromoCodeColumn::TenantId.eq(tenant_id))
+        .filter(
+            PromoCodeColumn::AmountMinor
+                .is_not_null()
+                .or(PromoCodeColumn::MaxDiscountMinor.is_not_null()),
+        )
+        .count(db)
+        .await?;
+    if has_rate_cards || priced_promos > 0 {
+        return Err(AppError::Conflict(
+            "Rate cards and promo code amounts are in the current currency; remove them before changing it".into(),
+        ));
+    }
+
+    let open_rides = RideEntity::find()
+        .filter(RideColumn::TenantId.eq(tenant_id))
+        .filter(RideColumn::Status.is_in(RideStatus::OPEN))
+        .count(db)
+        .await?;
+    if open_rides > 0 {
+        return Err(AppError::Conflict(
+            "The currency can't change while rides are open".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// GET /tenants/rate-cards
+pub async fn list_rate_cards_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+    let tenant = load_owned_tenant(db.get_ref(), &user).await?;
+
+    let cards = RateCardEntity::find()
+        .filter(RateCardColumn::TenantId.eq(tenant.id))
+        .order_by_asc(RateCardColumn::Tier)
+        .all(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let currency = TenantSettings::from_tenant(&tenant)?.currency;
+    let data: Vec<_> = cards.iter().map(|c| rate_card_datum(c, &currency)).collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Rate cards",
+        "data": data
+    })))
+}
+
+/// PUT /tenants/rate-cards/{tier} — create or replace the card for a tier
+pub async fn upsert_rate_card_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    tier: String,
+    body: web::Json<RateCardPayload>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+    let tenant = load_owned_tenant(db.get_ref(), &user).await?;
+
+    validate_rate_card(&tier, &body)?;
+
+    let existing = RateCardEntity::find()
+        .filter(RateCardColumn::TenantId.eq(tenant.id))
+        .filter(RateCardColumn::Tier.eq(tier.as_str()))
+        .one(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let is_new = existing.is_none();
+    let mut am: RateCardActiveModel = match existing {
+        Some(card) => card.into(),
+        None => RateCardActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            tenant_id: Set(tenant.id),
+            tier: Set(tier.clone()),
+            ..Default::default()
+        },
+    };
+    am.base_fare = Set(body.base_fare);
+    am.per_km = Set(body.per_km);
+    am.per_minute = Set(body.per_minute);
+    am.minimum_fare = Set(body.minimum_fare);
+    am.booking_fee = Set(body.booking_fee);
+    am.wait_per_minute = Set(body.wait_per_minute);
+    am.free_wait_mins = Set(body.free_wait_mins);
+    am.surge_cap = Set(body.surge_cap);
+    am.updated_at = Set(chrono::Utc::now().into());
+
+    let card = if is_new {
+        am.insert(db.get_ref()).await
+    } else {
+        am.update(db.get_ref()).await
+    }
+    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Rate card saved",
+        "data": rate_card_datum(&card, &TenantSettings::from_tenant(&tenant)?.currency)
+    })))
+}
+
+/// DELETE /tenants/rate-cards/{tier} — the tier falls back to the default card
+pub async fn delete_rate_card_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    tier: String,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+    let tenant = load_owned_tenant(db.get_ref(), &user).await?;
+
+    let res = RateCardEntity::delete_many()
+        .filter(RateCardColumn::TenantId.eq(tenant.id))
+        .filter(RateCardColumn::Tier.eq(tier.as_str()))
+        .exec(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    if res.rows_affected == 0 {
+        return Err(actix_web::error::ErrorNotFound("Rate card not found"));
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Rate card deleted",
+        "data": { "tier": tier }
+    })))
+}
+
+/// GET /tenants/flagged-accounts — drivers and riders whose average rating
+/// fell below `ratings.flag_below`
+pub async fn list_flagged_accounts_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+    let tenant = load_owned_tenant(db.get_ref(), &user).await?;
+
+    let drivers = DriverEntity::find()
+        .filter(DriverColumn::TenantId.eq(tenant.id))
+        .filter(DriverColumn::RatingFlaggedAt.is_not_null())
+        .order_by_asc(DriverColumn::RatingFlaggedAt)
+        .all(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    // rider totals live on the account; list riders this tenant's drivers rated
+    let rated_riders: Vec<Uuid> = RideRatingEntity::find()
+        .filter(RideRatingColumn::TenantId.eq(tenant.id))
+        .filter(RideRatingColumn::RaterRole.eq("driver"))
+        .all(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .into_iter()
+        .map(|r| r.ratee_user_id)
+        .collect();
+
+    let riders = UserEntity::find()
+        .filter(UserColumn::Id.is_in(rated_riders))
+        .filter(UserColumn::RiderRatingFlaggedAt.is_not_null())
+        .order_by_asc(UserColumn::RiderRatingFlaggedAt)
+        .all(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let drivers: Vec<_> = drivers
+        .iter()
+        .map(|d| {
+            json!({
+                "driver_id": d.id,
+                "user_id": d.user_id,
+                "rating": rating_summary_datum(d.rating_sum, d.rating_count, d.rating_flagged_at),
+            })
+        })
+        .collect();
+    let riders: Vec<_> = riders
+        .iter()
+        .map(|u| {
+            json!({
+                "user_id": u.id,
+                "email": u.email,
+                "rating": rating_summary_datum(
+                    u.rider_rating_sum,
+                    u.rider_rating_count,
+                    u.rider_rating_flagged_at,
+                ),
+            })
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Flagged accounts",
+        "data": {
+            "flag_below": TenantSettings::from_tenant(&tenant)?.ratings.flag_below,
+            "drivers": drivers,
+            "riders": riders,
+        }
+    })))
+}
+
+/// GET /tenants/promo-codes
+pub async fn list_promo_codes_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+    let tenant = load_owned_tenant(db.get_ref(), &user).await?;
+
+    let promos = PromoCodeEntity::find()
+        .filter(PromoCodeColumn::TenantId.eq(tenant.id))
+        .order_by_asc(PromoCodeColumn::Code)
+        .all(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let currency = TenantSettings::from_tenant(&tenant)?.currency;
+    let mut data = Vec::with_capacity(promos.len());
+    for promo in &promos {
+        let uses = held_uses(db.get_ref(), promo.id, None).await?;
+        data.push(promo_code_datum(promo, &currency, uses));
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Promo codes",
+        "data": data
+    })))
+}
+
+/// PUT /tenants/promo-codes/{code} — create or replace a code
+pub async fn upsert_promo_code_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    code: String,
+    body: web::Json<PromoCodePayload>,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+    let tenant = load_owned_tenant(db.get_ref(), &user).await?;
+
+    let code = normalize_code(&code);
+    if code.len() < 3
+        || code.len() > 32
+        || !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(AppError::Validation {
+            field: "code".into(),
+            message: "code must be 3 to 32 letters, digits, '-' or '_'".into(),
+        }
+        .into());
+    }
+    validate_promo_code(&body)?;
+
+    let existing = PromoCodeEntity::find()
+        .filter(PromoCodeColumn::TenantId.eq(tenant.id))
+        .filter(PromoCodeColumn::Code.eq(code.as_str()))
+        .one(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let is_new = existing.is_none();
+    let mut am: PromoCodeActiveModel = match existing {
+        Some(promo) => promo.into(),
+        None => PromoCodeActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            tenant_id: Set(tenant.id),
+            code: Set(code.clone()),
+            ..Default::default()
+        },
+    };
+    am.kind = Set(body.kind);
+    am.percent = Set(body.percent);
+    am.amount_minor = Set(body.amount_minor);
+    am.max_discount_minor = Set(body.max_discount_minor);
+    am.max_uses = Set(body.max_uses);
+    am.max_uses_per_user = Set(body.max_uses_per_user);
+    am.valid_from = Set(body.valid_from.map(Into::into));
+    am.valid_until = Set(body.valid_until.map(Into::into));
+    am.first_ride_only = Set(body.first_ride_only);
+    am.tiers = Set(json!(body.tiers));
+    am.active = Set(body.active);
+    am.updated_at = Set(chrono::Utc::now().into());
+
+    let promo = if is_new {
+        am.insert(db.get_ref()).await
+    } else {
+        am.update(db.get_ref()).await
+    }
+    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let uses = held_uses(db.get_ref(), promo.id, None).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Promo code saved",
+        "data": promo_code_datum(&promo, &TenantSettings::from_tenant(&tenant)?.currency, uses)
+    })))
+}
+
+/// DELETE /tenants/promo-codes/{code} — deactivates the code; rides that
+/// already reserved it keep their discount
+pub async fn deactivate_promo_code_service(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    code: String,
+) -> Result<HttpResponse, Error> {
+    let user = get_current_user(&req, db.get_ref()).await?;
+    let tenant = load_owned_tenant(db.get_ref(), &user).await?;
+
+    let code = normalize_code(&code);
+    let res = PromoCodeEntity::update_many()
+        .col_expr(PromoCodeColumn::Active, sea_orm::sea_query::Expr::value(false))
+        .col_expr(
+            PromoCodeColumn::UpdatedAt,
+            sea_orm::sea_query::Expr::value(chrono::DateTime::<chrono::FixedOffset>::from(
+                chrono::Utc::now(),
+            )),
+        )
+        .filter(PromoCodeColumn::TenantId.eq(tenant.id))
+        .filter(PromoCodeColumn::Code.eq(code.as_str()))
+        .exec(db.get_ref())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    if res.rows_affected == 0 {
+        return Err(actix_web::error::ErrorNotFound("Promo code not found"));
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": 200,
+        "code": 200,
+        "message": "Promo code deactivated",
+        "data": { "code": code }
+    })))
+}
@@ -0,0 +1,372 @@
+// src/types/fare.rs
+use serde::{Deserialize, Serialize};
+
+use dispatch::pool::PoolShare;
+
+use crate::entity::rate_card::Model as RateCardModel;
+use crate::types::money::{Currency, Money};
+use crate::types::pricing_rule::{Adjustment, PricingRule};
+use crate::types::promo::PromoLine;
+use crate::utils::trip_meter::TrailStats;
+
+/// Prices for one tier of a tenant. Amounts are minor units of `currency`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateCard {
+    pub currency: Currency,
+    pub base_fare: i64,
+    pub per_km: i64,
+    pub per_minute: i64,
+    /// Floor for the ride charge (before the booking fee)
+    pub minimum_fare: i64,
+    /// Flat fee on top of every ride, never surged
+    pub booking_fee: i64,
+    /// Charged per minute of waiting beyond `free_wait_mins` (pickup and each stop)
+    pub wait_per_minute: i64,
+    pub free_wait_mins: f64,
+    /// Highest surge multiplier charged; None = uncapped
+    pub surge_cap: Option<f64>,
+}
+
+impl RateCard {
+    /// Built-in card for tiers without one of their own
+    pub fn default_for(currency: Currency) -> Self {
+        Self {
+            base_fare: currency.to_minor(30.0),
+            per_km: currency.to_minor(20.0),
+            per_minute: currency.to_minor(1.0),
+            minimum_fare: currency.to_minor(50.0),
+            booking_fee: currency.to_minor(10.0),
+            wait_per_minute: currency.to_minor(2.0),
+            free_wait_mins: 3.0,
+            surge_cap: None,
+            currency,
+        }
+    }
+
+    /// Stored card; amounts are in the tenant's currency
+    pub fn from_model(m: &RateCardModel, currency: Currency) -> Self {
+        Self {
+            currency,
+            base_fare: m.base_fare,
+            per_km: m.per_km,
+            per_minute: m.per_minute,
+            minimum_fare: m.minimum_fare,
+            booking_fee: m.booking_fee,
+            wait_per_minute: m.wait_per_minute,
+            free_wait_mins: m.free_wait_mins,
+            surge_cap: m.surge_cap,
+        }
+    }
+
+    fn money(&self, amount_minor: i64) -> Money {
+        Money::new(amount_minor, self.currency.clone())
+    }
+
+    /// Surge multiplier after the card's cap
+    pub fn capped_surge(&self, surge_multiplier: f64) -> f64 {
+        let m = surge_multiplier.max(1.0);
+        match self.surge_cap {
+            Some(cap) => m.min(cap.max(1.0)),
+            None => m,
+        }
+    }
+
+    /// `duration_mins` is time on the move; `wait_mins` is time spent at each
+    /// waiting point (pickup, stops), charged beyond the free allowance and never
+    /// surged. `rules` are the tenant's pricing rules that matched this trip;
+    /// they apply in order after the minimum fare, each as its own line item.
+    pub fn fare(
+        &self,
+        distance_km: f64,
+        duration_mins: f64,
+        wait_mins: &[f64],
+        surge_multiplier: f64,
+        rules: &[&PricingRule],
+    ) -> FareBreakdown {
+        let base = self.money(self.base_fare);
+        let distance = self.money(self.per_km).scale(distance_km);
+        let time = self.money(self.per_minute).scale(duration_mins);
+
+        // surge applies to the ride itself, not to fees
+        let surge_multiplier = self.capped_surge(surge_multiplier);
+        let metered = base.clone() + distance.clone() + time.clone();
+        let surge = metered.scale(surge_multiplier - 1.0);
+
+        let chargeable_wait: f64 = wait_mins
+            .iter()
+            .map(|w| (w - self.free_wait_mins).max(0.0))
+            .sum();
+        let waiting = self.money(self.wait_per_minute).scale(chargeable_wait);
+
+        let mut ride_charge = metered + surge.clone() + waiting.clone();
+        let minimum_fare_adjustment =
+            self.money((self.minimum_fare - ride_charge.amount_minor).max(0));
+        ride_charge = ride_charge + minimum_fare_adjustment.clone();
+
+        let mut adjustments = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let delta = match &rule.adjustment {
+                Adjustment::Flat { amount_minor } => self.money(*amount_minor),
+                Adjustment::Percent { percent } => ride_charge.scale(percent / 100.0),
+                Adjustment::Fixed { amount_minor } => {
+                    self.money(amount_minor - ride_charge.amount_minor)
+                }
+            };
+            // discounts never take the ride below zero
+            let delta = self.money(delta.amount_minor.max(-ride_charge.amount_minor));
+
+            ride_charge = ride_charge + delta.clone();
+            adjustments.push(FareAdjustment {
+                rule: rule.name.clone(),
+                amount: delta,
+            });
+        }
+
+        let booking_fee = self.money(self.booking_fee);
+
+        FareBreakdown {
+            base,
+            distance,
+            time,
+            surge,
+            surge_multiplier,
+            waiting,
+            minimum_fare_adjustment,
+            adjustments,
+            total: ride_charge + booking_fee.clone(),
+            booking_fee,
+            quote_adjustment: None,
+            promo: None,
+            pre_promo_total: None,
+        }
+    }
+}
+
+/// Line-by-line fare, as shown to the rider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FareBreakdown {
+    pub base: Money,
+    pub distance: Money,
+    pub time: Money,
+    /// Extra charged because of surge (already multiplied out)
+    pub surge: Money,
+    /// Multiplier actually charged (after the card's surge cap)
+    pub surge_multiplier: f64,
+    /// Waiting beyond the free allowance
+    pub waiting: Money,
+    /// Top-up so the ride charge reaches the card's minimum fare
+    pub minimum_fare_adjustment: Money,
+    /// Pricing rules that matched, in the order applied
+    pub adjustments: Vec<FareAdjustment>,
+    pub booking_fee: Money,
+    /// Taken off so the ride costs no more than its quote (waiting not included)
+    pub quote_adjustment: Option<Money>,
+    /// Promo code taken off the total, if the ride has one
+    pub promo: Option<PromoLine>,
+    /// Total before the promo; set with `promo`
+    pub pre_promo_total: Option<Money>,
+    pub total: Money,
+}
+
+impl FareBreakdown {
+    /// Hold the fare to the `quoted` total; waiting charges come on top
+    pub fn cap_at_quote(&mut self, quoted: &Money) {
+        if quoted.currency != self.total.currency {
+            return;
+        }
+        let over = self.total.amount_minor - self.waiting.amount_minor - quoted.amount_minor;
+        if over <= 0 {
+            return;
+        }
+        self.total = Money::new(self.total.amount_minor - over, self.total.currency.clone());
+        self.quote_adjustment = Some(Money::new(-over, self.total.currency.clone()));
+    }
+
+    /// Take a promo code's `discount` off the total (never below zero)
+    pub fn apply_promo(&mut self, code: &str, discount: Money) {
+        let discount = Money::new(
+            discount.amount_minor.clamp(0, self.total.amount_minor.max(0)),
+            discount.currency,
+        );
+        self.pre_promo_total = Some(self.total.clone());
+        self.total = Money::new(
+            self.total.amount_minor - discount.amount_minor,
+            self.total.currency.clone(),
+        );
+        self.promo = Some(PromoLine {
+            code: code.to_string(),
+            discount,
+        });
+    }
+
+    /// What the driver earns on (and tips are capped against): the fare
+    /// before any promo, which the platform pays for
+    pub fn driver_fare(&self) -> &Money {
+        self.pre_promo_total.as_ref().unwrap_or(&self.total)
+    }
+}
+
+/// One applied pricing rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FareAdjustment {
+    pub rule: String,
+    pub amount: Money,
+}
+
+/// Where a ride's billed distance / duration came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeterSource {
+    /// Driver GPS trail
+    Gps,
+    /// Straight line pickup -> destination (GPS coverage too poor)
+    Estimate,
+    /// Pooled ride: the rider's share of the shared trip's planned legs
+    Pool,
+}
+
+/// What a finished ride was charged; stored as `ride.fare`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RideFare {
+    pub distance_km: f64,
+    pub duration_mins: f64,
+    pub meter_source: MeterSource,
+    /// Minutes the driver waited at pickup (arrival -> start), if they marked arrival
+    pub pickup_wait_mins: Option<f64>,
+    /// Minutes spent at each stop reached, in stop order
+    pub stop_wait_mins: Vec<f64>,
+    /// Pooled rides: how the trip was split with co-riders
+    pub pool: Option<PoolShare>,
+    /// Trail stats, whether or not they were used
+    pub gps: TrailStats,
+    /// Card the ride was priced with, as it was at completion
+    pub rate_card: RateCard,
+    pub breakdown: FareBreakdown,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card() -> RateCard {
+        RateCard {
+            currency: Currency::default(),
+            base_fare: 3000,
+            per_km: 2000,
+            per_minute: 100,
+            minimum_fare: 5000,
+            booking_fee: 1000,
+            wait_per_minute: 200,
+            free_wait_mins: 3.0,
+            surge_cap: None,
+        }
+    }
+
+    fn inr(amount_minor: i64) -> Money {
+        Money::new(amount_minor, Currency::default())
+    }
+
+    fn rule(adjustment: Adjustment) -> PricingRule {
+        PricingRule {
+            name: "rule".into(),
+            time_window: None,
+            weekdays: vec![],
+            pickup_zone: None,
+            dropoff_zone: None,
+            tiers: vec![],
+            adjustment,
+        }
+    }
+
+    #[test]
+    fn metered_ride_adds_the_booking_fee() {
+        // 3000 base + 10 km + 20 min
+        let fare = card().fare(10.0, 20.0, &[], 1.0, &[]);
+
+        assert_eq!(fare.distance, inr(20000));
+        assert_eq!(fare.time, inr(2000));
+        assert_eq!(fare.surge, inr(0));
+        assert_eq!(fare.minimum_fare_adjustment, inr(0));
+        assert_eq!(fare.total, inr(26000));
+    }
+
+    #[test]
+    fn short_ride_is_topped_up_to_the_minimum_fare() {
+        // 3000 + 1000 + 100 = 4100
+        let fare = card().fare(0.5, 1.0, &[], 1.0, &[]);
+
+        assert_eq!(fare.minimum_fare_adjustment, inr(900));
+        assert_eq!(fare.total, inr(6000));
+    }
+
+    #[test]
+    fn surge_is_capped_and_skips_fees_and_waiting() {
+        let capped = RateCard {
+            surge_cap: Some(1.5),
+            ..card()
+        };
+        // 2 of the 5 minutes at the one stop are past the free wait
+        let fare = capped.fare(10.0, 20.0, &[5.0], 2.0, &[]);
+
+        assert_eq!(fare.surge_multiplier, 1.5);
+        assert_eq!(fare.surge, inr(12500));
+        assert_eq!(fare.waiting, inr(400));
+        assert_eq!(fare.total, inr(25000 + 12500 + 400 + 1000));
+
+        // a multiplier under 1 never discounts
+        assert_eq!(card().fare(10.0, 20.0, &[], 0.5, &[]).surge, inr(0));
+    }
+
+    #[test]
+    fn rules_apply_in_order_and_never_go_below_zero() {
+        let percent = rule(Adjustment::Percent { percent: 10.0 });
+        let discount = rule(Adjustment::Flat {
+            amount_minor: -1_000_000,
+        });
+
+        let fare = card().fare(10.0, 20.0, &[], 1.0, &[&percent]);
+        assert_eq!(fare.adjustments[0].amount, inr(2500));
+        assert_eq!(fare.total, inr(27500 + 1000));
+
+        let fare = card().fare(10.0, 20.0, &[], 1.0, &[&percent, &discount]);
+        assert_eq!(fare.adjustments[1].amount, inr(-27500));
+        // the booking fee is still charged
+        assert_eq!(fare.total, inr(1000));
+    }
+
+    #[test]
+    fn quote_caps_the_fare_but_not_waiting() {
+        let mut fare = card().fare(10.0, 20.0, &[5.0], 1.0, &[]);
+        fare.cap_at_quote(&inr(20000));
+
+        assert_eq!(fare.quote_adjustment, Some(inr(-6000)));
+        assert_eq!(fare.total, inr(20400));
+
+        // cheaper than quoted: nothing to take off
+        let mut fare = card().fare(10.0, 20.0, &[], 1.0, &[]);
+        fare.cap_at_quote(&inr(30000));
+        assert_eq!(fare.quote_adjustment, None);
+        assert_eq!(fare.total, inr(26000));
+
+        // a quote in another currency can't cap anything
+        fare.cap_at_quote(&Money::new(100, Currency::parse("USD").unwrap()));
+        assert_eq!(fare.total, inr(26000));
+    }
+
+    #[test]
+    fn promo_comes_off_the_rider_total_not_the_driver_fare() {
+        let mut fare = card().fare(10.0, 20.0, &[], 1.0, &[]);
+        assert_eq!(fare.driver_fare(), &inr(26000));
+
+        fare.apply_promo("WELCOME", inr(5000));
+        assert_eq!(fare.total, inr(21000));
+        assert_eq!(fare.pre_promo_total, Some(inr(26000)));
+        assert_eq!(fare.driver_fare(), &inr(26000));
+
+        // never below zero
+        let mut fare = card().fare(10.0, 20.0, &[], 1.0, &[]);
+        fare.apply_promo("BIG", inr(1_000_000));
+        assert_eq!(fare.total, inr(0));
+        assert_eq!(fare.promo.unwrap().discount, inr(26000));
+    }
+}
@@ -1,2 +1,8 @@
 // /Users/xsm/Documents/workspace/xtras/daw/src/types/mod.rs
+pub mod fare;
+pub mod money;
+pub mod pricing_rule;
+pub mod promo;
 pub mod request_keys;
+pub mod ride_status;
+pub mod tenant_settings;
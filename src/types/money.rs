@@ -0,0 +1,152 @@
+// src/types/money.rs
+use std::ops::Add;
+
+use sea_orm::DeriveValueType;
+use serde::{Deserialize, Serialize};
+
+/// ISO 4217 currency code, e.g. "INR" (stored as text on `ride.currency`).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, DeriveValueType)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency(String);
+
+impl Currency {
+    /// Three ASCII letters, case-insensitive
+    pub fn parse(code: &str) -> Option<Self> {
+        let code = code.trim();
+        if code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) {
+            Some(Self(code.to_ascii_uppercase()))
+        } else {
+            None
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.0
+    }
+
+    /// Digits after the decimal point (ISO 4217 minor unit)
+    pub fn exponent(&self) -> u32 {
+        match self.0.as_str() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+            | "UGX" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        }
+    }
+
+    /// Major units (e.g. rupees) -> minor units (paise), rounded half away from zero
+    pub fn to_minor(&self, major: f64) -> i64 {
+        (major * 10_f64.powi(self.exponent() as i32)).round() as i64
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self("INR".to_string())
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = String;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Self::parse(&code).ok_or_else(|| format!("invalid ISO 4217 currency code: {code}"))
+    }
+}
+
+impl From<Currency> for String {
+    fn from(c: Currency) -> Self {
+        c.0
+    }
+}
+
+/// An amount of money: integer minor units (paise, cents, ...) of one currency.
+///
+/// Billing never goes through floats except when applying a rate or
+/// multiplier (`scale`), which rounds back to whole minor units once.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Money {
+    pub amount_minor: i64,
+    pub currency: Currency,
+}
+
+impl Money {
+    pub fn new(amount_minor: i64, currency: Currency) -> Self {
+        Self {
+            amount_minor,
+            currency,
+        }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+
+    /// `self * factor`, rounded half away from zero to whole minor units
+    pub fn scale(&self, factor: f64) -> Self {
+        Self::new(
+            (self.amount_minor as f64 * factor).round() as i64,
+            self.currency.clone(),
+        )
+    }
+}
+
+/// Adding different currencies is a bug, not a runtime condition.
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Money) -> Money {
+        assert_eq!(
+            self.currency, rhs.currency,
+            "cannot add {} to {}",
+            rhs.currency.code(),
+            self.currency.code()
+        );
+        Money::new(self.amount_minor + rhs.amount_minor, self.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inr(amount_minor: i64) -> Money {
+        Money::new(amount_minor, Currency::default())
+    }
+
+    #[test]
+    fn currency_codes_parse_case_insensitively() {
+        assert_eq!(Currency::parse(" usd ").unwrap().code(), "USD");
+        assert!(Currency::parse("US").is_none());
+        assert!(Currency::parse("U5D").is_none());
+    }
+
+    #[test]
+    fn minor_units_follow_the_currency_exponent() {
+        let jpy = Currency::parse("JPY").unwrap();
+        let kwd = Currency::parse("KWD").unwrap();
+
+        assert_eq!(Currency::default().to_minor(12.345), 1235);
+        assert_eq!(jpy.to_minor(12.5), 13);
+        assert_eq!(kwd.to_minor(1.2345), 1235);
+    }
+
+    #[test]
+    fn scale_rounds_half_away_from_zero() {
+        assert_eq!(inr(5).scale(0.5).amount_minor, 3);
+        assert_eq!(inr(-5).scale(0.5).amount_minor, -3);
+        assert_eq!(inr(1000).scale(1.2345).amount_minor, 1235);
+        assert_eq!(inr(1000).scale(0.0).amount_minor, 0);
+    }
+
+    #[test]
+    fn same_currency_adds_up() {
+        assert_eq!(inr(150) + inr(-50), inr(100));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot add USD to INR")]
+    fn adding_different_currencies_panics() {
+        let _ = inr(100) + Money::new(100, Currency::parse("USD").unwrap());
+    }
+}
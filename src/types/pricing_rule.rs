@@ -0,0 +1,257 @@
+// src/types/pricing_rule.rs
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+use dispatch::geo::haversine_km;
+
+/// Area a rule's pickup / drop-off must fall in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Geofence {
+    Circle { lat: f64, lon: f64, radius_km: f64 },
+    /// Closed ring of `[lat, lon]` points (first point need not be repeated)
+    Polygon { points: Vec<[f64; 2]> },
+}
+
+impl Geofence {
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        match self {
+            Geofence::Circle {
+                lat: c_lat,
+                lon: c_lon,
+                radius_km,
+            } => haversine_km(*c_lat, *c_lon, lat, lon) <= *radius_km,
+            Geofence::Polygon { points } => {
+                // ray casting; fine at city scale
+                let mut inside = false;
+                let mut j = points.len().wrapping_sub(1);
+                for (i, p) in points.iter().enumerate() {
+                    let q = points[j];
+                    if (p[1] > lon) != (q[1] > lon)
+                        && lat < (q[0] - p[0]) * (lon - p[1]) / (q[1] - p[1]) + p[0]
+                    {
+                        inside = !inside;
+                    }
+                    j = i;
+                }
+                inside
+            }
+        }
+    }
+}
+
+/// Local time-of-day window; `start > end` wraps past midnight (22:00 - 05:00).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, t: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= t && t < self.end
+        } else {
+            t >= self.start || t < self.end
+        }
+    }
+}
+
+/// What a matching rule does to the ride charge.
+/// Amounts are minor units of the tenant currency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Adjustment {
+    /// Add (or, if negative, take off) a fixed amount
+    Flat { amount_minor: i64 },
+    /// Add a percentage of the ride charge so far
+    Percent { percent: f64 },
+    /// Charge exactly this for the ride (zone-to-zone fares)
+    Fixed { amount_minor: i64 },
+}
+
+/// One pricing rule. Every condition that is set must match;
+/// unset conditions match anything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PricingRule {
+    /// Shown as the line item label, e.g. "Night surcharge"
+    pub name: String,
+    #[serde(default)]
+    pub time_window: Option<TimeWindow>,
+    /// Days of the trip's local date; empty = every day
+    #[serde(default)]
+    pub weekdays: Vec<Weekday>,
+    #[serde(default)]
+    pub pickup_zone: Option<Geofence>,
+    #[serde(default)]
+    pub dropoff_zone: Option<Geofence>,
+    /// Empty = every tier
+    #[serde(default)]
+    pub tiers: Vec<String>,
+    pub adjustment: Adjustment,
+}
+
+/// What rules are matched against.
+pub struct TripContext<'a> {
+    pub tier: &'a str,
+    pub pickup: (f64, f64),
+    pub dropoff: (f64, f64),
+    /// Pickup time in the tenant's local time
+    pub local_time: NaiveDateTime,
+}
+
+impl PricingRule {
+    pub fn matches(&self, trip: &TripContext) -> bool {
+        let time = trip.local_time.time();
+        if self.time_window.as_ref().is_some_and(|w| !w.contains(time)) {
+            return false;
+        }
+
+        if !self.weekdays.is_empty() && !self.weekdays.contains(&trip.local_time.weekday()) {
+            return false;
+        }
+
+        let (lat, lon) = trip.pickup;
+        if self.pickup_zone.as_ref().is_some_and(|z| !z.contains(lat, lon)) {
+            return false;
+        }
+
+        let (lat, lon) = trip.dropoff;
+        if self.dropoff_zone.as_ref().is_some_and(|z| !z.contains(lat, lon)) {
+            return false;
+        }
+
+        self.tiers.is_empty() || self.tiers.iter().any(|t| t == trip.tier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// ~1 km of latitude
+    const KM_LAT: f64 = 1.0 / 111.195;
+
+    fn hm(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn rule() -> PricingRule {
+        PricingRule {
+            name: "rule".into(),
+            time_window: None,
+            weekdays: vec![],
+            pickup_zone: None,
+            dropoff_zone: None,
+            tiers: vec![],
+            adjustment: Adjustment::Flat { amount_minor: 1000 },
+        }
+    }
+
+    /// 2025-01-06 was a Monday
+    fn trip(tier: &str, pickup: (f64, f64), h: u32, m: u32) -> TripContext<'_> {
+        TripContext {
+            tier,
+            pickup,
+            dropoff: (12.0, 77.0),
+            local_time: NaiveDate::from_ymd_opt(2025, 1, 6).unwrap().and_time(hm(h, m)),
+        }
+    }
+
+    #[test]
+    fn circle_contains_points_within_its_radius() {
+        let zone = Geofence::Circle {
+            lat: 12.0,
+            lon: 77.0,
+            radius_km: 2.0,
+        };
+
+        assert!(zone.contains(12.0, 77.0));
+        assert!(zone.contains(12.0 + 1.9 * KM_LAT, 77.0));
+        assert!(!zone.contains(12.0 + 2.1 * KM_LAT, 77.0));
+    }
+
+    #[test]
+    fn polygon_contains_points_inside_its_ring() {
+        let square = Geofence::Polygon {
+            points: vec![[12.0, 77.0], [12.0, 77.1], [12.1, 77.1], [12.1, 77.0]],
+        };
+
+        assert!(square.contains(12.05, 77.05));
+        assert!(!square.contains(12.15, 77.05));
+        assert!(!square.contains(12.05, 76.95));
+    }
+
+    #[test]
+    fn daytime_window_is_start_inclusive_end_exclusive() {
+        let day = TimeWindow {
+            start: hm(9, 0),
+            end: hm(17, 0),
+        };
+
+        assert!(day.contains(hm(9, 0)));
+        assert!(day.contains(hm(16, 59)));
+        assert!(!day.contains(hm(17, 0)));
+        assert!(!day.contains(hm(3, 0)));
+    }
+
+    #[test]
+    fn overnight_window_wraps_past_midnight() {
+        let night = TimeWindow {
+            start: hm(22, 0),
+            end: hm(5, 0),
+        };
+
+        assert!(night.contains(hm(22, 0)));
+        assert!(night.contains(hm(23, 59)));
+        assert!(night.contains(hm(0, 0)));
+        assert!(night.contains(hm(4, 59)));
+        assert!(!night.contains(hm(5, 0)));
+        assert!(!night.contains(hm(12, 0)));
+    }
+
+    #[test]
+    fn rule_without_conditions_matches_everything() {
+        assert!(rule().matches(&trip("economy", (12.0, 77.0), 12, 0)));
+    }
+
+    #[test]
+    fn every_set_condition_must_match() {
+        let night_airport = PricingRule {
+            time_window: Some(TimeWindow {
+                start: hm(22, 0),
+                end: hm(5, 0),
+            }),
+            pickup_zone: Some(Geofence::Circle {
+                lat: 13.0,
+                lon: 77.7,
+                radius_km: 3.0,
+            }),
+            tiers: vec!["economy".into(), "xl".into()],
+            ..rule()
+        };
+        let airport = (13.0, 77.7);
+
+        assert!(night_airport.matches(&trip("xl", airport, 23, 30)));
+        // wrong tier, wrong time, wrong place
+        assert!(!night_airport.matches(&trip("premium", airport, 23, 30)));
+        assert!(!night_airport.matches(&trip("xl", airport, 12, 0)));
+        assert!(!night_airport.matches(&trip("xl", (12.0, 77.0), 23, 30)));
+    }
+
+    #[test]
+    fn weekdays_use_the_local_date() {
+        let weekend = PricingRule {
+            weekdays: vec![Weekday::Sat, Weekday::Sun],
+            ..rule()
+        };
+        let weekday = PricingRule {
+            weekdays: vec![Weekday::Mon],
+            ..rule()
+        };
+
+        assert!(!weekend.matches(&trip("economy", (12.0, 77.0), 12, 0)));
+        assert!(weekday.matches(&trip("economy", (12.0, 77.0), 12, 0)));
+    }
+}
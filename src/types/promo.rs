@@ -0,0 +1,48 @@
+// src/types/promo.rs
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::types::money::Money;
+
+/// How a promo code takes money off (stored as text in `promo_code.kind`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+#[serde(rename_all = "snake_case")]
+pub enum PromoKind {
+    /// `percent` of the fare, up to `max_discount_minor`
+    #[sea_orm(string_value = "percent")]
+    Percent,
+    /// `amount_minor` off
+    #[sea_orm(string_value = "flat")]
+    Flat,
+}
+
+/// Where a code's use on a ride stands (`promo_redemption.status`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+#[serde(rename_all = "snake_case")]
+pub enum RedemptionStatus {
+    /// Ride requested; counts toward the code's limits
+    #[sea_orm(string_value = "reserved")]
+    Reserved,
+    /// Ride completed with the discount applied
+    #[sea_orm(string_value = "consumed")]
+    Consumed,
+    /// Ride ended without completing; the use is given back
+    #[sea_orm(string_value = "released")]
+    Released,
+}
+
+impl RedemptionStatus {
+    /// Uses that count against `max_uses` / `max_uses_per_user`
+    pub const HELD: &'static [RedemptionStatus] =
+        &[RedemptionStatus::Reserved, RedemptionStatus::Consumed];
+}
+
+/// Promo code discount on a fare.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromoLine {
+    pub code: String,
+    /// Taken off the total
+    pub discount: Money,
+}
@@ -0,0 +1,199 @@
+// src/types/ride_status.rs
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Lifecycle state of a ride (stored as text in `ride.status`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum RideStatus {
+    #[sea_orm(string_value = "scheduled")]
+    Scheduled,
+    #[sea_orm(string_value = "requested")]
+    Requested,
+    #[sea_orm(string_value = "assigned")]
+    Assigned,
+    #[sea_orm(string_value = "accepted")]
+    Accepted,
+    #[sea_orm(string_value = "driver_arrived")]
+    DriverArrived,
+    #[sea_orm(string_value = "in_progress")]
+    InProgress,
+    #[sea_orm(string_value = "completed")]
+    Completed,
+    #[sea_orm(string_value = "cancelled")]
+    Cancelled,
+    #[sea_orm(string_value = "rider_no_show")]
+    RiderNoShow,
+    #[sea_orm(string_value = "no_driver_found")]
+    NoDriverFound,
+}
+
+/// Who is moving the ride to its next state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RideActor {
+    Rider,
+    Driver,
+    System,
+}
+
+/// Central transition table: (from, to, actor allowed to make the move).
+/// New states only need a variant above and their rows here.
+const TRANSITIONS: &[(RideStatus, RideStatus, RideActor)] = &[
+    // advance booking reaches its dispatch lead time
+    (RideStatus::Scheduled, RideStatus::Requested, RideActor::System),
+    // dispatch picks a driver
+    (RideStatus::Requested, RideStatus::Assigned, RideActor::System),
+    // dispatch gave up after too many attempts
+    (RideStatus::Requested, RideStatus::NoDriverFound, RideActor::System),
+    // offer timed out before the driver answered
+    (RideStatus::Assigned, RideStatus::Requested, RideActor::System),
+    // driver answers the offer
+    (RideStatus::Assigned, RideStatus::Accepted, RideActor::Driver),
+    (RideStatus::Assigned, RideStatus::Requested, RideActor::Driver),
+    // driver drops an accepted ride; it goes back to dispatch
+    (RideStatus::Accepted, RideStatus::Requested, RideActor::Driver),
+    (RideStatus::DriverArrived, RideStatus::Requested, RideActor::Driver),
+    // driver reaches pickup; waiting clock starts
+    (RideStatus::Accepted, RideStatus::DriverArrived, RideActor::Driver),
+    // rider never came out
+    (RideStatus::DriverArrived, RideStatus::RiderNoShow, RideActor::Driver),
+    // trip itself
+    (RideStatus::Accepted, RideStatus::InProgress, RideActor::Driver),
+    (RideStatus::DriverArrived, RideStatus::InProgress, RideActor::Driver),
+    (RideStatus::InProgress, RideStatus::Completed, RideActor::Driver),
+    // rider walks away before the trip starts
+    (RideStatus::Scheduled, RideStatus::Cancelled, RideActor::Rider),
+    (RideStatus::Requested, RideStatus::Cancelled, RideActor::Rider),
+    (RideStatus::Assigned, RideStatus::Cancelled, RideActor::Rider),
+    (RideStatus::Accepted, RideStatus::Cancelled, RideActor::Rider),
+    (RideStatus::DriverArrived, RideStatus::Cancelled, RideActor::Rider),
+];
+
+impl RideStatus {
+    /// Not finished one way or another yet
+    pub const OPEN: [RideStatus; 6] = [
+        RideStatus::Scheduled,
+        RideStatus::Requested,
+        RideStatus::Assigned,
+        RideStatus::Accepted,
+        RideStatus::DriverArrived,
+        RideStatus::InProgress,
+    ];
+
+    /// States in which the ride's driver can't take another offer
+    pub const DRIVER_BUSY: [RideStatus; 4] = [
+        RideStatus::Assigned,
+        RideStatus::Accepted,
+        RideStatus::DriverArrived,
+        RideStatus::InProgress,
+    ];
+
+    /// States in which the driver has taken the ride on (past the offer)
+    pub const DRIVER_ENGAGED: [RideStatus; 3] = [
+        RideStatus::Accepted,
+        RideStatus::DriverArrived,
+        RideStatus::InProgress,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RideStatus::Scheduled => "scheduled",
+            RideStatus::Requested => "requested",
+            RideStatus::Assigned => "assigned",
+            RideStatus::Accepted => "accepted",
+            RideStatus::DriverArrived => "driver_arrived",
+            RideStatus::InProgress => "in_progress",
+            RideStatus::Completed => "completed",
+            RideStatus::Cancelled => "cancelled",
+            RideStatus::RiderNoShow => "rider_no_show",
+            RideStatus::NoDriverFound => "no_driver_found",
+        }
+    }
+
+    /// True if `actor` may move a ride from `self` to `to`.
+    pub fn can_transition(self, to: RideStatus, actor: RideActor) -> bool {
+        TRANSITIONS
+            .iter()
+            .any(|&(f, t, a)| f == self && t == to && a == actor)
+    }
+}
+
+impl std::fmt::Display for RideStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::Iterable;
+    use RideActor::*;
+    use RideStatus::*;
+
+    #[test]
+    fn allowed_moves() {
+        let allowed = [
+            (Scheduled, Requested, System),
+            (Requested, Assigned, System),
+            (Requested, NoDriverFound, System),
+            (Assigned, Requested, System),
+            (Assigned, Accepted, Driver),
+            (Assigned, Requested, Driver),
+            (Accepted, DriverArrived, Driver),
+            (DriverArrived, InProgress, Driver),
+            (DriverArrived, RiderNoShow, Driver),
+            (InProgress, Completed, Driver),
+            (Scheduled, Cancelled, Rider),
+            (DriverArrived, Cancelled, Rider),
+        ];
+        for (from, to, actor) in allowed {
+            assert!(from.can_transition(to, actor), "{from} -> {to} by {actor:?}");
+        }
+    }
+
+    #[test]
+    fn forbidden_moves() {
+        let forbidden = [
+            // right move, wrong actor
+            (Assigned, Accepted, Rider),
+            (Assigned, Accepted, System),
+            (InProgress, Completed, Rider),
+            (InProgress, Completed, System),
+            (Requested, Assigned, Driver),
+            (Requested, Cancelled, Driver),
+            // riders can't walk away once the trip started
+            (InProgress, Cancelled, Rider),
+            // no skipping ahead
+            (Requested, Accepted, Driver),
+            (Accepted, Completed, Driver),
+            // no-show only once the driver is there
+            (Accepted, RiderNoShow, Driver),
+        ];
+        for (from, to, actor) in forbidden {
+            assert!(!from.can_transition(to, actor), "{from} -> {to} by {actor:?}");
+        }
+    }
+
+    #[test]
+    fn finished_rides_stay_finished() {
+        let finished = [Completed, Cancelled, RiderNoShow, NoDriverFound];
+        for from in finished {
+            for to in RideStatus::iter() {
+                for actor in [Rider, Driver, System] {
+                    assert!(!from.can_transition(to, actor), "{from} -> {to} by {actor:?}");
+                }
+            }
+            assert!(!RideStatus::OPEN.contains(&from));
+        }
+    }
+
+    #[test]
+    fn status_strings_match_the_stored_values() {
+        for status in RideStatus::iter() {
+            assert_eq!(status.to_string(), status.to_value());
+        }
+    }
+}
@@ -0,0 +1,402 @@
+// src/types/tenant_settings.rs
+use serde::{Deserialize, Serialize};
+
+use crate::entity::tenant::Model as TenantModel;
+use crate::error::AppError;
+use crate::types::money::{Currency, Money};
+use crate::types::pricing_rule::{PricingRule, TripContext};
+
+// Dispatch settings live with the dispatch logic so the simulator shares them
+pub use dispatch::settings::{DispatchMode, DispatchSettings};
+pub use dispatch::pool::PoolSettings;
+
+/// Typed view over `tenant.settings` (JSONB).
+/// Missing keys fall back to the defaults below; values are checked by
+/// `validate_tenant_settings` before they are stored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TenantSettings {
+    /// Default currency for fares and fees (ISO 4217)
+    pub currency: Currency,
+    pub cancellation: CancellationPolicy,
+    pub dispatch: DispatchSettings,
+    pub pricing: PricingSettings,
+    pub scheduling: SchedulingSettings,
+    pub trip_changes: TripChangeSettings,
+    pub arrival: ArrivalSettings,
+    pub pickup_pin: PickupPinSettings,
+    pub pool: PoolSettings,
+    pub ratings: RatingSettings,
+    pub tips: TipSettings,
+}
+
+/// Riders tipping drivers after a trip. Amounts are minor units of the ride currency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TipSettings {
+    pub enabled: bool,
+    /// Tips are accepted for this long after completion
+    pub window_hours: i64,
+    /// Largest tip allowed
+    pub max_amount_minor: i64,
+    /// Tips may also not exceed this share of the fare; None = no limit
+    pub max_percent_of_fare: Option<f64>,
+}
+
+impl Default for TipSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_hours: 24,
+            max_amount_minor: 50000,
+            max_percent_of_fare: None,
+        }
+    }
+}
+
+impl TipSettings {
+    /// Largest tip allowed on a ride whose fare was `fare_minor`
+    pub fn max_tip_minor(&self, fare_minor: i64) -> i64 {
+        match self.max_percent_of_fare {
+            Some(pct) => self
+                .max_amount_minor
+                .min((fare_minor as f64 * pct / 100.0).floor() as i64),
+            None => self.max_amount_minor,
+        }
+    }
+}
+
+/// Riders and drivers rating each other after a trip.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RatingSettings {
+    /// Ratings are accepted for this long after completion
+    pub window_hours: i64,
+    /// Accounts averaging below this get flagged for review
+    pub flag_below: f64,
+    /// ...once they have at least this many ratings
+    pub min_ratings_to_flag: i32,
+}
+
+impl Default for RatingSettings {
+    fn default() -> Self {
+        Self {
+            window_hours: 72,
+            flag_below: 4.0,
+            min_ratings_to_flag: 10,
+        }
+    }
+}
+
+impl RatingSettings {
+    /// Whether an account with these totals should be flagged
+    pub fn should_flag(&self, sum: i64, count: i32) -> bool {
+        count > 0
+            && count >= self.min_ratings_to_flag
+            && (sum as f64 / count as f64) < self.flag_below
+    }
+}
+
+/// PIN the driver must get from the rider to start the trip.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PickupPinSettings {
+    pub enabled: bool,
+    /// Tiers that need a PIN; empty = every tier
+    pub tiers: Vec<String>,
+    /// Wrong PINs allowed per `lockout_secs` before /start is refused
+    pub max_attempts: u32,
+    pub lockout_secs: i64,
+}
+
+impl Default for PickupPinSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tiers: Vec::new(),
+            max_attempts: 5,
+            lockout_secs: 300,
+        }
+    }
+}
+
+impl PickupPinSettings {
+    pub fn required_for(&self, tier: &str) -> bool {
+        self.enabled && (self.tiers.is_empty() || self.tiers.iter().any(|t| t == tier))
+    }
+}
+
+/// Driver arrival at pickup and rider no-shows.
+/// The free waiting window and per-minute charge come from the rate card.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArrivalSettings {
+    /// Driver's last position must be this close to pickup to mark arrival (meters)
+    pub geofence_m: f64,
+    /// Driver may report a no-show once they have waited this long
+    pub no_show_after_mins: i64,
+    /// Charged to the rider and paid to the driver (minor units of the ride currency)
+    pub no_show_fee_minor: i64,
+}
+
+impl Default for ArrivalSettings {
+    fn default() -> Self {
+        Self {
+            geofence_m: 150.0,
+            no_show_after_mins: 5,
+            no_show_fee_minor: 5000,
+        }
+    }
+}
+
+/// Changes the rider makes to a ride already under way.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TripChangeSettings {
+    /// A new destination further than this from the old one (straight
+    /// line, km) needs the driver to accept it
+    pub max_destination_shift_km: f64,
+}
+
+impl Default for TripChangeSettings {
+    fn default() -> Self {
+        Self {
+            max_destination_shift_km: 5.0,
+        }
+    }
+}
+
+/// Advance-booked rides.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchedulingSettings {
+    /// Furthest ahead a ride can be booked
+    pub max_days_ahead: i64,
+    /// Closest pickup time that still counts as a booking (minutes from now)
+    pub min_lead_mins: i64,
+    /// Dispatch starts this long before pickup
+    pub dispatch_lead_mins: i64,
+    /// Rider reminders, minutes before pickup
+    pub reminder_mins_before: Vec<i64>,
+    /// Cap on scheduled rides picking up within `zone_radius_km` of each
+    /// other and within `overlap_window_mins`; None = no cap
+    pub max_overlapping_per_zone: Option<u64>,
+    pub zone_radius_km: f64,
+    pub overlap_window_mins: i64,
+}
+
+impl Default for SchedulingSettings {
+    fn default() -> Self {
+        Self {
+            max_days_ahead: 7,
+            min_lead_mins: 30,
+            dispatch_lead_mins: 15,
+            reminder_mins_before: vec![60],
+            max_overlapping_per_zone: None,
+            zone_radius_km: 2.0,
+            overlap_window_mins: 30,
+        }
+    }
+}
+
+/// Fare estimates and quotes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PricingSettings {
+    /// How long a quote from /rides/estimate locks its price
+    pub quote_ttl_secs: u64,
+    /// Used to turn distance into trip / pickup ETAs
+    pub avg_speed_kmh: f64,
+    /// Tenant local time = UTC + this; rule time windows / weekdays use it
+    pub utc_offset_minutes: i32,
+    /// Surcharges / zone fares on top of the rate card, applied in order
+    pub rules: Vec<PricingRule>,
+    pub trip_meter: TripMeterSettings,
+}
+
+/// Filtering for the GPS trail that meters the final fare.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TripMeterSettings {
+    /// Fixes less accurate than this are ignored (meters)
+    pub max_accuracy_m: f64,
+    /// Faster than this between two fixes = GPS jump
+    pub max_speed_kmh: f64,
+    /// Moves shorter than this are jitter (meters)
+    pub min_move_m: f64,
+    /// Fewer usable fixes than this -> bill the estimate
+    pub min_points: usize,
+    /// Trail must span this share of the trip time, else bill the estimate
+    pub min_coverage: f64,
+}
+
+impl Default for TripMeterSettings {
+    fn default() -> Self {
+        Self {
+            max_accuracy_m: 50.0,
+            max_speed_kmh: 160.0,
+            min_move_m: 15.0,
+            min_points: 5,
+            min_coverage: 0.6,
+        }
+    }
+}
+
+impl Default for PricingSettings {
+    fn default() -> Self {
+        Self {
+            quote_ttl_secs: 300,
+            avg_speed_kmh: 25.0,
+            utc_offset_minutes: 0,
+            rules: Vec::new(),
+            trip_meter: TripMeterSettings::default(),
+        }
+    }
+}
+
+impl PricingSettings {
+    pub fn eta_mins(&self, distance_km: f64) -> f64 {
+        distance_km / self.avg_speed_kmh.max(1.0) * 60.0
+    }
+
+    /// Rules that apply to a trip picked up at `at`
+    pub fn matching_rules(
+        &self,
+        tier: &str,
+        pickup: (f64, f64),
+        dropoff: (f64, f64),
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Vec<&PricingRule> {
+        let local_time = at.naive_utc() + chrono::Duration::minutes(self.utc_offset_minutes as i64);
+        let trip = TripContext {
+            tier,
+            pickup,
+            dropoff,
+            local_time,
+        };
+
+        self.rules.iter().filter(|r| r.matches(&trip)).collect()
+    }
+}
+
+/// When a rider cancel costs money.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CancellationPolicy {
+    /// Free cancellation window after the driver accepted (seconds)
+    pub grace_period_secs: i64,
+    /// Flat fee once the grace window is over (minor units of the tenant currency)
+    pub fee_minor: i64,
+    /// Fee is waived while the driver is still farther than this from pickup (km)
+    pub waive_if_driver_farther_than_km: f64,
+}
+
+impl Default for CancellationPolicy {
+    fn default() -> Self {
+        Self {
+            grace_period_secs: 120,
+            fee_minor: 5000,
+            waive_if_driver_farther_than_km: 2.0,
+        }
+    }
+}
+
+impl TenantSettings {
+    /// Unset settings are all defaults; settings that no longer parse are an
+    /// error, never a silent reset (currency included)
+    pub fn from_tenant(tenant: &TenantModel) -> Result<Self, AppError> {
+        if tenant.settings.is_null() {
+            return Ok(Self::default());
+        }
+        serde_json::from_value(tenant.settings.clone()).map_err(|e| {
+            AppError::Internal(format!("Tenant {} has unreadable settings: {e}", tenant.id))
+        })
+    }
+
+    /// Every tier the settings refer to by name. The built-in pool tier only
+    /// counts once the tenant renames it; until then pooling is opt-in by
+    /// adding a rate card for it.
+    pub fn referenced_tiers(&self) -> Vec<&str> {
+        let mut tiers: Vec<&str> = Vec::new();
+        if self.pool.tier != PoolSettings::default().tier {
+            tiers.push(self.pool.tier.as_str());
+        }
+        tiers.extend(self.pickup_pin.tiers.iter().map(String::as_str));
+        for rule in &self.pricing.rules {
+            tiers.extend(rule.tiers.iter().map(String::as_str));
+        }
+        tiers
+    }
+}
+
+impl CancellationPolicy {
+    /// Fee for a rider cancel.
+    ///
+    /// `accepted_secs_ago` is `None` while no driver has accepted yet;
+    /// `driver_distance_km` is `None` if we don't know where the driver is.
+    pub fn rider_fee(
+        &self,
+        accepted_secs_ago: Option<i64>,
+        driver_distance_km: Option<f64>,
+        currency: Currency,
+    ) -> Money {
+        let Some(elapsed) = accepted_secs_ago else {
+            return Money::zero(currency);
+        };
+
+        if elapsed <= self.grace_period_secs {
+            return Money::zero(currency);
+        }
+
+        match driver_distance_km {
+            Some(d) if d > self.waive_if_driver_farther_than_km => Money::zero(currency),
+            _ => Money::new(self.fee_minor, currency),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flagging_waits_for_enough_ratings() {
+        let ratings = RatingSettings::default();
+
+        // averaging 3 over 9 and then 10 ratings
+        assert!(!ratings.should_flag(27, 9));
+        assert!(ratings.should_flag(30, 10));
+        assert!(!ratings.should_flag(0, 0));
+    }
+
+    #[test]
+    fn average_exactly_at_the_threshold_is_not_flagged() {
+        let ratings = RatingSettings::default();
+
+        assert!(!ratings.should_flag(40, 10));
+        assert!(ratings.should_flag(39, 10));
+    }
+
+    #[test]
+    fn tip_cap_without_a_percent_is_the_flat_limit() {
+        let tips = TipSettings::default();
+
+        assert_eq!(tips.max_tip_minor(0), 50000);
+        assert_eq!(tips.max_tip_minor(1_000_000), 50000);
+    }
+
+    #[test]
+    fn tip_percent_cap_rounds_down() {
+        let tips = TipSettings {
+            max_percent_of_fare: Some(15.0),
+            ..TipSettings::default()
+        };
+
+        // 15% of 999 = 149.85
+        assert_eq!(tips.max_tip_minor(999), 149);
+        assert_eq!(tips.max_tip_minor(1000), 150);
+        assert_eq!(tips.max_tip_minor(0), 0);
+        // the flat limit still wins on big fares
+        assert_eq!(tips.max_tip_minor(1_000_000), 50000);
+    }
+}
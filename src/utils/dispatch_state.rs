@@ -0,0 +1,209 @@
+// src/utils/dispatch_state.rs
+
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::utils::redis_service::get_redis_connection;
+
+const EXCLUDED_KEY_PREFIX: &str = "dispatch:excluded:";
+
+/// Long enough to outlive any dispatch round for a ride
+const DISPATCH_STATE_TTL_SECONDS: i64 = 60 * 60 * 24;
+
+fn excluded_key_for_ride(ride_id: Uuid) -> String {
+    format!("{EXCLUDED_KEY_PREFIX}{ride_id}")
+}
+
+/// Never offer this ride to `driver_user_id` again
+pub async fn exclude_driver(ride_id: Uuid, driver_user_id: Uuid) -> anyhow::Result<()> {
+    let mut conn = get_redis_connection().await?;
+    let key = excluded_key_for_ride(ride_id);
+
+    // SADD key member ; EXPIRE key ttl
+    let _: () = redis::pipe()
+        .cmd("SADD")
+        .arg(&key)
+        .arg(driver_user_id.to_string())
+        .ignore()
+        .cmd("EXPIRE")
+        .arg(&key)
+        .arg(DISPATCH_STATE_TTL_SECONDS)
+        .ignore()
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Drivers that declined / dropped this ride already
+pub async fn excluded_drivers(ride_id: Uuid) -> anyhow::Result<HashSet<Uuid>> {
+    let mut conn = get_redis_connection().await?;
+    let key = excluded_key_for_ride(ride_id);
+
+    let raw: Vec<String> = redis::cmd("SMEMBERS")
+        .arg(&key)
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(raw
+        .into_iter()
+        .filter_map(|s| Uuid::parse_str(&s).ok())
+        .collect())
+}
+
+const OFFERS_KEY_PREFIX: &str = "dispatch:offers:";
+
+fn offers_key_for_ride(ride_id: Uuid) -> String {
+    format!("{OFFERS_KEY_PREFIX}{ride_id}")
+}
+
+/// Remember which drivers currently hold a (broadcast) offer for this ride
+pub async fn record_offers(ride_id: Uuid, driver_user_ids: &[Uuid]) -> anyhow::Result<()> {
+    let mut conn = get_redis_connection().await?;
+    let key = offers_key_for_ride(ride_id);
+    let members: Vec<String> = driver_user_ids.iter().map(|id| id.to_string()).collect();
+
+    // DEL key ; SADD key m1 m2 ... ; EXPIRE key ttl
+    let _: () = redis::pipe()
+        .atomic()
+        .cmd("DEL")
+        .arg(&key)
+        .ignore()
+        .cmd("SADD")
+        .arg(&key)
+        .arg(members)
+        .ignore()
+        .cmd("EXPIRE")
+        .arg(&key)
+        .arg(DISPATCH_STATE_TTL_SECONDS)
+        .ignore()
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Drivers currently holding an offer for this ride
+pub async fn offered_drivers(ride_id: Uuid) -> anyhow::Result<HashSet<Uuid>> {
+    let mut conn = get_redis_connection().await?;
+    let key = offers_key_for_ride(ride_id);
+
+    let raw: Vec<String> = redis::cmd("SMEMBERS")
+        .arg(&key)
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(raw
+        .into_iter()
+        .filter_map(|s| Uuid::parse_str(&s).ok())
+        .collect())
+}
+
+/// Drop one driver's offer; returns how many offers are still open
+pub async fn remove_offer(ride_id: Uuid, driver_user_id: Uuid) -> anyhow::Result<usize> {
+    let mut conn = get_redis_connection().await?;
+    let key = offers_key_for_ride(ride_id);
+
+    // SREM key member ; SCARD key
+    let (remaining,): (usize,) = redis::pipe()
+        .atomic()
+        .cmd("SREM")
+        .arg(&key)
+        .arg(driver_user_id.to_string())
+        .ignore()
+        .cmd("SCARD")
+        .arg(&key)
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(remaining)
+}
+
+/// Close all open offers for this ride; returns the drivers that held one
+pub async fn clear_offers(ride_id: Uuid) -> anyhow::Result<HashSet<Uuid>> {
+    let mut conn = get_redis_connection().await?;
+    let key = offers_key_for_ride(ride_id);
+
+    // SMEMBERS key ; DEL key — in one MULTI so no offer lands in between
+    let (raw,): (Vec<String>,) = redis::pipe()
+        .atomic()
+        .cmd("SMEMBERS")
+        .arg(&key)
+        .cmd("DEL")
+        .arg(&key)
+        .ignore()
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(raw
+        .into_iter()
+        .filter_map(|s| Uuid::parse_str(&s).ok())
+        .collect())
+}
+
+const BATCH_KEY_PREFIX: &str = "dispatch:batch:";
+const BATCH_SCHEDULED_KEY_PREFIX: &str = "dispatch:batch_scheduled:";
+
+fn batch_key_for_tenant(tenant_id: Uuid) -> String {
+    format!("{BATCH_KEY_PREFIX}{tenant_id}")
+}
+
+fn batch_scheduled_key_for_tenant(tenant_id: Uuid) -> String {
+    format!("{BATCH_SCHEDULED_KEY_PREFIX}{tenant_id}")
+}
+
+/// Park a ride in its tenant's open batch. Returns true if this ride opened
+/// a new window (the caller then schedules the batch job).
+pub async fn add_to_batch(tenant_id: Uuid, ride_id: Uuid, window_secs: u64) -> anyhow::Result<bool> {
+    let mut conn = get_redis_connection().await?;
+    let key = batch_key_for_tenant(tenant_id);
+
+    // SADD key ride ; EXPIRE key ttl ; SET scheduled 1 NX EX window*2
+    let (opened,): (Option<String>,) = redis::pipe()
+        .atomic()
+        .cmd("SADD")
+        .arg(&key)
+        .arg(ride_id.to_string())
+        .ignore()
+        .cmd("EXPIRE")
+        .arg(&key)
+        .arg(DISPATCH_STATE_TTL_SECONDS)
+        .ignore()
+        .cmd("SET")
+        .arg(batch_scheduled_key_for_tenant(tenant_id))
+        .arg(1)
+        .arg("NX")
+        .arg("EX")
+        // guard only; lapses if the batch job never runs
+        .arg(window_secs * 2)
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(opened.is_some())
+}
+
+/// Close the tenant's open batch and return its rides
+pub async fn take_batch(tenant_id: Uuid) -> anyhow::Result<Vec<Uuid>> {
+    let mut conn = get_redis_connection().await?;
+    let key = batch_key_for_tenant(tenant_id);
+
+    // SMEMBERS key ; DEL key ; DEL scheduled
+    let (raw,): (Vec<String>,) = redis::pipe()
+        .atomic()
+        .cmd("SMEMBERS")
+        .arg(&key)
+        .cmd("DEL")
+        .arg(&key)
+        .ignore()
+        .cmd("DEL")
+        .arg(batch_scheduled_key_for_tenant(tenant_id))
+        .ignore()
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(raw
+        .into_iter()
+        .filter_map(|s| Uuid::parse_str(&s).ok())
+        .collect())
+}
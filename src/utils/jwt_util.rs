@@ -5,6 +5,8 @@ use std::env;
 use actix_web::Error;
 use uuid::Uuid;
 
+use crate::types::money::Money;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JWTAuthClaims {
     pub sub: Uuid,   // user id
@@ -48,3 +50,47 @@ pub fn decode_jwt_token(token: &str) -> Result<JWTAuthClaims, Error> {
 
     Ok(data.claims)
 }
+
+/// What a ride quote id (from /rides/estimate) vouches for
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RideQuoteClaims {
+    pub sub: Uuid, // rider user id
+    pub tenant_id: Uuid,
+    pub tier: String,
+    pub pickup_lat: f64,
+    pub pickup_lon: f64,
+    pub dest_lat: f64,
+    pub dest_lon: f64,
+    /// Intermediate stops, in visiting order
+    pub stops: Vec<(f64, f64)>,
+    pub surge_multiplier: f64,
+    /// Before any promo code; the ride is never charged more than this
+    pub fare_total: Money,
+    /// One ride per quote
+    pub jti: Uuid,
+    pub exp: usize,
+}
+
+// separate key so a quote id can never pass as a login token
+fn quote_secret() -> String {
+    format!("{}:ride-quote", jwt_secret())
+}
+
+pub fn generate_quote_token(claims: &RideQuoteClaims) -> Result<String, Error> {
+    encode(
+        &Header::default(),
+        claims,
+        &EncodingKey::from_secret(quote_secret().as_bytes()),
+    )
+    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))
+}
+
+pub fn decode_quote_token(token: &str) -> Option<RideQuoteClaims> {
+    decode::<RideQuoteClaims>(
+        token,
+        &DecodingKey::from_secret(quote_secret().as_bytes()),
+        &Validation::default(),
+    )
+    .ok()
+    .map(|data| data.claims)
+}
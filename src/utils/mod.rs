@@ -5,4 +5,9 @@ pub mod redis_geo;
 pub mod surge;
 pub mod current_user;
 pub mod ws_auth;
-pub mod pagination;
\ No newline at end of file
+pub mod pagination;
+pub mod dispatch_state;
+pub mod trip_meter;
+pub mod pickup_pin;
+pub mod ride_quote;
+
@@ -0,0 +1,66 @@
+// src/utils/pickup_pin.rs
+use rand::Rng;
+use uuid::Uuid;
+
+use crate::utils::redis_service::get_redis_connection;
+
+const PIN_FAILS_KEY_PREFIX: &str = "ride:pin_fails:";
+
+fn pin_fails_key_for_ride(ride_id: Uuid) -> String {
+    format!("{PIN_FAILS_KEY_PREFIX}{ride_id}")
+}
+
+/// Random 4-digit code, leading zeros kept ("0427")
+pub fn generate_pickup_pin() -> String {
+    format!("{:04}", rand::rng().random_range(0..10_000))
+}
+
+/// Wrong PINs tried for this ride in the current window, and seconds until
+/// the window resets (0 if there were none)
+pub async fn failed_pin_attempts(ride_id: Uuid) -> anyhow::Result<(u32, i64)> {
+    let mut conn = get_redis_connection().await?;
+    let key = pin_fails_key_for_ride(ride_id);
+
+    // GET key ; TTL key
+    let (count, ttl): (Option<u32>, i64) = redis::pipe()
+        .cmd("GET")
+        .arg(&key)
+        .cmd("TTL")
+        .arg(&key)
+        .query_async(&mut conn)
+        .await?;
+
+    Ok((count.unwrap_or(0), ttl.max(0)))
+}
+
+/// Count one more wrong PIN; the window starts at the first failure
+pub async fn record_failed_pin_attempt(ride_id: Uuid, window_secs: i64) -> anyhow::Result<u32> {
+    let mut conn = get_redis_connection().await?;
+    let key = pin_fails_key_for_ride(ride_id);
+
+    // INCR key ; EXPIRE key ttl NX
+    let (count,): (u32,) = redis::pipe()
+        .atomic()
+        .cmd("INCR")
+        .arg(&key)
+        .cmd("EXPIRE")
+        .arg(&key)
+        .arg(window_secs)
+        .arg("NX")
+        .ignore()
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(count)
+}
+
+/// Forget failures once the right PIN was given
+pub async fn clear_pin_attempts(ride_id: Uuid) -> anyhow::Result<()> {
+    let mut conn = get_redis_connection().await?;
+    let _: () = redis::cmd("DEL")
+        .arg(pin_fails_key_for_ride(ride_id))
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(())
+}
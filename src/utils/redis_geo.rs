@@ -53,19 +53,20 @@ pub async fn remove_driver_location(
     Ok(())
 }
 
-/// Query nearby driver user_ids via Redis GEOSEARCH (radius in KM)
-pub async fn nearby_driver_ids(
+/// Query nearby drivers via Redis GEOSEARCH (radius in KM), nearest first,
+/// as `(user_id, distance_km)`
+pub async fn nearby_drivers(
     tenant_id: Uuid,
     lat: f64,
     lon: f64,
     radius_km: f64,
     max_results: usize,
-) -> anyhow::Result<Vec<Uuid>> {
+) -> anyhow::Result<Vec<(Uuid, f64)>> {
     let mut conn = get_redis_connection().await?;
     let key = geo_key_for_tenant(tenant_id);
 
-    // GEOSEARCH key FROMLONLAT lon lat BYRADIUS radius km ASC COUNT N
-    let raw: Vec<String> = redis::cmd("GEOSEARCH")
+    // GEOSEARCH key FROMLONLAT lon lat BYRADIUS radius km ASC COUNT N WITHDIST
+    let raw: Vec<(String, f64)> = redis::cmd("GEOSEARCH")
         .arg(&key)
         .arg("FROMLONLAT")
         .arg(lon)
@@ -76,13 +77,14 @@ pub async fn nearby_driver_ids(
         .arg("ASC")
         .arg("COUNT")
         .arg(max_results)
+        .arg("WITHDIST")
         .query_async(&mut conn)
         .await?;
 
-    let ids = raw
+    let drivers = raw
         .into_iter()
-        .filter_map(|s| Uuid::parse_str(&s).ok())
+        .filter_map(|(s, dist)| Uuid::parse_str(&s).ok().map(|id| (id, dist)))
         .collect();
 
-    Ok(ids)
+    Ok(drivers)
 }
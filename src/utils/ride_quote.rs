@@ -0,0 +1,28 @@
+// src/utils/ride_quote.rs
+use uuid::Uuid;
+
+use crate::utils::redis_service::get_redis_connection;
+
+const QUOTE_USED_KEY_PREFIX: &str = "ride:quote_used:";
+
+fn quote_used_key(jti: Uuid) -> String {
+    format!("{QUOTE_USED_KEY_PREFIX}{jti}")
+}
+
+/// Mark a quote as used; false if a ride was already booked on it.
+/// The marker only has to outlive the quote itself (`ttl_secs`).
+pub async fn redeem_quote(jti: Uuid, ttl_secs: i64) -> anyhow::Result<bool> {
+    let mut conn = get_redis_connection().await?;
+
+    // SET key 1 NX EX ttl
+    let set: Option<String> = redis::cmd("SET")
+        .arg(quote_used_key(jti))
+        .arg(1)
+        .arg("NX")
+        .arg("EX")
+        .arg(ttl_secs.max(1))
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(set.is_some())
+}
@@ -0,0 +1,294 @@
+// src/utils/trip_meter.rs
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use dispatch::geo::haversine_km;
+
+use crate::entity::ride_breadcrumb::Model as BreadcrumbModel;
+use crate::types::tenant_settings::TripMeterSettings;
+
+/// What the driver's GPS trail says about a trip.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrailStats {
+    pub distance_km: f64,
+    /// First to last usable fix
+    pub duration_mins: f64,
+    pub points_used: usize,
+    /// Inaccurate fixes and jumps
+    pub points_dropped: usize,
+    /// Share of the ride's start -> complete time the trail spans (0..1)
+    pub coverage: f64,
+}
+
+impl TrailStats {
+    /// Good enough to bill from
+    pub fn is_reliable(&self, cfg: &TripMeterSettings) -> bool {
+        self.points_used >= cfg.min_points && self.coverage >= cfg.min_coverage
+    }
+}
+
+/// Whether a fix recorded `at` belongs to a trip started at `started_at` and
+/// completed at `completed_at` (None = still under way). Device clocks are
+/// the driver's: only fixes inside the trip can bill time.
+pub fn within_trip(
+    at: DateTime<FixedOffset>,
+    started_at: Option<DateTime<FixedOffset>>,
+    completed_at: Option<DateTime<FixedOffset>>,
+) -> bool {
+    started_at.is_none_or(|s| at >= s) && completed_at.is_none_or(|c| at <= c)
+}
+
+/// Distance / duration along the breadcrumbs.
+///
+/// - fixes worse than `max_accuracy_m` are dropped
+/// - moves shorter than `min_move_m` from the last counted point are jitter:
+///   they extend the duration but add no distance
+/// - a fix that would need more than `max_speed_kmh` to reach is a jump and
+///   is dropped. Until some fix is plausible next to the first one, the
+///   first one may be the outlier: a jump then restarts the trail instead
+///
+/// `trip_secs` is the ride's start -> complete time, if known.
+pub fn measure_trail(
+    crumbs: &[BreadcrumbModel],
+    trip_secs: Option<i64>,
+    cfg: &TripMeterSettings,
+) -> TrailStats {
+    let mut fixes: Vec<&BreadcrumbModel> = crumbs
+        .iter()
+        .filter(|c| c.accuracy_m.is_none_or(|a| a <= cfg.max_accuracy_m))
+        .collect();
+    fixes.sort_by_key(|c| c.recorded_at);
+
+    let mut points_dropped = crumbs.len() - fixes.len();
+    let mut distance_km = 0.0;
+    let mut points_used = 0;
+
+    // last point distance was counted from / first and last usable fix
+    let mut anchor: Option<&BreadcrumbModel> = None;
+    let mut first_at = None;
+    let mut last_at = None;
+    // the first fix has a plausible neighbour
+    let mut confirmed = false;
+
+    for fix in fixes {
+        let Some(a) = anchor else {
+            anchor = Some(fix);
+            first_at = Some(fix.recorded_at);
+            last_at = Some(fix.recorded_at);
+            points_used = 1;
+            continue;
+        };
+
+        let km = haversine_km(a.lat, a.lon, fix.lat, fix.lon);
+        if km * 1000.0 < cfg.min_move_m {
+            confirmed = true;
+            last_at = Some(fix.recorded_at);
+            points_used += 1;
+            continue;
+        }
+
+        let hours = (fix.recorded_at - a.recorded_at).num_milliseconds() as f64 / 3_600_000.0;
+        if hours <= 0.0 || km / hours > cfg.max_speed_kmh {
+            points_dropped += 1;
+            if !confirmed {
+                // nothing backs the first fix up: start over from this one
+                anchor = Some(fix);
+                first_at = Some(fix.recorded_at);
+                last_at = Some(fix.recorded_at);
+            }
+            continue;
+        }
+
+        confirmed = true;
+        distance_km += km;
+        anchor = Some(fix);
+        last_at = Some(fix.recorded_at);
+        points_used += 1;
+    }
+
+    let span_secs = match (first_at, last_at) {
+        (Some(f), Some(l)) => (l - f).num_seconds().max(0),
+        _ => 0,
+    };
+    let coverage = match trip_secs {
+        Some(t) if t > 0 => (span_secs as f64 / t as f64).min(1.0),
+        _ => 1.0,
+    };
+
+    TrailStats {
+        distance_km,
+        duration_mins: span_secs as f64 / 60.0,
+        points_used,
+        points_dropped,
+        coverage,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    /// ~1 km of latitude
+    const KM_LAT: f64 = 1.0 / 111.195;
+
+    fn crumb(secs: i64, lat: f64, lon: f64, accuracy_m: Option<f64>) -> BreadcrumbModel {
+        let start = chrono::DateTime::parse_from_rfc3339("2025-01-01T10:00:00Z").unwrap();
+        let at = start + chrono::Duration::seconds(secs);
+        BreadcrumbModel {
+            id: Uuid::new_v4(),
+            tenant_id: Uuid::nil(),
+            ride_id: Uuid::nil(),
+            driver_user_id: Uuid::nil(),
+            lat,
+            lon,
+            accuracy_m,
+            recorded_at: at,
+            created_at: at,
+        }
+    }
+
+    /// A fix every minute, 1 km apart heading north (60 km/h)
+    fn straight_trail(points: i64) -> Vec<BreadcrumbModel> {
+        (0..points)
+            .map(|i| crumb(i * 60, 12.0 + i as f64 * KM_LAT, 77.0, Some(10.0)))
+            .collect()
+    }
+
+    #[test]
+    fn clean_trail_is_measured_end_to_end() {
+        let cfg = TripMeterSettings::default();
+        let stats = measure_trail(&straight_trail(6), Some(300), &cfg);
+
+        assert!((stats.distance_km - 5.0).abs() < 0.01);
+        assert_eq!(stats.duration_mins, 5.0);
+        assert_eq!(stats.points_used, 6);
+        assert_eq!(stats.points_dropped, 0);
+        assert!(stats.is_reliable(&cfg));
+    }
+
+    #[test]
+    fn jump_in_the_middle_is_dropped() {
+        let cfg = TripMeterSettings::default();
+        let mut crumbs = straight_trail(6);
+        // 50 km east, 30 s after the previous fix
+        crumbs.push(crumb(150, 12.0, 77.5, Some(10.0)));
+
+        let stats = measure_trail(&crumbs, Some(300), &cfg);
+
+        assert!((stats.distance_km - 5.0).abs() < 0.01);
+        assert_eq!(stats.points_used, 6);
+        assert_eq!(stats.points_dropped, 1);
+    }
+
+    #[test]
+    fn outlier_first_fix_does_not_sink_the_trail() {
+        let cfg = TripMeterSettings::default();
+        let mut crumbs = vec![crumb(-30, 12.0, 77.5, Some(10.0))];
+        crumbs.extend(straight_trail(6));
+
+        let stats = measure_trail(&crumbs, Some(300), &cfg);
+
+        assert!((stats.distance_km - 5.0).abs() < 0.01);
+        assert_eq!(stats.duration_mins, 5.0);
+        assert_eq!(stats.points_used, 6);
+        assert_eq!(stats.points_dropped, 1);
+        assert!(stats.is_reliable(&cfg));
+    }
+
+    #[test]
+    fn jitter_adds_time_but_no_distance() {
+        let cfg = TripMeterSettings::default();
+        // a few meters back and forth while parked
+        let crumbs: Vec<_> = (0..6)
+            .map(|i| crumb(i * 60, 12.0 + (i % 2) as f64 * 0.00005, 77.0, Some(10.0)))
+            .collect();
+
+        let stats = measure_trail(&crumbs, Some(300), &cfg);
+
+        assert_eq!(stats.distance_km, 0.0);
+        assert_eq!(stats.duration_mins, 5.0);
+        assert_eq!(stats.points_used, 6);
+    }
+
+    #[test]
+    fn concurrent_pooled_rides_each_meter_their_own_trip() {
+        let cfg = TripMeterSettings::default();
+        let trail = straight_trail(11);
+        let at = |secs: i64| trail[0].recorded_at + chrono::Duration::seconds(secs);
+
+        // A rides the whole 10 minutes; B gets in at 4 and out at 8
+        let a = (Some(at(0)), at(600));
+        let b = (Some(at(240)), at(480));
+
+        // the driver's trail is recorded on every ride under way
+        let recorded = |(started, _): (Option<DateTime<FixedOffset>>, DateTime<FixedOffset>)| {
+            trail
+                .iter()
+                .filter(|c| within_trip(c.recorded_at, started, None))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let billed = |ride: (Option<DateTime<FixedOffset>>, DateTime<FixedOffset>)| {
+            let crumbs: Vec<_> = recorded(ride)
+                .into_iter()
+                .filter(|c| within_trip(c.recorded_at, ride.0, Some(ride.1)))
+                .collect();
+            let secs = (ride.1 - ride.0.unwrap()).num_seconds();
+            measure_trail(&crumbs, Some(secs), &cfg)
+        };
+
+        let a_stats = billed(a);
+        assert!((a_stats.distance_km - 10.0).abs() < 0.01);
+        assert!(a_stats.is_reliable(&cfg));
+
+        let b_stats = billed(b);
+        assert!((b_stats.distance_km - 4.0).abs() < 0.01);
+        assert_eq!(b_stats.duration_mins, 4.0);
+        assert_eq!(b_stats.points_used, 5);
+        assert!(b_stats.is_reliable(&cfg));
+    }
+
+    #[test]
+    fn fixes_outside_the_trip_are_not_in_it() {
+        let trail = straight_trail(3);
+        let (start, end) = (trail[1].recorded_at, trail[1].recorded_at);
+
+        assert!(!within_trip(trail[0].recorded_at, Some(start), Some(end)));
+        assert!(within_trip(trail[1].recorded_at, Some(start), Some(end)));
+        assert!(!within_trip(trail[2].recorded_at, Some(start), Some(end)));
+        // no start time: nothing to hold the fix against
+        assert!(within_trip(trail[0].recorded_at, None, None));
+    }
+
+    #[test]
+    fn inaccurate_fixes_are_ignored() {
+        let cfg = TripMeterSettings::default();
+        let mut crumbs = straight_trail(6);
+        crumbs.push(crumb(90, 12.0, 77.2, Some(500.0)));
+
+        let stats = measure_trail(&crumbs, Some(300), &cfg);
+
+        assert!((stats.distance_km - 5.0).abs() < 0.01);
+        assert_eq!(stats.points_dropped, 1);
+    }
+
+    #[test]
+    fn thin_or_short_trails_fall_back_to_the_estimate() {
+        let cfg = TripMeterSettings::default();
+
+        // too few fixes
+        let few = measure_trail(&straight_trail(4), Some(180), &cfg);
+        assert!(!few.is_reliable(&cfg));
+
+        // enough fixes, but they cover 5 of the ride's 20 minutes
+        let short = measure_trail(&straight_trail(6), Some(1200), &cfg);
+        assert!(short.coverage < cfg.min_coverage);
+        assert!(!short.is_reliable(&cfg));
+
+        // nothing usable at all
+        let none = measure_trail(&[], Some(300), &cfg);
+        assert_eq!(none.points_used, 0);
+        assert!(!none.is_reliable(&cfg));
+    }
+}